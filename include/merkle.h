@@ -0,0 +1,60 @@
+/* C interface of the merkle crate, built with the `ffi` feature
+ *
+ * trees hold byte strings hashed with sha-256, proofs are exchanged in the
+ * compact binary encoding of MerkleProof::to_bytes. every function returns one
+ * of the MERKLE_* codes, none of them unwinds into the caller.
+ */
+
+#ifndef MERKLE_H
+#define MERKLE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define MERKLE_OK 0
+/* the proof does not verify */
+#define MERKLE_INVALID 1
+#define MERKLE_NULL_POINTER -1
+#define MERKLE_OUT_OF_BOUNDS -2
+/* the proof bytes could not be decoded */
+#define MERKLE_MALFORMED -3
+/* the call panicked, the tree it was given is left as it was before */
+#define MERKLE_PANIC -4
+
+/* length of a root written by merkle_tree_root */
+#define MERKLE_ROOT_LEN 32
+
+typedef struct MerkleTreeHandle MerkleTreeHandle;
+
+/* create an empty tree, NULL if that failed, free it with merkle_tree_free */
+MerkleTreeHandle *merkle_tree_new(void);
+
+/* free a tree, NULL is ignored */
+void merkle_tree_free(MerkleTreeHandle *tree);
+
+/* append a copy of len bytes at data as a leaf */
+int32_t merkle_tree_add_bytes(MerkleTreeHandle *tree, const uint8_t *data, size_t len);
+
+/* write the MERKLE_ROOT_LEN byte root to out */
+int32_t merkle_tree_root(const MerkleTreeHandle *tree, uint8_t *out);
+
+/* encode the proof of the leaf at index into a new buffer written to out and
+ * out_len, free it with merkle_bytes_free */
+int32_t merkle_tree_proof(const MerkleTreeHandle *tree, size_t index, uint8_t **out,
+                          size_t *out_len);
+
+/* free a buffer returned by merkle_tree_proof, NULL is ignored */
+void merkle_bytes_free(uint8_t *data, size_t len);
+
+/* decode and verify a proof, MERKLE_OK if it holds, MERKLE_INVALID if not */
+int32_t merkle_proof_verify(const uint8_t *data, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,30 @@
+//! compiles the verification path of merkle without std
+
+#![no_std]
+
+use merkle::{
+    hash::{FnvHasher, Sha256},
+    verify::verify_path,
+    LeafHash, Root, Step,
+};
+
+use core::hash::BuildHasherDefault;
+
+/// verify a sha-256 path from hashes alone
+pub fn verify_sha256(
+    leaf: &LeafHash<[u8; 32]>,
+    path: &[Step<[u8; 32]>],
+    root: &Root<[u8; 32]>,
+) -> bool {
+    verify_path(&Sha256, leaf, path, root)
+}
+
+/// verify a path of 64 bit digests hashed with the core only FNV hasher
+pub fn verify_fnv(leaf: &LeafHash, path: &[Step], root: &Root) -> bool {
+    verify_path(
+        &BuildHasherDefault::<FnvHasher>::default(),
+        leaf,
+        path,
+        root,
+    )
+}
@@ -0,0 +1,244 @@
+//! audit files with every leaf hash of a tree and its root, so that a third
+//! party can recompute the root without the values
+//!
+//! the layout is
+//! - the magic bytes `MRKA` and the format version as one byte
+//! - the padding strategy as one byte, encoded like in proofs
+//! - the length of a digest as an unsigned LEB128 varint
+//! - the number of leaf hashes as an unsigned LEB128 varint
+//! - the padding leaf and the hash of two padding leafs as siblings, which
+//!   tells trees of different hash backends apart
+//! - the claimed root
+//! - the 64 bit FNV-1a hash of everything before it, big endian
+//! - one record per leaf, the leaf hash followed by the 64 bit FNV-1a hash of
+//!   the index of the leaf as 64 bit big endian integer and the leaf hash
+//!
+//! the checksum of every record lets [`verify_audit`] name the first leaf
+//! whose hash was damaged, the file is read front to back only once.
+
+use core::hash::Hasher;
+use std::io::{self, Read, Write};
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    error::MerkleError,
+    hash::{Digest, FnvHasher, LeafHasher, MaybeSync, MerkleHasher},
+    hashed::HashedMerkleTree,
+    proof::bytes::{padding_byte, write_varint, Reader},
+    root::Root,
+    tree::{MerkleTree, PaddingStrategy},
+    OnceLock,
+};
+
+const MAGIC: &[u8; 4] = b"MRKA";
+const VERSION: u8 = 1;
+
+/// outcome of [`verify_audit`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditReport<D = u64> {
+    /// number of leaf hashes in the file, padding excluded
+    pub len: usize,
+    pub padding: PaddingStrategy,
+    /// root the file claims
+    pub root: Root<D>,
+    /// root of the leaf hashes as they were read
+    pub computed: Root<D>,
+    /// index of the first leaf whose record does not match its checksum
+    pub first_mismatch: Option<usize>,
+}
+
+impl<D: Digest> AuditReport<D> {
+    /// whether every record is intact and the leafs lead to the claimed root
+    pub fn is_valid(&self) -> bool {
+        self.first_mismatch.is_none() && self.root.ct_eq(&self.computed)
+    }
+}
+
+impl<T, S> MerkleTree<T, S>
+where
+    T: Default + MaybeSync,
+    S: LeafHasher<T> + Clone,
+{
+    /// write the leaf hashes and the root as audit file, see [`verify_audit`]
+    pub fn export_audit(&self, w: impl Write) -> io::Result<()> {
+        let empty = self.hasher().hash_padding();
+        let leafs = &self.cached_levels()[0][..self.len()];
+        export(
+            w,
+            self.hasher(),
+            self.padding(),
+            leafs,
+            &empty,
+            &self.root(),
+        )
+    }
+}
+
+impl<S: MerkleHasher> HashedMerkleTree<S> {
+    /// write the leaf hashes and the root as audit file, see [`verify_audit`]
+    pub fn export_audit(&self, w: impl Write) -> io::Result<()> {
+        let leafs = &self.levels()[0][..self.len()];
+        export(
+            w,
+            self.hasher(),
+            self.padding(),
+            leafs,
+            &self.empty(),
+            &self.root(),
+        )
+    }
+}
+
+/// read an audit file of a tree hashed with the default `S` and recompute its
+/// root, see [`verify_audit_with_hasher`]
+pub fn verify_audit<S: MerkleHasher + Default>(r: impl Read) -> io::Result<AuditReport<S::Digest>> {
+    verify_audit_with_hasher(r, S::default())
+}
+
+/// read an audit file and recompute the root from its leaf hashes
+///
+/// a damaged leaf record does not stop the verification, it is named in the
+/// report. a file of another version or hash backend, a damaged header or
+/// trailing bytes are an [`InvalidData`](io::ErrorKind::InvalidData) error
+/// wrapping the [`MerkleError`]
+pub fn verify_audit_with_hasher<S: MerkleHasher>(
+    mut r: impl Read,
+    hasher: S,
+) -> io::Result<AuditReport<S::Digest>> {
+    let mut header = vec![0; MAGIC.len() + 2];
+    r.read_exact(&mut header)?;
+    if &header[..MAGIC.len()] != MAGIC {
+        return Err(invalid_data(MerkleError::InvalidEncoding(
+            "not a merkle audit file",
+        )));
+    }
+    if header[MAGIC.len()] != VERSION {
+        return Err(invalid_data(MerkleError::InvalidEncoding(
+            "unsupported file version",
+        )));
+    }
+    for _ in 0..2 {
+        read_varint_bytes(&mut r, &mut header)?;
+    }
+    let digests = header.len();
+    header.resize(digests + 3 * S::Digest::LEN + 8, 0);
+    r.read_exact(&mut header[digests..])?;
+
+    let (body, checksum) = header.split_at(header.len() - 8);
+    if fnv(body).to_be_bytes() != checksum {
+        return Err(invalid_data(MerkleError::InvalidEncoding(
+            "checksum mismatch",
+        )));
+    }
+    let mut reader = Reader {
+        bytes: &body[MAGIC.len() + 1..],
+    };
+    let padding = reader.padding().map_err(invalid_data)?;
+    if reader.varint().map_err(invalid_data)? != S::Digest::LEN as u64 {
+        return Err(invalid_data(MerkleError::InvalidEncoding(
+            "file was written with another hash backend",
+        )));
+    }
+    let len = usize::try_from(reader.varint().map_err(invalid_data)?)
+        .map_err(|_| invalid_data(MerkleError::InvalidEncoding("tree size is too large")))?;
+    let empty = reader.digest::<S::Digest>().map_err(invalid_data)?;
+    if reader.digest::<S::Digest>().map_err(invalid_data)? != hasher.hash_siblings(&empty, &empty) {
+        return Err(invalid_data(MerkleError::InvalidEncoding(
+            "file was written with another hash backend",
+        )));
+    }
+    let root = Root(reader.digest::<S::Digest>().map_err(invalid_data)?);
+
+    // the length is not trusted with an allocation before the records are read
+    let mut leafs = Vec::new();
+    let mut first_mismatch = None;
+    let mut record = vec![0; S::Digest::LEN + 8];
+    for index in 0..len {
+        r.read_exact(&mut record)?;
+        let (bytes, checksum) = record.split_at(S::Digest::LEN);
+        let leaf = S::Digest::from_slice(bytes)
+            .ok_or_else(|| invalid_data(MerkleError::InvalidEncoding("invalid digest")))?;
+        if record_checksum(index, &leaf).to_be_bytes() != checksum && first_mismatch.is_none() {
+            first_mismatch = Some(index);
+        }
+        leafs.push(leaf);
+    }
+    let trailing = r.read_to_end(&mut Vec::new())?;
+    if trailing > 0 {
+        return Err(invalid_data(MerkleError::TrailingBytes(trailing)));
+    }
+
+    let computed = HashedMerkleTree::new(leafs, empty, hasher, padding, OnceLock::new()).root();
+    Ok(AuditReport {
+        len,
+        padding,
+        root,
+        computed,
+        first_mismatch,
+    })
+}
+
+fn export<S: MerkleHasher>(
+    mut w: impl Write,
+    hasher: &S,
+    padding: PaddingStrategy,
+    leafs: &[S::Digest],
+    empty: &S::Digest,
+    root: &Root<S::Digest>,
+) -> io::Result<()> {
+    let mut header = Vec::with_capacity(32 + 3 * S::Digest::LEN);
+    header.extend_from_slice(MAGIC);
+    header.push(VERSION);
+    header.push(padding_byte(padding));
+    write_varint(&mut header, S::Digest::LEN as u64);
+    write_varint(&mut header, leafs.len() as u64);
+    empty.write_bytes(&mut header);
+    hasher.hash_siblings(empty, empty).write_bytes(&mut header);
+    root.0.write_bytes(&mut header);
+    let checksum = fnv(&header);
+    header.extend_from_slice(&checksum.to_be_bytes());
+    w.write_all(&header)?;
+
+    let mut record = Vec::with_capacity(S::Digest::LEN + 8);
+    for (index, leaf) in leafs.iter().enumerate() {
+        record.clear();
+        leaf.write_bytes(&mut record);
+        record.extend_from_slice(&record_checksum(index, leaf).to_be_bytes());
+        w.write_all(&record)?;
+    }
+    w.flush()
+}
+
+/// read one varint byte by byte and append its bytes to `out`
+fn read_varint_bytes(r: &mut impl Read, out: &mut Vec<u8>) -> io::Result<()> {
+    // ten bytes hold any 64 bit varint, the reader checks the value later
+    for _ in 0..10 {
+        let mut byte = [0];
+        r.read_exact(&mut byte)?;
+        out.push(byte[0]);
+        if byte[0] & 0x80 == 0 {
+            return Ok(());
+        }
+    }
+    Err(invalid_data(MerkleError::InvalidEncoding(
+        "varint does not fit into 64 bits",
+    )))
+}
+
+fn record_checksum<D: Digest>(index: usize, leaf: &D) -> u64 {
+    let mut bytes = Vec::with_capacity(8 + D::LEN);
+    bytes.extend_from_slice(&(index as u64).to_be_bytes());
+    leaf.write_bytes(&mut bytes);
+    fnv(&bytes)
+}
+
+fn fnv(bytes: &[u8]) -> u64 {
+    let mut hasher = FnvHasher::default();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+fn invalid_data(err: MerkleError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
@@ -0,0 +1,265 @@
+//! trees over the fixed size chunks of a file, to content address large files
+//! and prove single chunks of them
+//!
+//! every chunk but the last has the chunk size, the last one holds the rest of
+//! the file. the leaf of a chunk is the leaf hash of its bytes, padding leafs
+//! are the leaf hash of no bytes.
+
+use core::ops::Range;
+use std::io::{self, Read};
+
+use alloc::{vec, vec::Vec};
+
+#[cfg(feature = "async")]
+use crate::stream::AsyncRead;
+use crate::{
+    error::MerkleError,
+    hash::{DefaultBuildHasher, Digest, LeafHasher, MerkleHasher},
+    hashed::HashedMerkleTree,
+    proof::MerkleProof,
+    root::Root,
+    tree::{MerkleTree, PaddingStrategy},
+    OnceLock,
+};
+
+/// chunk size for content addressing when there is no reason for another one
+pub const DEFAULT_CHUNK_SIZE: usize = 1 << 20;
+
+/// a merkle tree over the chunks of a file, only the chunk hashes are kept
+pub struct ChunkedMerkleTree<S: MerkleHasher = DefaultBuildHasher> {
+    tree: HashedMerkleTree<S>,
+    chunk_size: usize,
+    /// bytes read, the offset one past the last chunk
+    size: u64,
+}
+
+impl MerkleTree<Vec<u8>> {
+    /// hash the chunks of `reader` with the default hasher, see
+    /// [`from_reader_with_hasher`]
+    ///
+    /// [`from_reader_with_hasher`]: MerkleTree::from_reader_with_hasher
+    pub fn from_reader(reader: impl Read, chunk_size: usize) -> io::Result<ChunkedMerkleTree> {
+        Self::from_reader_with_hasher(reader, chunk_size, DefaultBuildHasher::default())
+    }
+
+    /// hash the chunks of an asynchronous `reader` with the default hasher, see
+    /// [`from_async_reader_with_hasher`]
+    ///
+    /// [`from_async_reader_with_hasher`]: MerkleTree::from_async_reader_with_hasher
+    #[cfg(feature = "async")]
+    pub async fn from_async_reader(
+        reader: impl AsyncRead,
+        chunk_size: usize,
+    ) -> io::Result<ChunkedMerkleTree> {
+        Self::from_async_reader_with_hasher(reader, chunk_size, DefaultBuildHasher::default()).await
+    }
+}
+
+impl<S: MerkleHasher + LeafHasher<[u8]>> MerkleTree<Vec<u8>, S> {
+    /// read `reader` to its end in chunks of `chunk_size` bytes and hash every
+    /// chunk as a leaf, only one chunk is in memory at a time
+    /// a chunk size of zero is an [`InvalidInput`](io::ErrorKind::InvalidInput)
+    /// error
+    pub fn from_reader_with_hasher(
+        mut reader: impl Read,
+        chunk_size: usize,
+        hasher: S,
+    ) -> io::Result<ChunkedMerkleTree<S>> {
+        check_chunk_size(chunk_size)?;
+        let mut leafs = Vec::new();
+        let mut size = 0;
+        let mut chunk = vec![0; chunk_size];
+        loop {
+            let read = fill(&mut reader, &mut chunk)?;
+            if read == 0 {
+                break;
+            }
+            leafs.push(hasher.hash_leaf(&chunk[..read]));
+            size += read as u64;
+            if read < chunk_size {
+                break;
+            }
+        }
+
+        Ok(ChunkedMerkleTree::new(leafs, hasher, chunk_size, size))
+    }
+
+    /// like [`from_reader_with_hasher`] for a reader whose bytes arrive
+    /// asynchronously
+    ///
+    /// [`from_reader_with_hasher`]: MerkleTree::from_reader_with_hasher
+    #[cfg(feature = "async")]
+    pub async fn from_async_reader_with_hasher(
+        reader: impl AsyncRead,
+        chunk_size: usize,
+        hasher: S,
+    ) -> io::Result<ChunkedMerkleTree<S>> {
+        check_chunk_size(chunk_size)?;
+        let mut reader = core::pin::pin!(reader);
+        let mut leafs = Vec::new();
+        let mut size = 0;
+        let mut chunk = vec![0; chunk_size];
+        loop {
+            let mut read = 0;
+            while read < chunk_size {
+                let buf = &mut chunk[read..];
+                match core::future::poll_fn(|cx| reader.as_mut().poll_read(cx, buf)).await {
+                    Ok(0) => break,
+                    Ok(n) => read += n,
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                    Err(err) => return Err(err),
+                }
+            }
+            if read == 0 {
+                break;
+            }
+            leafs.push(hasher.hash_leaf(&chunk[..read]));
+            size += read as u64;
+            if read < chunk_size {
+                break;
+            }
+        }
+        Ok(ChunkedMerkleTree::new(leafs, hasher, chunk_size, size))
+    }
+}
+
+impl<S: MerkleHasher + LeafHasher<[u8]>> ChunkedMerkleTree<S> {
+    /// a tree over the hashes of the chunks of a file of `size` bytes
+    pub(crate) fn new(leafs: Vec<S::Digest>, hasher: S, chunk_size: usize, size: u64) -> Self {
+        let empty = hasher.hash_padding();
+        let tree = HashedMerkleTree::new(
+            leafs,
+            empty,
+            hasher,
+            PaddingStrategy::default(),
+            OnceLock::new(),
+        );
+        Self {
+            tree,
+            chunk_size,
+            size,
+        }
+    }
+}
+
+impl<S: MerkleHasher> ChunkedMerkleTree<S> {
+    /// change how the tree is completed when its number of chunks is not a
+    /// power of two
+    pub fn with_padding(mut self, padding: PaddingStrategy) -> Self {
+        self.tree = self.tree.with_padding(padding);
+        self
+    }
+
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// bytes of the file
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// number of chunks, padding excluded
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    pub fn root(&self) -> Root<S::Digest> {
+        self.tree.root()
+    }
+
+    /// the tree over the chunk hashes
+    pub fn tree(&self) -> &HashedMerkleTree<S> {
+        &self.tree
+    }
+
+    /// index of the chunk holding the byte at `offset`
+    pub fn chunk_of(&self, offset: u64) -> Option<usize> {
+        (offset < self.size).then(|| (offset / self.chunk_size as u64) as usize)
+    }
+
+    /// byte range of the chunk at `index`, the last one may be shorter
+    pub fn chunk_range(&self, index: usize) -> Option<Range<u64>> {
+        let start = (index as u64).checked_mul(self.chunk_size as u64)?;
+        (start < self.size).then(|| start..self.size.min(start + self.chunk_size as u64))
+    }
+
+    /// proof for the chunk at `index`
+    pub fn get_proof(&self, index: usize) -> Result<MerkleProof<S>, MerkleError>
+    where
+        S: Clone,
+    {
+        if index >= self.len() {
+            return Err(MerkleError::IndexOutOfBounds {
+                index,
+                len: self.len(),
+            });
+        }
+        self.tree.get_proof(index)
+    }
+
+    /// proof for the chunk holding the byte at `offset`
+    pub fn proof_for_offset(&self, offset: u64) -> Result<MerkleProof<S>, MerkleError>
+    where
+        S: Clone,
+    {
+        let index = self
+            .chunk_of(offset)
+            .ok_or(MerkleError::OffsetOutOfBounds {
+                offset,
+                size: self.size,
+            })?;
+        self.tree.get_proof(index)
+    }
+
+    /// check that `chunk` is the leaf of the proof and that the proof leads
+    /// to its own root
+    #[deprecated(note = "use `ChunkedMerkleTree::verify_chunk_against` with a trusted root")]
+    pub fn verify_chunk(proof: &MerkleProof<S>, chunk: &[u8]) -> bool
+    where
+        S: LeafHasher<[u8]>,
+    {
+        Self::verify_chunk_against(proof, &proof.root, chunk)
+    }
+
+    /// check that `chunk` is the leaf of the proof and that the proof leads
+    /// to a root the verifier trusts
+    pub fn verify_chunk_against(
+        proof: &MerkleProof<S>,
+        root: &Root<S::Digest>,
+        chunk: &[u8],
+    ) -> bool
+    where
+        S: LeafHasher<[u8]>,
+    {
+        proof.hasher.hash_leaf(chunk).ct_eq(&proof.leaf.0) && proof.verify_against(root)
+    }
+}
+
+pub(crate) fn check_chunk_size(chunk_size: usize) -> io::Result<()> {
+    if chunk_size == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "chunk size must not be zero",
+        ));
+    }
+    Ok(())
+}
+
+/// read into `buf` until it is full or the reader ends, returns the bytes read
+fn fill(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(read)
+}
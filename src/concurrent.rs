@@ -0,0 +1,153 @@
+//! append only trees shared between threads
+//!
+//! values are hashed before the lock is taken and appends only push the hash,
+//! so producers hash in parallel. readers copy the leaf hashes of one length
+//! out of the lock and hash the snapshot without blocking anybody.
+
+use alloc::vec::Vec;
+use core::{hash::Hash, marker::PhantomData};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::{
+    error::MerkleError,
+    hash::{DefaultBuildHasher, LeafHasher, MerkleHasher},
+    hashed::HashedMerkleTree,
+    proof::MerkleProof,
+    root::Root,
+    tree::{checked_width, PaddingStrategy, MAX_LEAFS},
+    OnceLock,
+};
+
+/// a merkle tree that many threads append to through a shared reference
+///
+/// every root and proof is computed over the leafs of one length, the leafs
+/// before a length never change as the tree is append only.
+pub struct ConcurrentMerkleTree<T, S: MerkleHasher = DefaultBuildHasher> {
+    leafs: RwLock<Vec<S::Digest>>,
+    /// the padding leaf of the hasher
+    empty: S::Digest,
+    hasher: S,
+    padding: PaddingStrategy,
+    values: PhantomData<fn(&T)>,
+}
+
+impl<T: Hash + Default> ConcurrentMerkleTree<T> {
+    pub fn new() -> Self {
+        Self::with_hasher(DefaultBuildHasher::default())
+    }
+}
+
+impl<T: Hash + Default> Default for ConcurrentMerkleTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Default, S: LeafHasher<T>> ConcurrentMerkleTree<T, S> {
+    pub fn with_hasher(hasher: S) -> Self {
+        Self {
+            leafs: RwLock::new(Vec::new()),
+            empty: hasher.hash_padding(),
+            hasher,
+            padding: PaddingStrategy::default(),
+            values: PhantomData,
+        }
+    }
+
+    /// hash `value` and append it, returns the index of its leaf
+    pub fn add(&self, value: T) -> usize {
+        let leaf = self.hasher.hash_leaf(&value);
+        self.add_hash(leaf)
+    }
+}
+
+impl<T, S: MerkleHasher> ConcurrentMerkleTree<T, S> {
+    /// change how the tree is completed when its size is not a power of two
+    pub fn with_padding(mut self, padding: PaddingStrategy) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn padding(&self) -> PaddingStrategy {
+        self.padding
+    }
+
+    /// append the hash of a leaf, returns its index
+    pub fn add_hash(&self, leaf: S::Digest) -> usize {
+        let mut leafs = self.write();
+        leafs.push(leaf);
+        leafs.len() - 1
+    }
+
+    /// number of leafs appended so far, padding excluded
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// the tree over the leafs appended so far, later appends do not change it
+    pub fn snapshot(&self) -> HashedMerkleTree<S>
+    where
+        S: Clone,
+    {
+        let leafs = self.read().clone();
+        HashedMerkleTree::new(
+            leafs,
+            self.empty,
+            self.hasher.clone(),
+            self.padding,
+            OnceLock::new(),
+        )
+    }
+
+    /// the length of the tree at the time of the call and the root for it
+    pub fn root(&self) -> (usize, Root<S::Digest>)
+    where
+        S: Clone,
+    {
+        let snapshot = self.snapshot();
+        (snapshot.len(), snapshot.root())
+    }
+
+    /// proof for the leaf at `index` and the length it was made at
+    pub fn get_proof(&self, index: usize) -> Result<SnapshotProof<S>, MerkleError>
+    where
+        S: Clone,
+    {
+        let snapshot = self.snapshot();
+        let proof = snapshot.get_proof(index)?;
+        Ok(SnapshotProof {
+            len: snapshot.len(),
+            proof,
+        })
+    }
+
+    /// the lock only guards pushes, which leave no half updated state behind
+    /// even if a thread panics, so a poisoned lock is used as it is
+    fn read(&self) -> RwLockReadGuard<'_, Vec<S::Digest>> {
+        self.leafs.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Vec<S::Digest>> {
+        self.leafs.write().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// a proof together with the tree length it was made at
+#[derive(Debug)]
+pub struct SnapshotProof<S: MerkleHasher = DefaultBuildHasher> {
+    pub len: usize,
+    pub proof: MerkleProof<S>,
+}
+
+impl<S: MerkleHasher> SnapshotProof<S> {
+    /// check that the proof is for a tree of `len` leafs and leads to `root`,
+    /// the root the tree reported for that length
+    pub fn verify_against(&self, root: &Root<S::Digest>) -> bool {
+        checked_width(self.proof.padding, self.len, MAX_LEAFS) == Ok(self.proof.width)
+            && self.proof.verify_against(root)
+    }
+}
@@ -0,0 +1,328 @@
+//! merkle trees whose leaf hashes live in a file instead of memory
+
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::Path,
+    sync::Mutex,
+};
+
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    error::MerkleError,
+    hash::{DefaultBuildHasher, Digest, MerkleHasher},
+    proof::MerkleProof,
+    root::{LeafHash, Root},
+    tree::{build_levels, parents, proof_path, width, PaddingStrategy},
+};
+
+/// levels of a chunk, the leafs in the file are grouped into chunks of
+/// `2^CHUNK_LEVELS` whose roots are kept in memory
+const CHUNK_LEVELS: usize = 16;
+const CHUNK: usize = 1 << CHUNK_LEVELS;
+
+/// a merkle tree over leaf hashes stored in a file, for trees too large to
+/// keep their leafs in memory
+///
+/// the file holds the raw leaf hashes one after the other. in memory are only
+/// the root of every complete chunk of 65536 leafs and the leafs of the chunk
+/// being filled, so a tree of `n` leafs needs `O(n / 65536)` memory. [`root`]
+/// hashes the chunk roots, [`get_proof`] reads the one chunk holding the leaf.
+/// roots and proofs equal the ones of a [`MerkleTree`] over the same leafs.
+///
+/// [`root`]: DiskMerkleTree::root
+/// [`get_proof`]: DiskMerkleTree::get_proof
+/// [`MerkleTree`]: crate::MerkleTree
+pub struct DiskMerkleTree<S: MerkleHasher = DefaultBuildHasher> {
+    file: Mutex<File>,
+    /// leaf hashes of the chunk being filled, some may be written already
+    pending: Vec<S::Digest>,
+    /// number of leaf hashes in the file
+    written: usize,
+    /// roots of the complete chunks
+    chunks: Vec<S::Digest>,
+    /// hash of the padding leaf
+    empty: S::Digest,
+    hasher: S,
+    padding: PaddingStrategy,
+}
+
+impl<S: MerkleHasher> DiskMerkleTree<S> {
+    /// an empty tree in a new file at `path`, an existing file is truncated
+    /// `empty` is the padding leaf, [`MerkleHasher::hash_padding`] gives the
+    /// roots of a [`MerkleTree`](crate::MerkleTree)
+    pub fn create(path: impl AsRef<Path>, hasher: S, empty: S::Digest) -> io::Result<Self> {
+        let file = File::options()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+            pending: Vec::new(),
+            written: 0,
+            chunks: Vec::new(),
+            empty,
+            hasher,
+            padding: PaddingStrategy::default(),
+        })
+    }
+
+    /// continue the tree in the file at `path`, the roots of its chunks are
+    /// hashed once while reading it
+    pub fn open(path: impl AsRef<Path>, hasher: S, empty: S::Digest) -> io::Result<Self> {
+        let mut file = File::options().read(true).write(true).open(path)?;
+        let size = file.metadata()?.len();
+        if size % S::Digest::LEN as u64 != 0 {
+            return Err(invalid_data(MerkleError::TrailingBytes(
+                (size % S::Digest::LEN as u64) as usize,
+            )));
+        }
+        let len = usize::try_from(size / S::Digest::LEN as u64)
+            .map_err(|_| invalid_data(MerkleError::InvalidEncoding("tree size is too large")))?;
+
+        let mut chunks = Vec::with_capacity(len / CHUNK);
+        let mut pending = Vec::new();
+        for start in (0..len).step_by(CHUNK) {
+            let leafs = read_leafs::<S::Digest>(&mut file, start, CHUNK.min(len - start))?;
+            if leafs.len() == CHUNK {
+                chunks.push(chunk_root(&hasher, leafs));
+            } else {
+                pending = leafs;
+            }
+        }
+
+        Ok(Self {
+            file: Mutex::new(file),
+            pending,
+            written: len,
+            chunks,
+            empty,
+            hasher,
+            padding: PaddingStrategy::default(),
+        })
+    }
+
+    /// change how the tree is completed when its size is not a power of two
+    pub fn with_padding(mut self, padding: PaddingStrategy) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn padding(&self) -> PaddingStrategy {
+        self.padding
+    }
+
+    /// number of leaf hashes stored, padding excluded
+    pub fn len(&self) -> usize {
+        self.chunks.len() * CHUNK + self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// append the hash of a leaf, it has to be hashed with the tree's hasher
+    /// leafs are written to the file once their chunk is complete or on
+    /// [`flush`](DiskMerkleTree::flush)
+    pub fn add_leaf_hash(&mut self, leaf: S::Digest) -> io::Result<()> {
+        self.pending.push(leaf);
+        if self.pending.len() == CHUNK {
+            self.flush()?;
+            let leafs = core::mem::take(&mut self.pending);
+            self.chunks.push(chunk_root(&self.hasher, leafs));
+        }
+        Ok(())
+    }
+
+    /// write the leafs that are not in the file yet, dropping the tree does
+    /// so too but ignores errors
+    pub fn flush(&mut self) -> io::Result<()> {
+        let start = self.chunks.len() * CHUNK;
+        let unwritten = &self.pending[self.written - start..];
+        if unwritten.is_empty() {
+            return Ok(());
+        }
+
+        let mut bytes = Vec::with_capacity(unwritten.len() * S::Digest::LEN);
+        for leaf in unwritten {
+            leaf.write_bytes(&mut bytes);
+        }
+        let file = self.file.get_mut().unwrap_or_else(|err| err.into_inner());
+        file.seek(SeekFrom::Start((self.written * S::Digest::LEN) as u64))?;
+        file.write_all(&bytes)?;
+        self.written += unwritten.len();
+        Ok(())
+    }
+
+    pub fn root(&self) -> io::Result<Root<S::Digest>> {
+        let levels = match self.upper_levels()? {
+            Some(levels) => levels,
+            None => self.chunk_levels(0)?,
+        };
+        Ok(Root(levels[levels.len() - 1][0]))
+    }
+
+    /// proof for the leaf at `index`, an index outside of the tree is an
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) error wrapping the
+    /// [`MerkleError`]
+    pub fn get_proof(&self, index: usize) -> io::Result<MerkleProof<S>>
+    where
+        S: Clone,
+    {
+        let len = self.len();
+        if index >= len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                MerkleError::IndexOutOfBounds { index, len },
+            ));
+        }
+        self.proof_from(index)
+    }
+
+    /// proof for the padding leaf at `index`, a leaf that holds a value is an
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) error wrapping
+    /// [`MerkleError::NotPadding`]
+    pub fn get_padding_proof(&self, index: usize) -> io::Result<MerkleProof<S>>
+    where
+        S: Clone,
+    {
+        let len = self.len();
+        if index < len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                MerkleError::NotPadding { index, len },
+            ));
+        }
+        let width = width(self.padding, len);
+        if index >= width {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                MerkleError::IndexOutOfBounds { index, len: width },
+            ));
+        }
+        self.proof_from(index)
+    }
+
+    fn proof_from(&self, index: usize) -> io::Result<MerkleProof<S>>
+    where
+        S: Clone,
+    {
+        let chunk = index / CHUNK;
+        let lower = self.chunk_levels(chunk)?;
+        let (leaf, mut path) =
+            proof_path(&lower, self.padding, index % CHUNK).map_err(invalid_data)?;
+        let root = match self.upper_levels()? {
+            Some(upper) => {
+                let (_, upper_path) =
+                    proof_path(&upper, self.padding, chunk).map_err(invalid_data)?;
+                path.extend(upper_path);
+                upper[upper.len() - 1][0]
+            }
+            None => lower[lower.len() - 1][0],
+        };
+
+        Ok(MerkleProof {
+            path,
+            root: Root(root),
+            leaf: LeafHash(leaf),
+            index,
+            width: width(self.padding, self.len()),
+            padding: self.padding,
+            hasher: self.hasher.clone(),
+        })
+    }
+
+    /// number of levels above the leafs
+    fn height(&self) -> usize {
+        let mut nodes = width(self.padding, self.len());
+        let mut height = 0;
+        while nodes > 1 {
+            nodes = nodes.div_ceil(2);
+            height += 1;
+        }
+        height
+    }
+
+    /// the levels of the chunk from its leafs, padding included, up to its
+    /// root, or up to the root of the tree if the tree is no higher
+    fn chunk_levels(&self, chunk: usize) -> io::Result<Vec<Vec<S::Digest>>> {
+        let start = chunk * CHUNK;
+        let mut leafs = match chunk.cmp(&self.chunks.len()) {
+            core::cmp::Ordering::Less => {
+                let mut file = self.file.lock().unwrap_or_else(|err| err.into_inner());
+                read_leafs(&mut file, start, CHUNK)?
+            }
+            core::cmp::Ordering::Equal => self.pending.clone(),
+            core::cmp::Ordering::Greater => Vec::new(),
+        };
+        let end = width(self.padding, self.len()).min(start + CHUNK);
+        leafs.resize(end - start, self.empty);
+
+        if self.height() <= CHUNK_LEVELS {
+            return Ok(build_levels(&self.hasher, self.padding, leafs));
+        }
+        // the last chunk may be narrower but has the levels of a full one
+        let mut levels = vec![leafs];
+        for _ in 0..CHUNK_LEVELS {
+            let next = parents(&self.hasher, self.padding, &levels[levels.len() - 1]);
+            levels.push(next);
+        }
+        Ok(levels)
+    }
+
+    /// the levels from the chunk roots up to the root, none if the tree is
+    /// not higher than a chunk
+    fn upper_levels(&self) -> io::Result<Option<Vec<Vec<S::Digest>>>> {
+        if self.height() <= CHUNK_LEVELS {
+            return Ok(None);
+        }
+
+        let mut roots = self.chunks.clone();
+        if !self.pending.is_empty() {
+            let levels = self.chunk_levels(self.chunks.len())?;
+            roots.push(levels[levels.len() - 1][0]);
+        }
+        // chunks made of padding only
+        let nodes = width(self.padding, self.len()).div_ceil(CHUNK);
+        if roots.len() < nodes {
+            let empty = (0..CHUNK_LEVELS).fold(self.empty, |node, _| {
+                self.hasher.hash_siblings(&node, &node)
+            });
+            roots.resize(nodes, empty);
+        }
+        Ok(Some(build_levels(&self.hasher, self.padding, roots)))
+    }
+}
+
+impl<S: MerkleHasher> Drop for DiskMerkleTree<S> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// root of a complete chunk, complete chunks have no odd levels so the
+/// padding strategy does not matter
+fn chunk_root<S: MerkleHasher>(hasher: &S, leafs: Vec<S::Digest>) -> S::Digest {
+    let levels = build_levels(hasher, PaddingStrategy::DefaultValue, leafs);
+    levels[levels.len() - 1][0]
+}
+
+fn read_leafs<D: Digest>(file: &mut File, start: usize, count: usize) -> io::Result<Vec<D>> {
+    let mut bytes = vec![0; count * D::LEN];
+    file.seek(SeekFrom::Start((start * D::LEN) as u64))?;
+    file.read_exact(&mut bytes)?;
+    bytes
+        .chunks_exact(D::LEN)
+        .map(|bytes| {
+            D::from_slice(bytes)
+                .ok_or_else(|| invalid_data(MerkleError::InvalidEncoding("invalid digest")))
+        })
+        .collect()
+}
+
+fn invalid_data(err: MerkleError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
@@ -0,0 +1,242 @@
+use alloc::string::String;
+use core::{error::Error, fmt, fmt::Debug};
+
+use crate::tree::PaddingStrategy;
+
+/// everything that can go wrong in this crate
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MerkleError {
+    /// an index at or beyond `len` was requested
+    IndexOutOfBounds { index: usize, len: usize },
+    /// a padding proof was requested for a leaf that holds one of the `len`
+    /// values
+    NotPadding { index: usize, len: usize },
+    /// a byte offset at or beyond the `size` of a file was requested
+    OffsetOutOfBounds { offset: u64, size: u64 },
+    /// an operation over several indices was given none
+    NoIndices,
+    /// encoded data ended before `needed` bytes could be read
+    Truncated { needed: usize, available: usize },
+    /// encoded data continued after its end
+    TrailingBytes(usize),
+    /// a direction other than left or right was encountered
+    InvalidDirection(String),
+    /// encoded data is malformed
+    InvalidEncoding(&'static str),
+    /// a json proof is malformed
+    InvalidJson(String),
+    /// a hex digest is malformed
+    InvalidHex(String),
+    /// the operation is only defined for trees of another shape
+    UnsupportedPadding(PaddingStrategy),
+    /// the leafs `start..end` are not the leafs of a single subtree
+    UnalignedSubtree { start: usize, end: usize },
+    /// two trees that have to be of the same length are not
+    LengthMismatch { len: usize, other: usize },
+    /// two trees that have to be padded the same way are not
+    PaddingMismatch {
+        padding: PaddingStrategy,
+        other: PaddingStrategy,
+    },
+    /// a consistency proof between these tree sizes does not exist
+    ConsistencySize { old_len: usize, new_len: usize },
+    /// a version after the latest one was requested
+    UnknownVersion { version: usize, latest: usize },
+    /// a value of the version changed after it was reached, its root is lost
+    StaleVersion { version: usize },
+    /// encoded data is in a format version this build can't read
+    UnsupportedVersion { version: u8, latest: u8 },
+    /// encoded data was written with another hash backend
+    WrongBackend,
+    /// a membership proof was requested for a key that is not set
+    KeyNotFound,
+    /// a non membership proof was requested for a key that is set
+    KeyExists,
+    /// no stored value hashes like the one a proof was requested for
+    ValueNotFound,
+    /// a value was added to a set that holds it already and rejects copies
+    DuplicateValue,
+    /// a proof does not lead to the root of the tree at `index` of a forest
+    NotInForest { index: usize },
+    /// a tree of `len` values would need more than the `max` leafs allowed
+    TooLarge { len: usize, max: usize },
+    /// a k-ary tree needs at least two children per node
+    InvalidArity(usize),
+    /// a tree was rewound to a checkpoint at `len` values it no longer holds
+    StaleCheckpoint { len: usize },
+    /// the operation needs a tree that holds at least one value
+    EmptyTree,
+}
+
+impl fmt::Display for MerkleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MerkleError::IndexOutOfBounds { index, len } => {
+                write!(f, "index {index} out of bounds for length {len}")
+            }
+            MerkleError::NotPadding { index, len } => {
+                write!(
+                    f,
+                    "leaf {index} is not padding, the tree holds {len} values"
+                )
+            }
+            MerkleError::OffsetOutOfBounds { offset, size } => {
+                write!(f, "byte offset {offset} out of bounds for {size} bytes")
+            }
+            MerkleError::NoIndices => f.write_str("no indices given"),
+            MerkleError::Truncated { needed, available } => {
+                write!(
+                    f,
+                    "truncated input, expected {needed} more bytes but got {available}"
+                )
+            }
+            MerkleError::TrailingBytes(count) => write!(f, "{count} trailing bytes after input"),
+            MerkleError::InvalidDirection(direction) => write!(f, "unknown direction {direction}"),
+            MerkleError::InvalidEncoding(reason) => write!(f, "invalid encoding: {reason}"),
+            MerkleError::InvalidJson(reason) => write!(f, "invalid json: {reason}"),
+            MerkleError::InvalidHex(reason) => write!(f, "invalid hex: {reason}"),
+            MerkleError::UnsupportedPadding(padding) => {
+                write!(f, "not supported for trees padded with {padding:?}")
+            }
+            MerkleError::UnalignedSubtree { start, end } => {
+                write!(f, "leafs {start}..{end} are not the leafs of one subtree")
+            }
+            MerkleError::LengthMismatch { len, other } => {
+                write!(f, "trees of {len} and {other} leafs have different shapes")
+            }
+            MerkleError::PaddingMismatch { padding, other } => {
+                write!(
+                    f,
+                    "trees padded with {padding:?} and {other:?} have different shapes"
+                )
+            }
+            MerkleError::ConsistencySize { old_len, new_len } => {
+                write!(f, "no consistency proof from {old_len} to {new_len} leafs")
+            }
+            MerkleError::UnknownVersion { version, latest } => {
+                write!(f, "version {version} is after the latest version {latest}")
+            }
+            MerkleError::StaleVersion { version } => {
+                write!(
+                    f,
+                    "a value of version {version} changed after it was reached"
+                )
+            }
+            MerkleError::UnsupportedVersion { version, latest } => {
+                write!(
+                    f,
+                    "format version {version} is not supported, the latest is {latest}"
+                )
+            }
+            MerkleError::WrongBackend => f.write_str("written with another hash backend"),
+            MerkleError::KeyNotFound => f.write_str("key is not in the tree"),
+            MerkleError::KeyExists => f.write_str("key is already in the tree"),
+            MerkleError::ValueNotFound => f.write_str("value is not in the tree"),
+            MerkleError::DuplicateValue => f.write_str("value is already in the set"),
+            MerkleError::NotInForest { index } => {
+                write!(f, "proof does not lead to the root of tree {index}")
+            }
+            MerkleError::TooLarge { len, max } => {
+                write!(
+                    f,
+                    "a tree of {len} values needs more than the {max} leafs allowed"
+                )
+            }
+            MerkleError::InvalidArity(arity) => {
+                write!(
+                    f,
+                    "a tree needs at least two children per node, not {arity}"
+                )
+            }
+            MerkleError::StaleCheckpoint { len } => {
+                write!(f, "the checkpoint at {len} values is no longer valid")
+            }
+            MerkleError::EmptyTree => f.write_str("the tree is empty"),
+        }
+    }
+}
+
+impl Error for MerkleError {}
+
+/// why a proof failed to verify
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProofError<D> {
+    /// the leaf index lies outside of the tree the proof claims to be from
+    IndexOutOfBounds { index: usize, width: usize },
+    /// the path does not have the length the tree size and the index require
+    PathLength { expected: usize, actual: usize },
+    /// the direction of the step at `step` does not agree with the leaf index
+    DirectionMismatch { step: usize },
+    /// the path leads to another root than the expected one
+    RootMismatch { computed: D, expected: D },
+    /// the inner proof of a chain leads to `root` but the outer one starts
+    /// from another `leaf`
+    ChainMismatch { root: D, leaf: D },
+}
+
+impl<D: Debug> fmt::Display for ProofError<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProofError::IndexOutOfBounds { index, width } => {
+                write!(
+                    f,
+                    "leaf index {index} out of bounds for a tree of {width} leafs"
+                )
+            }
+            ProofError::PathLength { expected, actual } => {
+                write!(
+                    f,
+                    "proof path has {actual} steps but the tree needs {expected}"
+                )
+            }
+            ProofError::DirectionMismatch { step } => {
+                write!(f, "direction of step {step} does not match the leaf index")
+            }
+            ProofError::RootMismatch { computed, expected } => {
+                write!(
+                    f,
+                    "proof leads to root {computed:?} instead of {expected:?}"
+                )
+            }
+            ProofError::ChainMismatch { root, leaf } => {
+                write!(
+                    f,
+                    "inner proof leads to {root:?} but the outer proof is for {leaf:?}"
+                )
+            }
+        }
+    }
+}
+
+impl<D: Debug> Error for ProofError<D> {}
+
+/// why a compare and swap update left the tree unchanged
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CasError<D> {
+    /// an index at or beyond `len` was requested
+    IndexOutOfBounds { index: usize, len: usize },
+    /// the leaf at `index` does not hold the expected value, `actual` is the
+    /// hash of the one it holds
+    Mismatch { index: usize, actual: D },
+}
+
+impl<D: Debug> fmt::Display for CasError<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CasError::IndexOutOfBounds { index, len } => {
+                write!(f, "index {index} out of bounds for length {len}")
+            }
+            CasError::Mismatch { index, actual } => {
+                write!(
+                    f,
+                    "leaf {index} holds another value, its hash is {actual:?}"
+                )
+            }
+        }
+    }
+}
+
+impl<D: Debug> Error for CasError<D> {}
@@ -0,0 +1,169 @@
+//! C interface over sha-256 trees of byte strings
+//!
+//! trees are handed out as opaque pointers and freed with
+//! [`merkle_tree_free`], proofs are exchanged in the encoding of
+//! [`MerkleProof::to_bytes`]. every function returns one of the `MERKLE_*`
+//! codes and catches panics instead of unwinding into the caller. the matching
+//! header is `include/merkle.h`.
+
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use crate::{hash::Sha256, MerkleError, MerkleProof, MerkleTree, Root};
+
+pub const MERKLE_OK: i32 = 0;
+/// the proof does not verify
+pub const MERKLE_INVALID: i32 = 1;
+pub const MERKLE_NULL_POINTER: i32 = -1;
+pub const MERKLE_OUT_OF_BOUNDS: i32 = -2;
+/// the proof bytes could not be decoded
+pub const MERKLE_MALFORMED: i32 = -3;
+/// the call panicked, the tree it was given is left as it was before
+pub const MERKLE_PANIC: i32 = -4;
+
+/// opaque tree handle
+pub struct MerkleTreeHandle(MerkleTree<Vec<u8>, Sha256>);
+
+fn guard(f: impl FnOnce() -> i32) -> i32 {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(MERKLE_PANIC)
+}
+
+/// create an empty tree, free it with [`merkle_tree_free`]
+#[no_mangle]
+pub extern "C" fn merkle_tree_new() -> *mut MerkleTreeHandle {
+    catch_unwind(|| {
+        Box::into_raw(Box::new(MerkleTreeHandle(MerkleTree::with_hasher(
+            Vec::new(),
+            Sha256,
+        ))))
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// free a tree, null is ignored
+///
+/// # Safety
+/// `tree` has to come from [`merkle_tree_new`] and must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn merkle_tree_free(tree: *mut MerkleTreeHandle) {
+    if !tree.is_null() {
+        drop(Box::from_raw(tree));
+    }
+}
+
+/// append a copy of `len` bytes at `data` as a leaf
+///
+/// # Safety
+/// `tree` has to be a live tree and `data` valid for reads of `len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn merkle_tree_add_bytes(
+    tree: *mut MerkleTreeHandle,
+    data: *const u8,
+    len: usize,
+) -> i32 {
+    guard(|| {
+        let Some(tree) = tree.as_mut() else {
+            return MERKLE_NULL_POINTER;
+        };
+        let value = match bytes(data, len) {
+            Some(value) => value.to_vec(),
+            None => return MERKLE_NULL_POINTER,
+        };
+        tree.0.add(value);
+        MERKLE_OK
+    })
+}
+
+/// write the 32 byte root to `out`
+///
+/// # Safety
+/// `tree` has to be a live tree and `out` valid for writes of 32 bytes
+#[no_mangle]
+pub unsafe extern "C" fn merkle_tree_root(tree: *const MerkleTreeHandle, out: *mut u8) -> i32 {
+    guard(|| {
+        let Some(tree) = tree.as_ref() else {
+            return MERKLE_NULL_POINTER;
+        };
+        if out.is_null() {
+            return MERKLE_NULL_POINTER;
+        }
+        let Root(root) = tree.0.root();
+        ptr::copy_nonoverlapping(root.as_ptr(), out, root.len());
+        MERKLE_OK
+    })
+}
+
+/// encode the proof of the leaf at `index` into a new buffer
+/// the buffer is written to `out` and `out_len`, free it with
+/// [`merkle_bytes_free`]
+///
+/// # Safety
+/// `tree` has to be a live tree, `out` and `out_len` valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn merkle_tree_proof(
+    tree: *const MerkleTreeHandle,
+    index: usize,
+    out: *mut *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    guard(|| {
+        let Some(tree) = tree.as_ref() else {
+            return MERKLE_NULL_POINTER;
+        };
+        if out.is_null() || out_len.is_null() {
+            return MERKLE_NULL_POINTER;
+        }
+        let proof = match tree.0.get_proof(index) {
+            Ok(proof) => proof,
+            Err(_) => return MERKLE_OUT_OF_BOUNDS,
+        };
+
+        let bytes = proof.to_bytes().into_boxed_slice();
+        *out_len = bytes.len();
+        *out = Box::into_raw(bytes).cast();
+        MERKLE_OK
+    })
+}
+
+/// free a buffer returned by [`merkle_tree_proof`], null is ignored
+///
+/// # Safety
+/// `data` and `len` have to be exactly the ones returned and must not be used
+/// afterwards
+#[no_mangle]
+pub unsafe extern "C" fn merkle_bytes_free(data: *mut u8, len: usize) {
+    if !data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(data, len)));
+    }
+}
+
+/// decode and verify a proof, returns [`MERKLE_OK`] if it holds and
+/// [`MERKLE_INVALID`] if it does not
+///
+/// # Safety
+/// `data` has to be valid for reads of `len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn merkle_proof_verify(data: *const u8, len: usize) -> i32 {
+    guard(|| {
+        let Some(bytes) = bytes(data, len) else {
+            return MERKLE_NULL_POINTER;
+        };
+        match MerkleProof::<Sha256>::from_bytes(bytes) {
+            Ok(proof) if proof.verify() => MERKLE_OK,
+            Ok(_) => MERKLE_INVALID,
+            Err(MerkleError::IndexOutOfBounds { .. }) => MERKLE_OUT_OF_BOUNDS,
+            Err(_) => MERKLE_MALFORMED,
+        }
+    })
+}
+
+/// view `len` bytes at `data`, null is only allowed for empty input
+unsafe fn bytes<'a>(data: *const u8, len: usize) -> Option<&'a [u8]> {
+    match (data.is_null(), len) {
+        (true, 0) => Some(&[]),
+        (true, _) => None,
+        (false, _) => Some(slice::from_raw_parts(data, len)),
+    }
+}
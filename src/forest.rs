@@ -0,0 +1,139 @@
+//! a tree over the roots of other trees, e.g. one tree per day and a weekly
+//! root over the daily ones
+//!
+//! the roots are the leafs of the top tree as they are, without hashing them
+//! again. a [`ChainedProof`] leads from a leaf of one of the trees to its root
+//! and from there to the root of the forest.
+
+use alloc::vec::Vec;
+
+use crate::{
+    error::{MerkleError, ProofError},
+    hash::{DefaultBuildHasher, Digest, LeafHasher, MaybeSync, MerkleHasher},
+    hashed::HashedMerkleTree,
+    proof::MerkleProof,
+    root::Root,
+    tree::{MerkleTree, PaddingStrategy},
+    OnceLock,
+};
+
+/// a merkle tree whose leafs are the roots of other trees, in order
+pub struct MerkleForest<S: MerkleHasher = DefaultBuildHasher> {
+    tree: HashedMerkleTree<S>,
+}
+
+impl MerkleForest {
+    /// a forest over `roots` of trees with the default hasher
+    pub fn new(roots: Vec<Root>) -> Self {
+        Self::with_hasher(roots, DefaultBuildHasher::default())
+    }
+}
+
+impl<S: MerkleHasher> MerkleForest<S> {
+    /// a forest over `roots`, the top tree is hashed like the trees were
+    pub fn with_hasher(roots: Vec<Root<S::Digest>>, hasher: S) -> Self {
+        let empty = hasher.hash_padding();
+        let roots = roots.into_iter().map(Root::into_inner).collect();
+        Self {
+            tree: HashedMerkleTree::new(
+                roots,
+                empty,
+                hasher,
+                PaddingStrategy::default(),
+                OnceLock::new(),
+            ),
+        }
+    }
+
+    /// a forest over the roots of `trees`, with the hasher of the first one
+    pub fn from_trees<T: Default + MaybeSync>(trees: &[MerkleTree<T, S>]) -> Self
+    where
+        S: LeafHasher<T> + Clone + Default,
+    {
+        let hasher = trees
+            .first()
+            .map(|tree| tree.hasher().clone())
+            .unwrap_or_default();
+        Self::with_hasher(trees.iter().map(MerkleTree::root).collect(), hasher)
+    }
+
+    /// change how the top tree is completed when the number of trees is not a
+    /// power of two
+    pub fn with_padding(mut self, padding: PaddingStrategy) -> Self {
+        self.tree = self.tree.with_padding(padding);
+        self
+    }
+
+    /// number of trees in the forest
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// the root over the roots of all trees
+    pub fn root(&self) -> Root<S::Digest> {
+        self.tree.root()
+    }
+
+    /// proof that the root of the tree at `index` is a leaf of the forest
+    pub fn get_proof(&self, index: usize) -> Result<MerkleProof<S>, MerkleError>
+    where
+        S: Clone,
+    {
+        self.tree.get_proof(index)
+    }
+
+    /// chain `inner`, a proof from the tree at `index`, with the proof of that
+    /// tree's root in the forest
+    /// a proof that does not lead to the root of the tree is
+    /// [`NotInForest`](MerkleError::NotInForest)
+    pub fn chain(&self, index: usize, inner: MerkleProof<S>) -> Result<ChainedProof<S>, MerkleError>
+    where
+        S: Clone,
+    {
+        let outer = self.get_proof(index)?;
+        if !inner.verify_against(&Root(outer.leaf.0)) {
+            return Err(MerkleError::NotInForest { index });
+        }
+        Ok(ChainedProof { inner, outer })
+    }
+}
+
+/// proof that a leaf is part of one of the trees of a forest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainedProof<S: MerkleHasher = DefaultBuildHasher> {
+    /// from the leaf to the root of its tree
+    pub inner: MerkleProof<S>,
+    /// from the root of the tree to the root of the forest
+    pub outer: MerkleProof<S>,
+}
+
+impl<S: MerkleHasher> ChainedProof<S> {
+    /// check that the inner proof ends in the leaf of the outer one and that
+    /// both verify, the outer one against the trusted `root` of the forest
+    pub fn verify(&self, root: &Root<S::Digest>) -> bool {
+        self.verify_detailed(root).is_ok()
+    }
+
+    /// like [`verify`](ChainedProof::verify) but reports the first check that
+    /// failed, proofs that are not chained are
+    /// [`ChainMismatch`](ProofError::ChainMismatch)
+    pub fn verify_detailed(&self, root: &Root<S::Digest>) -> Result<(), ProofError<S::Digest>> {
+        if !self.inner.root.0.ct_eq(&self.outer.leaf.0) {
+            return Err(ProofError::ChainMismatch {
+                root: self.inner.root.0,
+                leaf: self.outer.leaf.0,
+            });
+        }
+        self.inner.verify_detailed()?;
+        self.outer.check(root)
+    }
+
+    /// index of the tree in the forest
+    pub fn tree_index(&self) -> usize {
+        self.outer.index
+    }
+}
@@ -0,0 +1,192 @@
+//! a tree stored as one flat array of all its nodes
+//!
+//! the `2 * width - 1` nodes of a tree over `width` leafs sit in heap order:
+//! the root at `0`, the children of node `i` at `2 * i + 1` and `2 * i + 2` and
+//! the leafs from `width - 1` on. the root is a single read, proofs read one
+//! sibling per level and updates rehash the nodes on one path.
+
+use alloc::vec::Vec;
+use core::{fmt, hash::Hash};
+
+use crate::{
+    error::MerkleError,
+    hash::{DefaultBuildHasher, LeafHasher, MerkleHasher},
+    proof::{Direction, MerkleProof, Step},
+    root::{LeafHash, Root},
+    tree::PaddingStrategy,
+};
+
+/// a merkle tree padded with [`PaddingStrategy::DefaultValue`] that keeps every
+/// node in a single vector
+///
+/// roots and proofs equal the ones of a [`MerkleTree`](crate::MerkleTree)
+/// over the same values, only the padding strategy with a power of two of
+/// leafs fits the heap layout.
+#[derive(Clone)]
+pub struct FullMerkleTree<T, S: MerkleHasher = DefaultBuildHasher> {
+    values: Vec<T>,
+    hasher: S,
+    nodes: Vec<S::Digest>,
+}
+
+impl<T: fmt::Debug, S: MerkleHasher> fmt::Debug for FullMerkleTree<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FullMerkleTree")
+            .field("values", &self.values)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> From<Vec<T>> for FullMerkleTree<T>
+where
+    T: Hash,
+{
+    fn from(values: Vec<T>) -> Self {
+        Self::with_hasher(values, DefaultBuildHasher::default())
+    }
+}
+
+impl<T, S: MerkleHasher> FullMerkleTree<T, S> {
+    pub fn hasher(&self) -> &S {
+        &self.hasher
+    }
+
+    /// the stored values in insertion order, padding excluded
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.values.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// number of leafs including padding, a power of two
+    pub fn padded_len(&self) -> usize {
+        self.nodes.len().div_ceil(2)
+    }
+
+    /// all nodes in heap order, the root first and the leafs last
+    pub fn nodes(&self) -> &[S::Digest] {
+        &self.nodes
+    }
+
+    pub fn root(&self) -> Root<S::Digest> {
+        Root(self.nodes[0])
+    }
+
+    /// proof that the value at `index` is part of the tree, see
+    /// [`MerkleTree::get_proof`](crate::MerkleTree::get_proof)
+    pub fn get_proof(&self, index: usize) -> Result<MerkleProof<S>, MerkleError>
+    where
+        S: Clone,
+    {
+        let len = self.values.len();
+        if index >= len {
+            return Err(MerkleError::IndexOutOfBounds { index, len });
+        }
+
+        let width = self.padded_len();
+        let mut node = width - 1 + index;
+        let mut path = Vec::with_capacity(width.trailing_zeros() as usize);
+        while node > 0 {
+            // right children sit at even positions
+            let step = if node.is_multiple_of(2) {
+                Step {
+                    direction: Direction::Left,
+                    value: self.nodes[node - 1],
+                }
+            } else {
+                Step {
+                    direction: Direction::Right,
+                    value: self.nodes[node + 1],
+                }
+            };
+            path.push(step);
+            node = (node - 1) / 2;
+        }
+
+        Ok(MerkleProof {
+            path,
+            root: self.root(),
+            leaf: LeafHash(self.nodes[width - 1 + index]),
+            index,
+            width,
+            padding: PaddingStrategy::DefaultValue,
+            hasher: self.hasher.clone(),
+        })
+    }
+}
+
+impl<T, S: LeafHasher<T>> FullMerkleTree<T, S> {
+    /// build a tree whose leafs and nodes are hashed with the given hasher
+    /// factory, every node is hashed once here
+    pub fn with_hasher(values: Vec<T>, hasher: S) -> Self {
+        let mut tree = Self {
+            values,
+            hasher,
+            nodes: Vec::new(),
+        };
+        tree.rebuild();
+        tree
+    }
+
+    /// replace the value at `index`, only the nodes above it are rehashed
+    pub fn update(&mut self, index: usize, value: T) -> Result<(), MerkleError> {
+        let len = self.values.len();
+        let slot = self
+            .values
+            .get_mut(index)
+            .ok_or(MerkleError::IndexOutOfBounds { index, len })?;
+        *slot = value;
+
+        let leaf = self.hasher.hash_leaf(&self.values[index]);
+        self.set_leaf(index, leaf);
+        Ok(())
+    }
+
+    /// append a value, it takes the place of the first padding leaf
+    /// only a full tree is rebuilt, at twice its width
+    pub fn add(&mut self, value: T) {
+        let index = self.values.len();
+        self.values.push(value);
+        if index < self.padded_len() {
+            let leaf = self.hasher.hash_leaf(&self.values[index]);
+            self.set_leaf(index, leaf);
+        } else {
+            self.rebuild();
+        }
+    }
+
+    fn set_leaf(&mut self, index: usize, leaf: S::Digest) {
+        let mut node = self.padded_len() - 1 + index;
+        self.nodes[node] = leaf;
+        while node > 0 {
+            node = (node - 1) / 2;
+            self.nodes[node] = self
+                .hasher
+                .hash_siblings(&self.nodes[2 * node + 1], &self.nodes[2 * node + 2]);
+        }
+    }
+
+    fn rebuild(&mut self) {
+        let width = self.values.len().next_power_of_two();
+        let mut nodes = Vec::with_capacity(2 * width - 1);
+        nodes.resize(width - 1, self.hasher.hash_padding());
+        nodes.extend(self.values.iter().map(|value| self.hasher.hash_leaf(value)));
+        nodes.resize(2 * width - 1, self.hasher.hash_padding());
+        for node in (0..width - 1).rev() {
+            nodes[node] = self
+                .hasher
+                .hash_siblings(&nodes[2 * node + 1], &nodes[2 * node + 2]);
+        }
+        self.nodes = nodes;
+    }
+}
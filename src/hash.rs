@@ -0,0 +1,252 @@
+use alloc::{vec, vec::Vec};
+use core::{
+    cmp::Ordering,
+    fmt::Debug,
+    hash::{BuildHasher, BuildHasherDefault, Hash, Hasher},
+    hint::black_box,
+};
+
+mod domain;
+mod empty;
+mod fnv;
+mod sorted_pairs;
+mod streaming;
+
+#[cfg(feature = "blake2b")]
+pub mod blake2b;
+#[cfg(feature = "blake3")]
+pub mod blake3;
+#[cfg(feature = "keccak")]
+pub mod keccak;
+#[cfg(feature = "poseidon")]
+pub mod poseidon;
+#[cfg(feature = "rfc6962")]
+pub mod rfc6962;
+#[cfg(feature = "sha256")]
+pub mod sha256;
+
+#[cfg(feature = "blake2b")]
+pub use blake2b::Blake2b512;
+#[cfg(feature = "blake3")]
+pub use blake3::Blake3;
+#[cfg(feature = "keccak")]
+pub use keccak::Keccak256;
+#[cfg(feature = "poseidon")]
+pub use poseidon::{Fr, Poseidon};
+#[cfg(feature = "rfc6962")]
+pub use rfc6962::Rfc6962;
+#[cfg(feature = "sha256")]
+pub use sha256::Sha256;
+
+pub use domain::Domain;
+pub use empty::EmptyHashes;
+pub use fnv::FnvHasher;
+pub use sorted_pairs::SortedPairs;
+pub use streaming::{Hashing, StreamingDigest};
+
+/// hasher factory used when none is given explicitly
+#[cfg(feature = "std")]
+pub type DefaultBuildHasher = BuildHasherDefault<std::hash::DefaultHasher>;
+
+/// hasher factory used when none is given explicitly
+/// without std there is no `DefaultHasher`, so digests differ from std builds.
+/// use a byte oriented backend for proofs that cross between them
+#[cfg(not(feature = "std"))]
+pub type DefaultBuildHasher = BuildHasherDefault<FnvHasher>;
+
+/// `Sync` with the `parallel` feature, which hashes values, hashers and
+/// digests on several threads, implemented for every type otherwise
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Sync {}
+#[cfg(feature = "parallel")]
+impl<T: Sync + ?Sized> MaybeSync for T {}
+/// `Sync` with the `parallel` feature, which hashes values, hashers and
+/// digests on several threads, implemented for every type otherwise
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "parallel"))]
+impl<T: ?Sized> MaybeSync for T {}
+
+/// `Send` with the `parallel` feature, like [`MaybeSync`]
+#[cfg(feature = "parallel")]
+pub trait MaybeSend: Send {}
+#[cfg(feature = "parallel")]
+impl<T: Send + ?Sized> MaybeSend for T {}
+/// `Send` with the `parallel` feature, like [`MaybeSync`]
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSend {}
+#[cfg(not(feature = "parallel"))]
+impl<T: ?Sized> MaybeSend for T {}
+
+/// a fixed width digest that can be written to and read from bytes
+pub trait Digest: Copy + Eq + Debug + MaybeSend + MaybeSync {
+    /// number of bytes of the encoded digest
+    const LEN: usize;
+
+    /// append the `LEN` bytes of the digest to `out`
+    fn write_bytes(&self, out: &mut Vec<u8>);
+
+    /// read a digest from exactly `LEN` bytes
+    fn from_slice(bytes: &[u8]) -> Option<Self>;
+
+    /// equality in time that does not depend on where the digests differ,
+    /// verification compares computed roots with it
+    /// by default the encoded bytes are compared
+    fn ct_eq(&self, other: &Self) -> bool {
+        let (mut left, mut right) = (Vec::with_capacity(Self::LEN), Vec::with_capacity(Self::LEN));
+        self.write_bytes(&mut left);
+        other.write_bytes(&mut right);
+        ct_eq(&left, &right)
+    }
+}
+
+/// 64 bit digests are encoded big endian
+impl Digest for u64 {
+    const LEN: usize = 8;
+
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_be_bytes());
+    }
+
+    fn from_slice(bytes: &[u8]) -> Option<Self> {
+        Some(u64::from_be_bytes(bytes.try_into().ok()?))
+    }
+
+    fn ct_eq(&self, other: &Self) -> bool {
+        black_box(self ^ other) == 0
+    }
+}
+
+impl<const N: usize> Digest for [u8; N] {
+    const LEN: usize = N;
+
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self);
+    }
+
+    fn from_slice(bytes: &[u8]) -> Option<Self> {
+        bytes.try_into().ok()
+    }
+
+    fn ct_eq(&self, other: &Self) -> bool {
+        ct_eq(self, other)
+    }
+}
+
+/// compare two byte strings without stopping at the first difference, every
+/// byte is read and only the lengths may end the comparison early
+pub fn ct_eq(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+    let diff = left
+        .iter()
+        .zip(right)
+        .fold(0, |diff, (left, right)| diff | (left ^ right));
+    black_box(diff) == 0
+}
+
+/// the digest of all zero bytes
+pub(crate) fn zero<D: Digest>() -> D {
+    D::from_slice(&vec![0; D::LEN]).expect("all zero bytes are a digest of every backend")
+}
+
+/// order digests by their encoded bytes
+pub(crate) fn compare<D: Digest>(a: &D, b: &D) -> Ordering {
+    let (mut left, mut right) = (Vec::with_capacity(D::LEN), Vec::with_capacity(D::LEN));
+    a.write_bytes(&mut left);
+    b.write_bytes(&mut right);
+    left.cmp(&right)
+}
+
+/// a hashing backend for the inner nodes of a tree
+/// verification only ever needs this part of a backend
+pub trait MerkleHasher: MaybeSync {
+    /// the digest stored in leafs, nodes and proofs
+    type Digest: Digest;
+
+    /// hash two siblings into their parent node
+    fn hash_siblings(&self, left: &Self::Digest, right: &Self::Digest) -> Self::Digest;
+
+    /// hash the children of a node of a tree with more than two children per
+    /// node, two children have to hash like siblings
+    /// by default the children are chained through `hash_siblings`
+    fn hash_children(&self, children: &[Self::Digest]) -> Self::Digest {
+        let (first, rest) = children.split_first().expect("a node has children");
+        rest.iter()
+            .fold(*first, |acc, child| self.hash_siblings(&acc, child))
+    }
+
+    /// hash every pair of consecutive `nodes` into their parent, the way
+    /// whole levels are reduced, `nodes` holds an even number of digests
+    /// by default the pairs are hashed one by one with `hash_siblings`,
+    /// backends override it where many pairs hash faster at once
+    fn hash_pairs(&self, nodes: &[Self::Digest]) -> Vec<Self::Digest> {
+        debug_assert!(nodes.len().is_multiple_of(2), "nodes come in pairs");
+        nodes
+            .chunks_exact(2)
+            .map(|pair| self.hash_siblings(&pair[0], &pair[1]))
+            .collect()
+    }
+
+    /// the leaf that pads a tree to its shape, it differs from the leaf hash
+    /// of every value, a stored default value included
+    /// by default the all zero digest, which no value hashes to with a
+    /// preimage resistant backend
+    fn hash_padding(&self) -> Self::Digest {
+        zero()
+    }
+}
+
+/// a hashing backend that knows how to turn values of type `T` into leafs
+pub trait LeafHasher<T: ?Sized>: MerkleHasher {
+    /// hash a single value into a leaf of the tree
+    fn hash_leaf(&self, leaf: &T) -> Self::Digest;
+}
+
+/// prefix fed into the hasher before a leaf value
+const LEAF_PREFIX: u8 = 0;
+/// prefix fed into the hasher before a pair of siblings
+const NODE_PREFIX: u8 = 1;
+/// prefix hashed on its own into the padding leaf
+const PADDING_PREFIX: u8 = 2;
+
+/// any std hasher factory produces 64 bit digests
+/// leafs, inner nodes and padding are domain separated by a prefix byte, so
+/// that a value can never hash to the same digest as a pair of siblings or a
+/// padding leaf
+impl<S: BuildHasher + MaybeSync> MerkleHasher for S {
+    type Digest = u64;
+
+    fn hash_siblings(&self, left: &u64, right: &u64) -> u64 {
+        let mut hasher = self.build_hasher();
+        hasher.write_u8(NODE_PREFIX);
+        left.hash(&mut hasher);
+        right.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn hash_children(&self, children: &[u64]) -> u64 {
+        let mut hasher = self.build_hasher();
+        hasher.write_u8(NODE_PREFIX);
+        for child in children {
+            child.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    fn hash_padding(&self) -> u64 {
+        let mut hasher = self.build_hasher();
+        hasher.write_u8(PADDING_PREFIX);
+        hasher.finish()
+    }
+}
+
+impl<T: Hash + ?Sized, S: BuildHasher + MaybeSync> LeafHasher<T> for S {
+    fn hash_leaf(&self, leaf: &T) -> u64 {
+        let mut hasher = self.build_hasher();
+        hasher.write_u8(LEAF_PREFIX);
+        leaf.hash(&mut hasher);
+        hasher.finish()
+    }
+}
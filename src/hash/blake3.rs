@@ -0,0 +1,224 @@
+//! blake3 backend with 32 byte digests
+//!
+//! leafs are hashed over their raw bytes, inner nodes over the concatenation
+//! `left || right` of their children's digests. blake3 is a tree hash itself,
+//! with the `parallel` feature the halves of long inputs are hashed on
+//! separate threads, splitting only as often as there are cores for.
+
+use super::{LeafHasher, MerkleHasher};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Blake3;
+
+impl MerkleHasher for Blake3 {
+    type Digest = [u8; 32];
+
+    fn hash_siblings(&self, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut block = [0; 64];
+        block[..32].copy_from_slice(left);
+        block[32..].copy_from_slice(right);
+        digest(&block)
+    }
+
+    fn hash_children(&self, children: &[[u8; 32]]) -> [u8; 32] {
+        digest(children.as_flattened())
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> LeafHasher<T> for Blake3 {
+    fn hash_leaf(&self, leaf: &T) -> [u8; 32] {
+        digest(leaf.as_ref())
+    }
+}
+
+/// blake3 digest of the given bytes
+pub fn digest(data: &[u8]) -> [u8; 32] {
+    #[cfg(feature = "parallel")]
+    let splits = crate::parallel::threads().ilog2();
+    #[cfg(not(feature = "parallel"))]
+    let splits = 0;
+    let root = subtree(data, 0, splits);
+    let words = compress(
+        &root.cv,
+        &root.block,
+        root.counter,
+        root.len,
+        root.flags | ROOT,
+    );
+
+    let mut out = [0; 32];
+    for (chunk, word) in out.chunks_exact_mut(4).zip(&words[..8]) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    out
+}
+
+const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+
+const BLOCK_LEN: usize = 64;
+const CHUNK_LEN: usize = 1024;
+
+/// inputs at least this long have their halves hashed on two threads, as
+/// long as the split budget lasts
+#[cfg(feature = "parallel")]
+const MIN_PARALLEL_LEN: usize = 1 << 20;
+
+/// the last compression of a node, kept open so that the root can be
+/// finalized with the root flag
+struct Output {
+    cv: [u32; 8],
+    block: [u32; 16],
+    counter: u64,
+    len: u32,
+    flags: u32,
+}
+
+impl Output {
+    fn chaining_value(&self) -> [u32; 8] {
+        let words = compress(&self.cv, &self.block, self.counter, self.len, self.flags);
+        words[..8].try_into().expect("eight words")
+    }
+}
+
+/// the output of the subtree over `data`, whose first chunk is `counter`
+/// the left subtree holds the largest power of two of chunks that leaves at
+/// least one byte for the right one
+/// up to `splits` levels below this one hand their left half to a new thread,
+/// so at most `2^splits` threads hash `data`
+#[cfg_attr(not(feature = "parallel"), allow(clippy::only_used_in_recursion))]
+fn subtree(data: &[u8], counter: u64, splits: u32) -> Output {
+    if data.len() <= CHUNK_LEN {
+        return chunk(data, counter);
+    }
+
+    let chunks = (data.len() - 1) / CHUNK_LEN;
+    let left_chunks = 1 << chunks.ilog2();
+    let (left, right) = data.split_at(left_chunks * CHUNK_LEN);
+    let right_counter = counter + left_chunks as u64;
+
+    #[cfg(feature = "parallel")]
+    let (left, right) = if splits > 0 && data.len() >= MIN_PARALLEL_LEN {
+        crate::parallel::join(
+            || subtree(left, counter, splits - 1).chaining_value(),
+            || subtree(right, right_counter, splits - 1).chaining_value(),
+        )
+    } else {
+        (
+            subtree(left, counter, 0).chaining_value(),
+            subtree(right, right_counter, 0).chaining_value(),
+        )
+    };
+    #[cfg(not(feature = "parallel"))]
+    let (left, right) = (
+        subtree(left, counter, splits).chaining_value(),
+        subtree(right, right_counter, splits).chaining_value(),
+    );
+
+    let mut block = [0; 16];
+    block[..8].copy_from_slice(&left);
+    block[8..].copy_from_slice(&right);
+    Output {
+        cv: IV,
+        block,
+        counter: 0,
+        len: BLOCK_LEN as u32,
+        flags: PARENT,
+    }
+}
+
+/// the output of a single chunk of at most `CHUNK_LEN` bytes
+fn chunk(data: &[u8], counter: u64) -> Output {
+    let mut cv = IV;
+    let blocks = data.len().div_ceil(BLOCK_LEN).max(1);
+    let mut flags = CHUNK_START;
+
+    for block in data.chunks(BLOCK_LEN).take(blocks - 1) {
+        let words = compress(&cv, &words(block), counter, BLOCK_LEN as u32, flags);
+        cv = words[..8].try_into().expect("eight words");
+        flags = 0;
+    }
+
+    let last = &data[(blocks - 1) * BLOCK_LEN..];
+    Output {
+        cv,
+        block: words(last),
+        counter,
+        len: last.len() as u32,
+        flags: flags | CHUNK_END,
+    }
+}
+
+/// read a block of at most 64 bytes as little endian words, zero padded
+fn words(block: &[u8]) -> [u32; 16] {
+    let mut bytes = [0; BLOCK_LEN];
+    bytes[..block.len()].copy_from_slice(block);
+
+    let mut words = [0; 16];
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+        *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    words
+}
+
+fn compress(cv: &[u32; 8], block: &[u32; 16], counter: u64, len: u32, flags: u32) -> [u32; 16] {
+    let mut s = [
+        cv[0],
+        cv[1],
+        cv[2],
+        cv[3],
+        cv[4],
+        cv[5],
+        cv[6],
+        cv[7],
+        IV[0],
+        IV[1],
+        IV[2],
+        IV[3],
+        counter as u32,
+        (counter >> 32) as u32,
+        len,
+        flags,
+    ];
+    let mut m = *block;
+
+    for round in 0..7 {
+        g(&mut s, 0, 4, 8, 12, m[0], m[1]);
+        g(&mut s, 1, 5, 9, 13, m[2], m[3]);
+        g(&mut s, 2, 6, 10, 14, m[4], m[5]);
+        g(&mut s, 3, 7, 11, 15, m[6], m[7]);
+        g(&mut s, 0, 5, 10, 15, m[8], m[9]);
+        g(&mut s, 1, 6, 11, 12, m[10], m[11]);
+        g(&mut s, 2, 7, 8, 13, m[12], m[13]);
+        g(&mut s, 3, 4, 9, 14, m[14], m[15]);
+
+        if round < 6 {
+            m = PERMUTATION.map(|i| m[i]);
+        }
+    }
+
+    for i in 0..8 {
+        s[i] ^= s[i + 8];
+        s[i + 8] ^= cv[i];
+    }
+    s
+}
+
+fn g(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, x: u32, y: u32) {
+    s[a] = s[a].wrapping_add(s[b]).wrapping_add(x);
+    s[d] = (s[d] ^ s[a]).rotate_right(16);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_right(12);
+    s[a] = s[a].wrapping_add(s[b]).wrapping_add(y);
+    s[d] = (s[d] ^ s[a]).rotate_right(8);
+    s[c] = s[c].wrapping_add(s[d]);
+    s[b] = (s[b] ^ s[c]).rotate_right(7);
+}
@@ -0,0 +1,62 @@
+//! domain separated hashing, trees of different applications never share
+//! hashes even over the same values
+
+use alloc::vec::Vec;
+
+use super::{DefaultBuildHasher, LeafHasher, MerkleHasher};
+
+/// wraps a backend so that a key derived from a domain tag is hashed into
+/// every leaf and every node
+///
+/// a leaf is `hash_children([key, leaf])` and a node `hash_children([key,
+/// left, right])` of the wrapped backend, so proofs of a tree only verify with
+/// a hasher of the same tag. the tag is no secret, verifiers configure it
+/// like they configure the backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Domain<S: MerkleHasher = DefaultBuildHasher> {
+    hasher: S,
+    key: S::Digest,
+}
+
+impl<S: MerkleHasher> Domain<S> {
+    /// the key of the domain is the leaf hash of `tag`
+    pub fn new(hasher: S, tag: &[u8]) -> Self
+    where
+        S: LeafHasher<[u8]>,
+    {
+        let key = hasher.hash_leaf(tag);
+        Self { hasher, key }
+    }
+
+    /// the wrapped backend
+    pub fn hasher(&self) -> &S {
+        &self.hasher
+    }
+}
+
+impl<S: MerkleHasher> MerkleHasher for Domain<S> {
+    type Digest = S::Digest;
+
+    fn hash_siblings(&self, left: &S::Digest, right: &S::Digest) -> S::Digest {
+        self.hasher.hash_children(&[self.key, *left, *right])
+    }
+
+    fn hash_children(&self, children: &[S::Digest]) -> S::Digest {
+        let mut keyed = Vec::with_capacity(children.len() + 1);
+        keyed.push(self.key);
+        keyed.extend_from_slice(children);
+        self.hasher.hash_children(&keyed)
+    }
+
+    fn hash_padding(&self) -> S::Digest {
+        self.hasher
+            .hash_children(&[self.key, self.hasher.hash_padding()])
+    }
+}
+
+impl<T: ?Sized, S: LeafHasher<T>> LeafHasher<T> for Domain<S> {
+    fn hash_leaf(&self, leaf: &T) -> S::Digest {
+        self.hasher
+            .hash_children(&[self.key, self.hasher.hash_leaf(leaf)])
+    }
+}
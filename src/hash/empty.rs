@@ -0,0 +1,112 @@
+//! roots of subtrees made of padding only
+//!
+//! `z0` is the padding leaf and `z(h + 1) = hash_siblings(z(h), z(h))`. trees
+//! padded with default values, sparse trees and compact proofs look these up
+//! instead of hashing padding over and over.
+
+use alloc::vec::Vec;
+use core::ops::Index;
+
+use super::{Digest, MerkleHasher};
+use crate::{
+    error::MerkleError,
+    proof::bytes::{write_varint, Reader},
+};
+
+/// roots of the subtrees made of padding only, by height
+/// prover and verifier have to build it from the same padding leaf
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmptyHashes<D> {
+    hashes: Vec<D>,
+}
+
+impl<D: Digest> EmptyHashes<D> {
+    /// the roots of empty subtrees of every height up to `height` over the
+    /// padding leaf `empty`
+    pub fn new<S: MerkleHasher<Digest = D>>(hasher: &S, empty: D, height: usize) -> Self {
+        let mut table = Self {
+            hashes: Vec::with_capacity(height + 1),
+        };
+        table.hashes.push(empty);
+        table.extend_to(hasher, height);
+        table
+    }
+
+    /// hash the missing heights up to `height`, the hasher has to be the one
+    /// the table was built with
+    pub fn extend_to<S: MerkleHasher<Digest = D>>(&mut self, hasher: &S, height: usize) {
+        while self.height() < height {
+            let top = self.hashes[self.hashes.len() - 1];
+            self.hashes.push(hasher.hash_siblings(&top, &top));
+        }
+    }
+
+    /// root of an empty subtree of `height`, the padding leaf at height `0`
+    pub fn get(&self, height: usize) -> Option<&D> {
+        self.hashes.get(height)
+    }
+
+    /// the greatest height in the table
+    pub fn height(&self) -> usize {
+        self.hashes.len() - 1
+    }
+
+    /// encode the table, the digest length and the height as unsigned LEB128
+    /// varints followed by the hashes from the padding leaf up
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(4 + self.hashes.len() * D::LEN);
+        write_varint(&mut out, D::LEN as u64);
+        write_varint(&mut out, self.height() as u64);
+        self.hashes
+            .iter()
+            .for_each(|hash| hash.write_bytes(&mut out));
+        out
+    }
+
+    /// decode a table written by [`to_bytes`]
+    /// the hashes are not checked against a hasher, compare the table with one
+    /// built locally where the file is not trusted
+    ///
+    /// [`to_bytes`]: EmptyHashes::to_bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MerkleError> {
+        let mut reader = Reader { bytes };
+        if reader.varint()? != D::LEN as u64 {
+            return Err(MerkleError::InvalidEncoding(
+                "table was written with another hash backend",
+            ));
+        }
+        let count = usize::try_from(reader.varint()?)
+            .ok()
+            .and_then(|height| height.checked_add(1))
+            .ok_or(MerkleError::InvalidEncoding("table is too high"))?;
+
+        // check the size before allocating anything for the hashes
+        let expected = count
+            .checked_mul(D::LEN)
+            .ok_or(MerkleError::InvalidEncoding("table is too high"))?;
+        let available = reader.bytes.len();
+        if available < expected {
+            return Err(MerkleError::Truncated {
+                needed: expected,
+                available,
+            });
+        }
+        if available > expected {
+            return Err(MerkleError::TrailingBytes(available - expected));
+        }
+
+        let hashes = (0..count)
+            .map(|_| reader.digest::<D>())
+            .collect::<Result<_, _>>()?;
+        Ok(Self { hashes })
+    }
+}
+
+/// root of an empty subtree of the given height, panics above the table
+impl<D> Index<usize> for EmptyHashes<D> {
+    type Output = D;
+
+    fn index(&self, height: usize) -> &D {
+        &self.hashes[height]
+    }
+}
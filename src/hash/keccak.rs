@@ -0,0 +1,132 @@
+//! keccak-256 backend, compatible with solidity's `keccak256`
+//!
+//! leafs are hashed over their raw bytes, inner nodes over the 64 byte
+//! concatenation `left || right` of their children's digests.
+
+use super::{LeafHasher, MerkleHasher};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Keccak256;
+
+impl MerkleHasher for Keccak256 {
+    type Digest = [u8; 32];
+
+    fn hash_siblings(&self, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut data = [0; 64];
+        data[..32].copy_from_slice(left);
+        data[32..].copy_from_slice(right);
+        digest(&data)
+    }
+
+    fn hash_children(&self, children: &[[u8; 32]]) -> [u8; 32] {
+        digest(&children.concat())
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> LeafHasher<T> for Keccak256 {
+    fn hash_leaf(&self, leaf: &T) -> [u8; 32] {
+        digest(leaf.as_ref())
+    }
+}
+
+/// number of bytes absorbed per permutation
+const RATE: usize = 136;
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// rotation offsets, indexed by `x + 5 * y`
+const ROTATIONS: [u32; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// keccak-256 digest of the given bytes
+/// this is the original keccak padding used by ethereum, not sha3-256
+pub fn digest(data: &[u8]) -> [u8; 32] {
+    let mut state = [0u64; 25];
+
+    let mut chunks = data.chunks_exact(RATE);
+    for block in &mut chunks {
+        absorb(&mut state, block);
+    }
+
+    let rest = chunks.remainder();
+    let mut last = [0u8; RATE];
+    last[..rest.len()].copy_from_slice(rest);
+    last[rest.len()] ^= 0x01;
+    last[RATE - 1] ^= 0x80;
+    absorb(&mut state, &last);
+
+    let mut out = [0; 32];
+    for (chunk, lane) in out.chunks_exact_mut(8).zip(state) {
+        chunk.copy_from_slice(&lane.to_le_bytes());
+    }
+    out
+}
+
+fn absorb(state: &mut [u64; 25], block: &[u8]) {
+    for (lane, chunk) in state.iter_mut().zip(block.chunks_exact(8)) {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(chunk);
+        *lane ^= u64::from_le_bytes(bytes);
+    }
+    permute(state);
+}
+
+fn permute(a: &mut [u64; 25]) {
+    for rc in ROUND_CONSTANTS {
+        // theta
+        let mut c = [0u64; 5];
+        for x in 0..5 {
+            c[x] = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[x + 5 * y] ^= d;
+            }
+        }
+
+        // rho and pi
+        let mut b = [0u64; 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = a[x + 5 * y].rotate_left(ROTATIONS[x + 5 * y]);
+            }
+        }
+
+        // chi
+        for x in 0..5 {
+            for y in 0..5 {
+                a[x + 5 * y] = b[x + 5 * y] ^ (!b[(x + 1) % 5 + 5 * y] & b[(x + 2) % 5 + 5 * y]);
+            }
+        }
+
+        // iota
+        a[0] ^= rc;
+    }
+}
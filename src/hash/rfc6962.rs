@@ -0,0 +1,51 @@
+//! certificate transparency hashing as defined in RFC 6962
+//!
+//! sha-256 with domain separated leafs `H(0x00 || leaf)` and inner nodes
+//! `H(0x01 || left || right)`. combine with [`PaddingStrategy::PromoteOdd`]
+//! (or use [`MerkleTree::rfc6962`]) to get the RFC tree shape.
+//!
+//! [`PaddingStrategy::PromoteOdd`]: crate::PaddingStrategy::PromoteOdd
+//! [`MerkleTree::rfc6962`]: crate::MerkleTree::rfc6962
+
+use super::{
+    sha256::{digest, State},
+    LeafHasher, MerkleHasher,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rfc6962;
+
+impl MerkleHasher for Rfc6962 {
+    type Digest = [u8; 32];
+
+    fn hash_siblings(&self, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut state = State::new();
+        state.update(&[0x01]);
+        state.update(left);
+        state.update(right);
+        state.finalize()
+    }
+
+    fn hash_children(&self, children: &[[u8; 32]]) -> [u8; 32] {
+        let mut state = State::new();
+        state.update(&[0x01]);
+        for child in children {
+            state.update(child);
+        }
+        state.finalize()
+    }
+
+    /// the root of the empty tree, `MTH({}) = SHA-256()`
+    fn hash_padding(&self) -> [u8; 32] {
+        digest(b"")
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> LeafHasher<T> for Rfc6962 {
+    fn hash_leaf(&self, leaf: &T) -> [u8; 32] {
+        let mut state = State::new();
+        state.update(&[0x00]);
+        state.update(leaf.as_ref());
+        state.finalize()
+    }
+}
@@ -0,0 +1,217 @@
+//! sha-256 backend with 32 byte digests
+//!
+//! leafs are hashed over their raw bytes, inner nodes over the concatenation
+//! `left || right` of their children's digests.
+
+use alloc::vec::Vec;
+
+use super::{LeafHasher, MerkleHasher, StreamingDigest};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sha256;
+
+impl MerkleHasher for Sha256 {
+    type Digest = [u8; 32];
+
+    fn hash_siblings(&self, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut state = State::new();
+        state.update(left);
+        state.update(right);
+        state.finalize()
+    }
+
+    fn hash_children(&self, children: &[[u8; 32]]) -> [u8; 32] {
+        let mut state = State::new();
+        for child in children {
+            state.update(child);
+        }
+        state.finalize()
+    }
+
+    /// a pair is exactly one block, the block of padding after it is the
+    /// same for every pair and its message schedule is expanded only once
+    fn hash_pairs(&self, nodes: &[[u8; 32]]) -> Vec<[u8; 32]> {
+        nodes
+            .chunks_exact(2)
+            .map(|pair| {
+                let mut h = INITIAL;
+                rounds(&mut h, &schedule(pair.as_flattened()));
+                rounds(&mut h, &PAIR_PADDING);
+                to_bytes(h)
+            })
+            .collect()
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> LeafHasher<T> for Sha256 {
+    fn hash_leaf(&self, leaf: &T) -> [u8; 32] {
+        digest(leaf.as_ref())
+    }
+}
+
+/// sha-256 digest of the given bytes
+pub fn digest(data: &[u8]) -> [u8; 32] {
+    let mut state = State::new();
+    state.update(data);
+    state.finalize()
+}
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const INITIAL: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// streaming sha-256 state, `Hashing<State>` hashes like [`Sha256`]
+///
+/// [`Hashing`]: super::Hashing
+#[derive(Debug, Clone)]
+pub struct State {
+    h: [u32; 8],
+    block: [u8; 64],
+    filled: usize,
+    len: u64,
+}
+
+impl State {
+    pub fn new() -> Self {
+        Self {
+            h: INITIAL,
+            block: [0; 64],
+            filled: 0,
+            len: 0,
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.len += data.len() as u64;
+
+        while !data.is_empty() {
+            let take = (64 - self.filled).min(data.len());
+            self.block[self.filled..self.filled + take].copy_from_slice(&data[..take]);
+            self.filled += take;
+            data = &data[take..];
+
+            if self.filled == 64 {
+                compress(&mut self.h, &self.block);
+                self.filled = 0;
+            }
+        }
+    }
+
+    pub fn finalize(mut self) -> [u8; 32] {
+        let bits = self.len * 8;
+
+        self.block[self.filled] = 0x80;
+        self.block[self.filled + 1..].fill(0);
+        if self.filled >= 56 {
+            compress(&mut self.h, &self.block);
+            self.block.fill(0);
+        }
+        self.block[56..].copy_from_slice(&bits.to_be_bytes());
+        compress(&mut self.h, &self.block);
+        to_bytes(self.h)
+    }
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamingDigest for State {
+    type Output = [u8; 32];
+
+    fn update(&mut self, data: &[u8]) {
+        State::update(self, data);
+    }
+
+    fn finalize(self) -> [u8; 32] {
+        State::finalize(self)
+    }
+}
+
+fn to_bytes(h: [u32; 8]) -> [u8; 32] {
+    let mut out = [0; 32];
+    for (chunk, word) in out.chunks_exact_mut(4).zip(h) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+/// the message schedule of the block that pads a 64 byte message
+const PAIR_PADDING: [u32; 64] = {
+    let mut block = [0; 64];
+    block[0] = 0x80;
+    // 512 bits
+    block[62] = 0x02;
+    schedule(&block)
+};
+
+fn compress(h: &mut [u32; 8], block: &[u8; 64]) {
+    rounds(h, &schedule(block));
+}
+
+/// expand a block into the 64 words of its message schedule
+const fn schedule(block: &[u8]) -> [u32; 64] {
+    let mut w = [0u32; 64];
+    let mut i = 0;
+    while i < 16 {
+        w[i] = u32::from_be_bytes([
+            block[4 * i],
+            block[4 * i + 1],
+            block[4 * i + 2],
+            block[4 * i + 3],
+        ]);
+        i += 1;
+    }
+    while i < 64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+        i += 1;
+    }
+    w
+}
+
+fn rounds(h: &mut [u32; 8], w: &[u32; 64]) {
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = *h;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = hh
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+
+        hh = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    for (word, value) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+        *word = word.wrapping_add(value);
+    }
+}
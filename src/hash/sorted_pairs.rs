@@ -0,0 +1,47 @@
+//! order independent sibling hashing, the way openzeppelin's `MerkleProof.sol`
+//! and merkletreejs with `sortPairs` hash nodes
+
+use alloc::vec::Vec;
+
+use super::{compare, LeafHasher, MerkleHasher};
+
+/// wraps a backend so that siblings are hashed smaller one first, ordered by
+/// their encoded bytes
+///
+/// the hash of a node no longer depends on the side its children sit on, so
+/// proofs need nothing but the sibling hashes, see
+/// [`verify_sorted_pairs`](crate::verify::verify_sorted_pairs). merkletreejs
+/// carries the odd node of a level up unhashed, trees padded with
+/// [`PaddingStrategy::PromoteOdd`](crate::PaddingStrategy::PromoteOdd) have
+/// the same roots.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SortedPairs<S>(pub S);
+
+impl<S: MerkleHasher> MerkleHasher for SortedPairs<S> {
+    type Digest = S::Digest;
+
+    fn hash_siblings(&self, left: &S::Digest, right: &S::Digest) -> S::Digest {
+        if compare(left, right).is_gt() {
+            self.0.hash_siblings(right, left)
+        } else {
+            self.0.hash_siblings(left, right)
+        }
+    }
+
+    /// all children are sorted, not only pairs of them
+    fn hash_children(&self, children: &[S::Digest]) -> S::Digest {
+        let mut sorted: Vec<_> = children.to_vec();
+        sorted.sort_by(compare);
+        self.0.hash_children(&sorted)
+    }
+
+    fn hash_padding(&self) -> S::Digest {
+        self.0.hash_padding()
+    }
+}
+
+impl<T: ?Sized, S: LeafHasher<T>> LeafHasher<T> for SortedPairs<S> {
+    fn hash_leaf(&self, leaf: &T) -> S::Digest {
+        self.0.hash_leaf(leaf)
+    }
+}
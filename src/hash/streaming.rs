@@ -0,0 +1,93 @@
+//! a backend for any hash function that absorbs bytes and finishes into a
+//! fixed size digest
+//!
+//! [`StreamingDigest`] has the shape of RustCrypto's `Digest`: a default
+//! state, `update` and `finalize`. any such hash becomes a backend through
+//! [`Hashing`], leafs are hashed over their raw bytes and inner nodes over the
+//! concatenation `left || right` of their children's digests.
+
+use core::{fmt, marker::PhantomData};
+
+use super::{Digest, LeafHasher, MerkleHasher};
+
+/// a hash function fed in pieces
+pub trait StreamingDigest: Default {
+    /// the finished digest, a byte array like `[u8; 32]`
+    type Output: Digest + AsRef<[u8]>;
+
+    fn update(&mut self, data: &[u8]);
+
+    fn finalize(self) -> Self::Output;
+
+    /// the digest of `data` in one call
+    fn digest(data: &[u8]) -> Self::Output {
+        let mut state = Self::default();
+        state.update(data);
+        state.finalize()
+    }
+}
+
+/// the backend of the hash function `D`, e.g. `Hashing<sha256::State>` hashes
+/// like [`Sha256`](super::Sha256)
+pub struct Hashing<D>(PhantomData<fn() -> D>);
+
+impl<D> Hashing<D> {
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+// derives would require `D` itself to be cloneable, comparable and printable
+
+impl<D> fmt::Debug for Hashing<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Hashing<{}>", core::any::type_name::<D>())
+    }
+}
+
+impl<D> Clone for Hashing<D> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<D> Copy for Hashing<D> {}
+
+impl<D> Default for Hashing<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D> PartialEq for Hashing<D> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<D> Eq for Hashing<D> {}
+
+impl<D: StreamingDigest> MerkleHasher for Hashing<D> {
+    type Digest = D::Output;
+
+    fn hash_siblings(&self, left: &D::Output, right: &D::Output) -> D::Output {
+        let mut state = D::default();
+        state.update(left.as_ref());
+        state.update(right.as_ref());
+        state.finalize()
+    }
+
+    fn hash_children(&self, children: &[D::Output]) -> D::Output {
+        let mut state = D::default();
+        for child in children {
+            state.update(child.as_ref());
+        }
+        state.finalize()
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized, D: StreamingDigest> LeafHasher<T> for Hashing<D> {
+    fn hash_leaf(&self, leaf: &T) -> D::Output {
+        D::digest(leaf.as_ref())
+    }
+}
@@ -0,0 +1,278 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{
+    error::{CasError, MerkleError},
+    hash::{DefaultBuildHasher, Digest, EmptyHashes, LeafHasher, MerkleHasher},
+    proof::{MerkleProof, RangeProof},
+    root::{LeafHash, Root},
+    tree::{
+        build_padded_levels, diff_levels, positions, proof_path, range_decommitments,
+        refresh_levels, update_path, PaddingStrategy,
+    },
+    OnceLock,
+};
+
+pub(crate) mod file;
+
+/// a merkle tree that only stores the hashes of its leafs
+///
+/// built with [`MerkleTree::finalize`] once the values are no longer needed,
+/// roots and proofs equal the ones of the tree it was built from
+///
+/// [`MerkleTree::finalize`]: crate::MerkleTree::finalize
+pub struct HashedMerkleTree<S: MerkleHasher = DefaultBuildHasher> {
+    /// hashes of the stored values, without padding
+    leafs: Vec<S::Digest>,
+    /// the padding leaf of the hasher
+    empty: S::Digest,
+    hasher: S,
+    padding: PaddingStrategy,
+    levels: OnceLock<Vec<Vec<S::Digest>>>,
+    /// roots of empty subtrees hashed while updating the cached levels
+    empties: Option<EmptyHashes<S::Digest>>,
+}
+
+impl<S: MerkleHasher> HashedMerkleTree<S> {
+    pub(crate) fn new(
+        leafs: Vec<S::Digest>,
+        empty: S::Digest,
+        hasher: S,
+        padding: PaddingStrategy,
+        levels: OnceLock<Vec<Vec<S::Digest>>>,
+    ) -> Self {
+        Self {
+            leafs,
+            empty,
+            hasher,
+            padding,
+            levels,
+            empties: None,
+        }
+    }
+
+    /// change how the tree is completed when its size is not a power of two
+    pub fn with_padding(mut self, padding: PaddingStrategy) -> Self {
+        self.padding = padding;
+        self.levels.take();
+        self
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hasher
+    }
+
+    pub fn padding(&self) -> PaddingStrategy {
+        self.padding
+    }
+
+    /// hash of the padding leaf
+    #[cfg(feature = "std")]
+    pub(crate) fn empty(&self) -> S::Digest {
+        self.empty
+    }
+
+    /// number of leaf hashes stored, padding excluded
+    pub fn len(&self) -> usize {
+        self.leafs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leafs.is_empty()
+    }
+
+    /// append the hash of a leaf, it has to be hashed with the tree's hasher
+    /// cached levels are kept, only the path of the new leaf is hashed
+    pub fn add_hash(&mut self, leaf: S::Digest) {
+        self.leafs.push(leaf);
+        if let Some(mut levels) = self.levels.take() {
+            let len = self.leafs.len();
+            levels[0].truncate(len - 1);
+            levels[0].push(leaf);
+            let (empties, empty) = (&mut self.empties, || self.empty);
+            refresh_levels(
+                &self.hasher,
+                self.padding,
+                &mut levels,
+                len,
+                len - 1,
+                empties,
+                empty,
+            );
+            self.levels = OnceLock::from(levels);
+        }
+    }
+
+    /// replace the leaf hash at `index` with `new` only if it is still
+    /// `expected`, see [`MerkleTree::compare_and_update`]
+    /// cached levels are kept, only the path of the leaf is hashed again
+    ///
+    /// [`MerkleTree::compare_and_update`]: crate::MerkleTree::compare_and_update
+    pub fn compare_and_update_hash(
+        &mut self,
+        index: usize,
+        expected: &S::Digest,
+        new: S::Digest,
+    ) -> Result<(), CasError<S::Digest>> {
+        let len = self.leafs.len();
+        let current = self
+            .leafs
+            .get_mut(index)
+            .ok_or(CasError::IndexOutOfBounds { index, len })?;
+        if !current.ct_eq(expected) {
+            return Err(CasError::Mismatch {
+                index,
+                actual: *current,
+            });
+        }
+        *current = new;
+        if let Some(levels) = self.levels.get_mut() {
+            update_path(&self.hasher, self.padding, levels, index, new);
+        }
+        Ok(())
+    }
+
+    /// index of the first stored leaf hash equal to the hash of `value`
+    pub fn position_of<T: ?Sized>(&self, value: &T) -> Option<usize>
+    where
+        S: LeafHasher<T>,
+    {
+        let hash = self.hasher.hash_leaf(value);
+        self.leafs.iter().position(|leaf| *leaf == hash)
+    }
+
+    /// indices of all stored leaf hashes equal to the hash of `value`
+    pub fn positions_of<T: ?Sized>(&self, value: &T) -> Vec<usize>
+    where
+        S: LeafHasher<T>,
+    {
+        positions(&self.leafs, self.hasher.hash_leaf(value))
+    }
+
+    pub fn root(&self) -> Root<S::Digest> {
+        let levels = self.levels();
+        Root(levels[levels.len() - 1][0])
+    }
+
+    /// proof for the leaf at `index`, see
+    /// [`MerkleTree::get_proof`](crate::MerkleTree::get_proof)
+    pub fn get_proof(&self, index: usize) -> Result<MerkleProof<S>, MerkleError>
+    where
+        S: Clone,
+    {
+        let len = self.leafs.len();
+        if index >= len {
+            return Err(MerkleError::IndexOutOfBounds { index, len });
+        }
+        self.proof_from(index)
+    }
+
+    /// proof for the padding leaf at `index`, see
+    /// [`MerkleTree::get_padding_proof`](crate::MerkleTree::get_padding_proof)
+    pub fn get_padding_proof(&self, index: usize) -> Result<MerkleProof<S>, MerkleError>
+    where
+        S: Clone,
+    {
+        let len = self.leafs.len();
+        if index < len {
+            return Err(MerkleError::NotPadding { index, len });
+        }
+        self.proof_from(index)
+    }
+
+    fn proof_from(&self, index: usize) -> Result<MerkleProof<S>, MerkleError>
+    where
+        S: Clone,
+    {
+        let levels = self.levels();
+        let (leaf, path) = proof_path(levels, self.padding, index)?;
+
+        Ok(MerkleProof {
+            path,
+            root: Root(levels[levels.len() - 1][0]),
+            leaf: LeafHash(leaf),
+            index,
+            width: levels[0].len(),
+            padding: self.padding,
+            hasher: self.hasher.clone(),
+        })
+    }
+
+    /// proof for the node at `index` of `level`, see
+    /// [`MerkleTree::get_subtree_proof`](crate::MerkleTree::get_subtree_proof)
+    pub fn get_subtree_proof(
+        &self,
+        level: usize,
+        index: usize,
+    ) -> Result<MerkleProof<S>, MerkleError>
+    where
+        S: Clone,
+    {
+        let levels = self.levels();
+        if level >= levels.len() {
+            return Err(MerkleError::IndexOutOfBounds {
+                index: level,
+                len: levels.len(),
+            });
+        }
+        let levels = &levels[level..];
+        let (leaf, path) = proof_path(levels, self.padding, index)?;
+        Ok(MerkleProof {
+            path,
+            root: Root(levels[levels.len() - 1][0]),
+            leaf: LeafHash(leaf),
+            index,
+            width: levels[0].len(),
+            padding: self.padding,
+            hasher: self.hasher.clone(),
+        })
+    }
+
+    /// indices of the leaf hashes that differ from the ones of `other`, see
+    /// [`MerkleTree::diff`](crate::MerkleTree::diff)
+    pub fn diff(&self, other: &Self) -> Result<Vec<usize>, MerkleError> {
+        if self.len() != other.len() {
+            return Err(MerkleError::LengthMismatch {
+                len: self.len(),
+                other: other.len(),
+            });
+        }
+        if self.padding != other.padding {
+            return Err(MerkleError::PaddingMismatch {
+                padding: self.padding,
+                other: other.padding,
+            });
+        }
+        Ok(diff_levels(self.levels(), other.levels(), self.len()))
+    }
+
+    /// proof for the contiguous leafs of `range`, see
+    /// [`MerkleTree::get_range_proof`](crate::MerkleTree::get_range_proof)
+    pub fn get_range_proof(&self, range: Range<usize>) -> Result<RangeProof<S>, MerkleError>
+    where
+        S: Clone,
+    {
+        let levels = self.levels();
+        Ok(RangeProof {
+            decommitments: range_decommitments(levels, self.leafs.len(), range)?,
+            width: levels[0].len(),
+            padding: self.padding,
+            hasher: self.hasher.clone(),
+        })
+    }
+
+    /// the roots of empty subtrees up to the height of the tree, for
+    /// [compact proofs](MerkleProof::compact)
+    pub fn empty_hashes(&self) -> EmptyHashes<S::Digest> {
+        EmptyHashes::new(&self.hasher, self.empty, self.levels().len() - 1)
+    }
+
+    /// the cached levels of the tree, leafs first and root last
+    pub(crate) fn levels(&self) -> &[Vec<S::Digest>] {
+        self.levels.get_or_init(|| {
+            build_padded_levels(&self.hasher, self.padding, self.leafs.clone(), || {
+                self.empty
+            })
+        })
+    }
+}
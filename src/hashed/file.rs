@@ -0,0 +1,220 @@
+//! binary file format of hashed trees, so that large trees are not rebuilt
+//! from their values on every start
+//!
+//! the layout is
+//! - the magic bytes `MRKL` and the format version as one byte
+//! - the padding strategy as one byte, encoded like in proofs
+//! - the length of a digest as an unsigned LEB128 varint
+//! - the number of stored leaf hashes as an unsigned LEB128 varint
+//! - `1` if the cached levels follow instead of the leaf hashes, `0` if not
+//! - the padding leaf and the hash of two padding leafs as siblings, which
+//!   tells trees of different hash backends apart
+//! - the leaf hashes, or every level from the padded leafs up to the root
+//! - the 64 bit FNV-1a hash of everything before it, big endian
+//!
+//! digests are written with [`Digest::write_bytes`].
+
+use alloc::{vec, vec::Vec};
+use core::hash::Hasher;
+
+use super::HashedMerkleTree;
+use crate::{
+    error::MerkleError,
+    hash::{Digest, FnvHasher, MerkleHasher},
+    proof::bytes::{padding_byte, write_varint, Reader},
+    tree::{checked_width, PaddingStrategy, MAX_LEAFS},
+    OnceLock,
+};
+
+const MAGIC: &[u8; 4] = b"MRKL";
+const VERSION: u8 = 1;
+
+impl<S: MerkleHasher> HashedMerkleTree<S> {
+    /// encode the leaf hashes into the file format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode(&self.hasher, self.padding, &self.leafs, &self.empty, None)
+    }
+
+    /// like [`to_bytes`] but with all cached levels, so that loading the tree
+    /// hashes nothing
+    ///
+    /// [`to_bytes`]: HashedMerkleTree::to_bytes
+    pub fn to_bytes_with_levels(&self) -> Vec<u8> {
+        let levels = self.levels();
+        encode(
+            &self.hasher,
+            self.padding,
+            &self.leafs,
+            &self.empty,
+            Some(levels),
+        )
+    }
+
+    /// decode a tree from the file format
+    /// files of other versions, of another hash backend or with a wrong
+    /// checksum are rejected
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MerkleError>
+    where
+        S: Default,
+    {
+        Self::from_bytes_with_max(bytes, MAX_LEAFS)
+    }
+
+    /// like [`from_bytes`] but a tree wider than `max_leafs` leafs, padding
+    /// included, is [`TooLarge`](MerkleError::TooLarge)
+    /// the size is checked before the hashes are read or anything is
+    /// allocated for them
+    ///
+    /// [`from_bytes`]: HashedMerkleTree::from_bytes
+    pub fn from_bytes_with_max(bytes: &[u8], max_leafs: usize) -> Result<Self, MerkleError>
+    where
+        S: Default,
+    {
+        if bytes.len() < MAGIC.len() + 1 || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(MerkleError::InvalidEncoding("not a merkle tree file"));
+        }
+        if bytes[MAGIC.len()] != VERSION {
+            return Err(MerkleError::InvalidEncoding("unsupported file version"));
+        }
+        if bytes.len() < MAGIC.len() + 1 + 8 {
+            return Err(MerkleError::Truncated {
+                needed: MAGIC.len() + 1 + 8,
+                available: bytes.len(),
+            });
+        }
+        let (body, checksum) = bytes.split_at(bytes.len() - 8);
+        if fnv(body).to_be_bytes() != checksum {
+            return Err(MerkleError::InvalidEncoding("checksum mismatch"));
+        }
+
+        let mut reader = Reader {
+            bytes: &body[MAGIC.len() + 1..],
+        };
+        let padding = reader.padding()?;
+        if reader.varint()? != S::Digest::LEN as u64 {
+            return Err(MerkleError::InvalidEncoding(
+                "file was written with another hash backend",
+            ));
+        }
+        let len = usize::try_from(reader.varint()?)
+            .map_err(|_| MerkleError::InvalidEncoding("tree size is too large"))?;
+        let with_levels = match reader.take(1)?[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(MerkleError::InvalidEncoding("unknown levels flag")),
+        };
+
+        let hasher = S::default();
+        let empty = reader.digest::<S::Digest>()?;
+        if reader.digest::<S::Digest>()? != hasher.hash_siblings(&empty, &empty) {
+            return Err(MerkleError::InvalidEncoding(
+                "file was written with another hash backend",
+            ));
+        }
+
+        let width = checked_width(padding, len, max_leafs)?;
+        let mut sizes = vec![if with_levels { width } else { len }];
+        while with_levels && sizes[sizes.len() - 1] > 1 {
+            sizes.push(sizes[sizes.len() - 1].div_ceil(2));
+        }
+        // check the size before allocating anything for the hashes
+        let expected = sizes
+            .iter()
+            .try_fold(0usize, |total, size| total.checked_add(*size))
+            .and_then(|hashes| hashes.checked_mul(S::Digest::LEN))
+            .ok_or(MerkleError::InvalidEncoding("tree size is too large"))?;
+        let available = reader.bytes.len();
+        if available < expected {
+            return Err(MerkleError::Truncated {
+                needed: expected,
+                available,
+            });
+        }
+        if available > expected {
+            return Err(MerkleError::TrailingBytes(available - expected));
+        }
+
+        let mut levels = Vec::with_capacity(sizes.len());
+        for size in sizes {
+            let level = (0..size)
+                .map(|_| reader.digest::<S::Digest>())
+                .collect::<Result<Vec<_>, _>>()?;
+            levels.push(level);
+        }
+
+        let leafs = levels[0][..len].to_vec();
+        let cache = OnceLock::new();
+        if with_levels {
+            let _ = cache.set(levels);
+        }
+        Ok(HashedMerkleTree::new(leafs, empty, hasher, padding, cache))
+    }
+
+    /// write the leaf hashes to the file at `path`
+    #[cfg(feature = "std")]
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_bytes())
+    }
+
+    /// like [`save`] but with all cached levels
+    ///
+    /// [`save`]: HashedMerkleTree::save
+    #[cfg(feature = "std")]
+    pub fn save_with_levels(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_bytes_with_levels())
+    }
+
+    /// read a tree written by [`save`], a malformed file is an
+    /// [`InvalidData`](std::io::ErrorKind::InvalidData) error wrapping the
+    /// [`MerkleError`]
+    ///
+    /// [`save`]: HashedMerkleTree::save
+    #[cfg(feature = "std")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<Self>
+    where
+        S: Default,
+    {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(&bytes)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+}
+
+/// the file format over the given leaf hashes and optionally all levels
+pub(crate) fn encode<S: MerkleHasher>(
+    hasher: &S,
+    padding: PaddingStrategy,
+    leafs: &[S::Digest],
+    empty: &S::Digest,
+    levels: Option<&[Vec<S::Digest>]>,
+) -> Vec<u8> {
+    let hashes = levels.map_or(leafs.len(), |levels| levels.iter().map(Vec::len).sum());
+    let mut out = Vec::with_capacity(32 + (hashes + 2) * S::Digest::LEN);
+
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    out.push(padding_byte(padding));
+    write_varint(&mut out, S::Digest::LEN as u64);
+    write_varint(&mut out, leafs.len() as u64);
+    out.push(u8::from(levels.is_some()));
+    empty.write_bytes(&mut out);
+    hasher.hash_siblings(empty, empty).write_bytes(&mut out);
+
+    match levels {
+        Some(levels) => levels
+            .iter()
+            .flatten()
+            .for_each(|hash| hash.write_bytes(&mut out)),
+        None => leafs.iter().for_each(|hash| hash.write_bytes(&mut out)),
+    }
+
+    let checksum = fnv(&out);
+    out.extend_from_slice(&checksum.to_be_bytes());
+    out
+}
+
+fn fnv(bytes: &[u8]) -> u64 {
+    let mut hasher = FnvHasher::default();
+    hasher.write(bytes);
+    hasher.finish()
+}
@@ -0,0 +1,47 @@
+//! hex form of digests, the lowercase hex of [`Digest::write_bytes`]
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+
+use crate::hash::Digest;
+
+pub(crate) fn write<D: Digest>(out: &mut impl fmt::Write, digest: &D) -> fmt::Result {
+    let mut bytes = Vec::with_capacity(D::LEN);
+    digest.write_bytes(&mut bytes);
+    for byte in bytes {
+        write!(out, "{byte:02x}")?;
+    }
+    Ok(())
+}
+
+/// parse exactly `2 * D::LEN` hex digits of either case
+/// the error completes a sentence about the input, e.g. `has 3 hex digits, …`
+pub(crate) fn parse<D: Digest>(digits: &str) -> Result<D, String> {
+    if digits.len() != 2 * D::LEN {
+        return Err(format!(
+            "has {} hex digits, a digest has {}",
+            digits.len(),
+            2 * D::LEN
+        ));
+    }
+
+    let nibble = |digit: u8| {
+        (digit as char)
+            .to_digit(16)
+            .ok_or_else(|| format!("contains the non hex digit {:?}", digit as char))
+    };
+    let bytes = digits
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| Ok((nibble(pair[0])? << 4 | nibble(pair[1])?) as u8))
+        .collect::<Result<Vec<_>, String>>()?;
+    D::from_slice(&bytes).ok_or_else(|| String::from("is not a valid digest"))
+}
+
+/// the first `digits` hex digits of a digest, for labels meant for humans
+pub(crate) fn short<D: Digest>(digest: &D, digits: usize) -> String {
+    let mut out = String::new();
+    let _ = write(&mut out, digest);
+    out.truncate(digits);
+    out
+}
@@ -0,0 +1,130 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::{
+    hash::{DefaultBuildHasher, EmptyHashes, LeafHasher, MerkleHasher},
+    root::Root,
+};
+
+/// an append only merkle tree that only keeps the frontier of the tree
+///
+/// for every complete subtree on the left edge of the tree the frontier holds
+/// its root, so an append only hashes `O(log n)` siblings and [`root`] combines
+/// the frontier with the hashes of empty subtrees.
+/// the roots equal the ones of a [`MerkleTree`] padded with default values.
+///
+/// [`root`]: IncrementalMerkleTree::root
+/// [`MerkleTree`]: crate::MerkleTree
+pub struct IncrementalMerkleTree<T, S: MerkleHasher = DefaultBuildHasher> {
+    /// `frontier[h]` is the root of the complete subtree of height `h`, it is
+    /// only valid if bit `h` of `len` is set or `len == 2^h`
+    frontier: Vec<S::Digest>,
+    /// roots of subtrees made of padding, one level above the frontier
+    zeros: EmptyHashes<S::Digest>,
+    len: usize,
+    hasher: S,
+    values: PhantomData<fn(&T)>,
+}
+
+impl<T> IncrementalMerkleTree<T>
+where
+    T: Default,
+    DefaultBuildHasher: LeafHasher<T>,
+{
+    pub fn new() -> Self {
+        Self::with_hasher(DefaultBuildHasher::default())
+    }
+}
+
+impl<T> Default for IncrementalMerkleTree<T>
+where
+    T: Default,
+    DefaultBuildHasher: LeafHasher<T>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S: MerkleHasher> IncrementalMerkleTree<T, S> {
+    pub fn with_hasher(hasher: S) -> Self {
+        Self {
+            frontier: Vec::new(),
+            zeros: EmptyHashes::new(&hasher, hasher.hash_padding(), 0),
+            len: 0,
+            hasher,
+            values: PhantomData,
+        }
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hasher
+    }
+
+    /// number of values appended so far
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// append the hash of a leaf, it has to be hashed with the tree's hasher
+    /// at most one node per level is hashed
+    pub fn add_hash(&mut self, leaf: S::Digest) {
+        let mut node = leaf;
+        self.len += 1;
+
+        let mut size = self.len;
+        let mut height = 0;
+        while size & 1 == 0 {
+            node = self.hasher.hash_siblings(&self.frontier[height], &node);
+            size >>= 1;
+            height += 1;
+        }
+
+        if height == self.frontier.len() {
+            self.frontier.push(node);
+        } else {
+            self.frontier[height] = node;
+        }
+
+        // keep the empty subtree hashes one level above the frontier
+        self.zeros.extend_to(&self.hasher, self.frontier.len());
+    }
+
+    /// the root of the tree padded to the next power of two
+    pub fn root(&self) -> Root<S::Digest> {
+        let depth = self.len.next_power_of_two().trailing_zeros() as usize;
+        if self.len.is_power_of_two() {
+            return Root(self.frontier[depth]);
+        }
+
+        // walk up from the first empty slot, everything right of it is padding
+        let mut node = self.zeros[0];
+        let mut size = self.len;
+        for height in 0..depth {
+            node = if size & 1 == 1 {
+                self.hasher.hash_siblings(&self.frontier[height], &node)
+            } else {
+                self.hasher.hash_siblings(&node, &self.zeros[height])
+            };
+            size >>= 1;
+        }
+
+        Root(node)
+    }
+}
+
+impl<T, S> IncrementalMerkleTree<T, S>
+where
+    T: Default,
+    S: LeafHasher<T>,
+{
+    /// append a value, hashing at most one node per level
+    pub fn add(&mut self, value: T) {
+        let leaf = self.hasher.hash_leaf(&value);
+        self.add_hash(leaf);
+    }
+}
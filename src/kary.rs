@@ -0,0 +1,219 @@
+use alloc::{vec, vec::Vec};
+use core::hash::Hash;
+
+use crate::{
+    error::MerkleError,
+    hash::{DefaultBuildHasher, Digest, LeafHasher, MerkleHasher},
+    root::{LeafHash, Root},
+    OnceLock,
+};
+
+/// a merkle tree whose nodes have `arity` children
+///
+/// the leafs are padded with the padding leaf of the hasher up to the next
+/// power of the arity and every node is hashed with [`MerkleHasher::hash_children`].
+/// with an arity of two the roots equal the ones of a [`MerkleTree`].
+///
+/// [`MerkleTree`]: crate::MerkleTree
+pub struct KaryMerkleTree<T, S: MerkleHasher = DefaultBuildHasher> {
+    values: Vec<T>,
+    arity: usize,
+    hasher: S,
+    levels: OnceLock<Vec<Vec<S::Digest>>>,
+}
+
+/// one step of a k-ary proof, the other children of the node above
+#[derive(Debug)]
+pub struct KaryStep<D = u64> {
+    /// position of the node among its siblings
+    pub position: usize,
+    /// the `arity - 1` siblings in order, without the node itself
+    pub siblings: Vec<D>,
+}
+
+/// proof that leaf is included at `index` in a k-ary tree with the given root
+#[derive(Debug)]
+pub struct KaryProof<S: MerkleHasher = DefaultBuildHasher> {
+    pub path: Vec<KaryStep<S::Digest>>,
+    pub root: Root<S::Digest>,
+    pub leaf: LeafHash<S::Digest>,
+    pub index: usize,
+    /// number of values of the tree, padding excluded, the length of the
+    /// path has to agree with it. unlike [`MerkleProof::width`] this is not
+    /// the padded number of leafs
+    ///
+    /// [`MerkleProof::width`]: crate::MerkleProof::width
+    pub len: usize,
+    pub arity: usize,
+    pub hasher: S,
+}
+
+impl<T: Hash> KaryMerkleTree<T> {
+    /// build a tree with `arity` children per node, an arity smaller than
+    /// two is [`InvalidArity`](MerkleError::InvalidArity)
+    pub fn with_arity(values: Vec<T>, arity: usize) -> Result<Self, MerkleError> {
+        Self::with_hasher(values, arity, DefaultBuildHasher::default())
+    }
+}
+
+impl<T, S: MerkleHasher> KaryMerkleTree<T, S> {
+    /// an arity smaller than two is [`InvalidArity`](MerkleError::InvalidArity)
+    pub fn with_hasher(values: Vec<T>, arity: usize, hasher: S) -> Result<Self, MerkleError> {
+        if arity < 2 {
+            return Err(MerkleError::InvalidArity(arity));
+        }
+        Ok(Self {
+            values,
+            arity,
+            hasher,
+            levels: OnceLock::new(),
+        })
+    }
+
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+}
+
+impl<T, S> KaryMerkleTree<T, S>
+where
+    T: Default,
+    S: LeafHasher<T> + Clone,
+{
+    pub fn add(&mut self, value: T) {
+        self.values.push(value);
+        self.levels.take();
+    }
+
+    /// the hashed values padded up to the next power of the arity
+    pub fn leafs(&self) -> &[S::Digest] {
+        &self.levels()[0]
+    }
+
+    pub fn root(&self) -> Root<S::Digest> {
+        let levels = self.levels();
+        Root(levels[levels.len() - 1][0])
+    }
+
+    /// proof for the value at `index`, padding has no proofs
+    pub fn get_proof(&self, index: usize) -> Result<KaryProof<S>, MerkleError> {
+        let len = self.values.len();
+        if index >= len {
+            return Err(MerkleError::IndexOutOfBounds { index, len });
+        }
+        let levels = self.levels();
+        let leaf = levels[0][index];
+
+        let mut path = Vec::with_capacity(levels.len() - 1);
+        let mut node = index;
+        for hashes in &levels[..levels.len() - 1] {
+            let first = node - node % self.arity;
+            let siblings = (first..first + self.arity)
+                .filter(|&sibling| sibling != node)
+                .map(|sibling| hashes[sibling])
+                .collect();
+            path.push(KaryStep {
+                position: node % self.arity,
+                siblings,
+            });
+            node /= self.arity;
+        }
+
+        Ok(KaryProof {
+            path,
+            root: self.root(),
+            leaf: LeafHash(leaf),
+            index,
+            len,
+            arity: self.arity,
+            hasher: self.hasher.clone(),
+        })
+    }
+
+    /// the cached levels of the tree, leafs first and root last
+    fn levels(&self) -> &[Vec<S::Digest>] {
+        self.levels.get_or_init(|| {
+            let mut size = 1;
+            while size < self.values.len() {
+                size *= self.arity;
+            }
+
+            let mut leafs: Vec<_> = self
+                .values
+                .iter()
+                .map(|value| self.hasher.hash_leaf(value))
+                .collect();
+            if size > leafs.len() {
+                leafs.resize(size, self.hasher.hash_padding());
+            }
+
+            let mut levels = vec![leafs];
+            while levels[levels.len() - 1].len() > 1 {
+                let parents = levels[levels.len() - 1]
+                    .chunks(self.arity)
+                    .map(|children| self.hasher.hash_children(children))
+                    .collect();
+                levels.push(parents);
+            }
+            levels
+        })
+    }
+}
+
+impl<S: MerkleHasher> KaryProof<S> {
+    /// check that the path leads from the leaf to the root of the proof
+    #[deprecated(note = "use `KaryProof::verify_against` with a trusted root")]
+    pub fn verify(&self) -> bool {
+        self.verify_against(&self.root)
+    }
+
+    /// check that the path leads from the leaf to a root the verifier
+    /// trusts and that the positions of the steps agree with the index and
+    /// its length with the number of values of the tree, the root stored in
+    /// the proof is ignored
+    pub fn verify_against(&self, root: &Root<S::Digest>) -> bool {
+        if self.arity < 2 || self.index >= self.len {
+            return false;
+        }
+        if path_len(self.len, self.arity) != Some(self.path.len()) {
+            return false;
+        }
+
+        let mut node = self.index;
+        let mut acc = self.leaf.0;
+        let mut children = Vec::with_capacity(self.arity);
+        for step in &self.path {
+            if step.position != node % self.arity || step.siblings.len() != self.arity - 1 {
+                return false;
+            }
+
+            children.clear();
+            children.extend_from_slice(&step.siblings[..step.position]);
+            children.push(acc);
+            children.extend_from_slice(&step.siblings[step.position..]);
+            acc = self.hasher.hash_children(&children);
+            node /= self.arity;
+        }
+
+        node == 0 && acc.ct_eq(&root.0)
+    }
+
+    /// like [`verify_against`] but for a tree of `len` values the verifier
+    /// expects
+    ///
+    /// [`verify_against`]: KaryProof::verify_against
+    pub fn verify_with_len(&self, root: &Root<S::Digest>, len: usize) -> bool {
+        self.len == len && self.verify_against(root)
+    }
+}
+
+/// number of levels above the leafs of a tree of `len` values padded to a
+/// power of `arity`, `None` if the padded width overflows
+fn path_len(len: usize, arity: usize) -> Option<usize> {
+    let (mut size, mut levels) = (1usize, 0);
+    while size < len {
+        size = size.checked_mul(arity)?;
+        levels += 1;
+    }
+    Some(levels)
+}
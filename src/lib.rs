@@ -0,0 +1,83 @@
+//! a simple merkle tree
+//!
+//! build a tree out of a list of values, generate proofs that a value is
+//! contained in it and verify them.
+//!
+//! without the default `std` feature the crate only needs `alloc`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use core::cell::OnceCell as OnceLock;
+/// the level caches are shareable between threads where std is available
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "std")]
+pub mod audit;
+#[cfg(feature = "std")]
+pub mod chunked;
+#[cfg(feature = "std")]
+pub mod concurrent;
+#[cfg(feature = "disk")]
+pub mod disk;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod forest;
+pub mod full;
+pub mod hash;
+pub mod hashed;
+mod hex;
+pub mod incremental;
+pub mod kary;
+pub mod map;
+pub mod mmr;
+#[cfg(feature = "parallel")]
+mod parallel;
+pub mod proof;
+pub mod root;
+pub mod salted;
+pub mod set;
+pub mod sorted;
+pub mod sparse;
+#[cfg(feature = "async")]
+pub mod stream;
+pub mod tree;
+pub mod verify;
+#[cfg(feature = "std")]
+pub mod writer;
+
+#[cfg(feature = "std")]
+pub use audit::{verify_audit, verify_audit_with_hasher, AuditReport};
+#[cfg(feature = "std")]
+pub use chunked::ChunkedMerkleTree;
+#[cfg(feature = "std")]
+pub use concurrent::ConcurrentMerkleTree;
+#[cfg(feature = "disk")]
+pub use disk::DiskMerkleTree;
+pub use error::{CasError, MerkleError, ProofError};
+pub use forest::{ChainedProof, MerkleForest};
+pub use full::FullMerkleTree;
+pub use hash::EmptyHashes;
+pub use hashed::HashedMerkleTree;
+pub use incremental::IncrementalMerkleTree;
+pub use kary::{KaryMerkleTree, KaryProof, KaryStep};
+pub use map::{KeyedProof, MerkleMap};
+pub use mmr::{Mmr, MmrProof};
+#[cfg(feature = "std")]
+pub use proof::ProofReader;
+pub use proof::{
+    CompactProof, CompactStep, ConsistencyProof, Direction, MerkleMultiProof, MerkleProof,
+    PackedPath, RangeProof, Step, UpdateProof,
+};
+pub use root::{LeafHash, Root};
+pub use salted::{Salt, SaltedMerkleTree, SaltedProof};
+pub use set::{Duplicates, MerkleSet, ValueProof};
+pub use sorted::{SetProof, SortedMerkleTree};
+pub use sparse::{SparseMerkleProof, SparseMerkleTree};
+pub use tree::{Checkpoint, MerkleTree, PaddingStrategy};
+#[cfg(feature = "std")]
+pub use writer::MerkleWriter;
@@ -1,197 +1,183 @@
-// array of values
-// root() -> calculate merke root
-// proof(index) -> return proof for index
-
 use std::{
-    collections::VecDeque,
-    hash::{DefaultHasher, Hash, Hasher},
+    env,
+    fs::File,
+    io::{self, BufRead, BufReader, Read},
+    num::NonZeroUsize,
+    process::ExitCode,
 };
 
-use eyre::OptionExt;
+use eyre::{bail, eyre, WrapErr};
+use merkle::{
+    hash::{LeafHasher, Sha256},
+    HashedMerkleTree, MerkleProof, MerkleTree, Root,
+};
 
-#[derive(Debug)]
-enum Direction {
-    Left,
-    Right,
-}
+const USAGE: &str = "usage:
+  merkle root <file> [--chunk-size <bytes>]
+  merkle proof <file> --index <n> [--chunk-size <bytes>]
+  merkle verify --root <hex> --proof <proof.json>
 
-#[derive(Debug)]
-struct Step {
-    direction: Direction,
-    value: u64,
-}
+leafs are the lines of the file, or chunks of the given size, hashed with sha-256";
 
-/// proof that leaf is included in a tree with the given root
-#[derive(Debug)]
-pub struct MerkleProof {
-    path: Vec<Step>,
-    root: u64,
-    leaf: u64,
-}
-
-pub struct MerkleTree<T> {
-    values: Vec<T>,
+fn main() -> ExitCode {
+    match run(env::args().skip(1).collect()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            ExitCode::FAILURE
+        }
+    }
 }
 
-impl<T> From<Vec<T>> for MerkleTree<T>
-where
-    T: Hash + Clone,
-{
-    fn from(values: Vec<T>) -> Self {
-        Self { values }
+fn run(args: Vec<String>) -> eyre::Result<()> {
+    let mut args = Args::new(args);
+    match args.command()?.as_str() {
+        "root" => {
+            let tree = read_tree(&mut args)?;
+            println!("{}", tree.root());
+        }
+        "proof" => {
+            let index = args
+                .number("--index")?
+                .ok_or_else(|| eyre!("missing --index"))?;
+            let tree = read_tree(&mut args)?;
+            // padding leafs have proofs too, but they are not part of the input
+            if index >= tree.len() {
+                bail!("index {index} is out of range for {} leafs", tree.len());
+            }
+            let proof = tree.get_proof(index)?;
+            println!("{}", proof.to_json());
+        }
+        "verify" => {
+            let root = args
+                .option("--root")?
+                .ok_or_else(|| eyre!("missing --root"))?;
+            let path = args
+                .option("--proof")?
+                .ok_or_else(|| eyre!("missing --proof"))?;
+            args.finish()?;
+
+            let root = root.parse::<Root<[u8; 32]>>().wrap_err("invalid --root")?;
+            let json =
+                std::fs::read_to_string(&path).wrap_err_with(|| format!("reading {path}"))?;
+            let proof = MerkleProof::<Sha256>::from_json(&json)
+                .wrap_err_with(|| format!("parsing {path}"))?;
+            if !proof.verify_against(&root) {
+                bail!("proof does not verify against root {root}");
+            }
+            println!("ok");
+        }
+        "help" | "--help" | "-h" => println!("{USAGE}"),
+        command => bail!("unknown command {command:?}\n\n{USAGE}"),
     }
+    Ok(())
 }
 
-impl<T> MerkleTree<T>
-where
-    T: Hash + Default + Clone + Copy,
-{
-    pub fn add(&mut self, value: T) {
-        self.values.push(value);
+/// stream the leafs of the input file into a tree of their hashes
+fn read_tree(args: &mut Args) -> eyre::Result<HashedMerkleTree<Sha256>> {
+    let chunk_size = args
+        .number("--chunk-size")?
+        .map(|size| NonZeroUsize::new(size).ok_or_else(|| eyre!("--chunk-size has to be positive")))
+        .transpose()?;
+    let path = args
+        .positional()
+        .ok_or_else(|| eyre!("missing input file"))?;
+    args.finish()?;
+
+    let file = File::open(&path).wrap_err_with(|| format!("opening {path}"))?;
+    let mut reader = BufReader::new(file);
+    let mut tree = MerkleTree::<Vec<u8>, _>::with_hasher(Vec::new(), Sha256).finalize();
+    let mut leaf = Vec::new();
+    loop {
+        leaf.clear();
+        let more = match chunk_size {
+            Some(size) => read_chunk(&mut reader, &mut leaf, size.get()),
+            None => read_line(&mut reader, &mut leaf),
+        }
+        .wrap_err_with(|| format!("reading {path}"))?;
+        if !more {
+            break;
+        }
+        tree.add_hash(Sha256.hash_leaf(&leaf));
     }
 
-    /// return the leafs of the merkle tree
-    /// the leafs are hashed values or hashed default values
-    /// the number of leafs always equals the smallest power of two that is greater
-    /// than the number of values stored in the tree
-    pub fn leafs(&self) -> VecDeque<u64> {
-        let size = self.values.len().next_power_of_two();
-        let empty = vec![T::default(); size - self.values.len()];
-        let mut leafs = self.values.clone();
-        leafs.extend(empty);
-
-        leafs.iter().map(Self::hash_leaf).collect()
+    if tree.is_empty() {
+        bail!("{path} is empty, a tree needs at least one leaf");
     }
+    Ok(tree)
+}
 
-    pub fn root(&self) -> u64 {
-        let mut hashes = self.leafs();
-        while hashes.len() > 1 {
-            Self::parents(&mut hashes);
+/// read a line without its line ending, false at the end of the input
+fn read_line(reader: &mut impl BufRead, line: &mut Vec<u8>) -> io::Result<bool> {
+    if reader.read_until(b'\n', line)? == 0 {
+        return Ok(false);
+    }
+    if line.ends_with(b"\n") {
+        line.pop();
+        if line.ends_with(b"\r") {
+            line.pop();
         }
-
-        hashes.pop_front().unwrap()
     }
+    Ok(true)
+}
 
-    pub fn get_proof(&self, index: usize) -> eyre::Result<MerkleProof> {
-        let mut index = index;
-        let mut hashes = self.leafs();
-        let leaf = *hashes.get(index).ok_or_eyre("index out of bounds")?;
-
-        let mut proof = MerkleProof {
-            leaf,
-            root: self.root(),
-            path: Vec::new(),
-        };
-
-        while hashes.len() > 1 {
-            let sibling = if index % 2 == 0 { index + 1 } else { index - 1 };
-            let direction = if index > sibling {
-                Direction::Left
-            } else {
-                Direction::Right
-            };
-            let value = hashes.get(sibling).unwrap().clone();
-            proof.path.push(Step { direction, value });
-
-            Self::parents(&mut hashes);
-            index = index / 2;
-        }
+/// read up to `size` bytes, only the last chunk may be shorter
+fn read_chunk(reader: &mut impl Read, chunk: &mut Vec<u8>, size: usize) -> io::Result<bool> {
+    reader.take(size as u64).read_to_end(chunk)?;
+    Ok(!chunk.is_empty())
+}
 
-        Ok(proof)
-    }
+/// command line arguments, options are taken out as they are asked for
+struct Args(Vec<String>);
 
-    pub fn contains(&self, hash: &u64) -> bool {
-        self.leafs().contains(hash)
+impl Args {
+    fn new(args: Vec<String>) -> Self {
+        Self(args)
     }
 
-    pub fn verify_proof(proof: &MerkleProof) -> bool {
-        let mut acc = proof.leaf;
-
-        for step in &proof.path {
-            acc = match step.direction {
-                Direction::Left => Self::hash_siblings(&step.value, &acc),
-                Direction::Right => Self::hash_siblings(&acc, &step.value),
-            }
+    fn command(&mut self) -> eyre::Result<String> {
+        if self.0.is_empty() {
+            bail!("missing command\n\n{USAGE}");
         }
-
-        proof.root == acc
+        Ok(self.0.remove(0))
     }
 
-    fn parents(hashes: &mut VecDeque<u64>) {
-        let len = hashes.len();
-
-        assert_eq!(len % 2, 0);
-        assert!(len > 0);
-
-        for _ in 0..(len / 2) {
-            let left = hashes.pop_front().unwrap();
-            let right = hashes.pop_front().unwrap();
-            let parent = Self::hash_siblings(&left, &right);
-            hashes.push_back(parent);
+    fn option(&mut self, name: &str) -> eyre::Result<Option<String>> {
+        let Some(at) = self.0.iter().position(|arg| arg == name) else {
+            return Ok(None);
+        };
+        if self
+            .0
+            .get(at + 1)
+            .is_none_or(|value| value.starts_with("--"))
+        {
+            bail!("{name} needs a value");
         }
+        self.0.remove(at);
+        Ok(Some(self.0.remove(at)))
     }
 
-    fn hash_leaf(leaf: &T) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        leaf.hash(&mut hasher);
-        hasher.finish()
+    fn number(&mut self, name: &str) -> eyre::Result<Option<usize>> {
+        self.option(name)?
+            .map(|value| {
+                value
+                    .parse()
+                    .wrap_err_with(|| format!("invalid {name} {value:?}"))
+            })
+            .transpose()
     }
 
-    fn hash_siblings(left: &u64, right: &u64) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        left.hash(&mut hasher);
-        right.hash(&mut hasher);
-        hasher.finish()
+    /// the first argument that is not an option, anything starting with a
+    /// dash is left for [`finish`](Args::finish) to report
+    fn positional(&mut self) -> Option<String> {
+        let at = self.0.iter().position(|arg| !arg.starts_with('-'))?;
+        Some(self.0.remove(at))
     }
-}
-
-#[test]
-fn basic_proof() -> eyre::Result<()> {
-    let values: Vec<u32> = (0..100_000).collect();
-    let mut tree = MerkleTree::from(values);
-    let proof = tree.get_proof(500)?;
-
-    assert!(MerkleTree::<u32>::verify_proof(&proof));
-    assert_eq!(&proof.root, &tree.root());
-
-    tree.add(42);
-    assert_ne!(&proof.root, &tree.root());
-
-    Ok(())
-}
-
-#[test]
-fn empty_tree() -> eyre::Result<()> {
-    let tree: MerkleTree<u32> = MerkleTree::from(vec![]);
 
-    // tree initiated with an empty list should have 1 leaf which is also the root
-    assert_eq!(tree.leafs().len(), 1);
-
-    let proof = tree.get_proof(0)?;
-    assert!(MerkleTree::<u32>::verify_proof(&proof));
-    assert_eq!(&proof.root, &tree.root());
-
-    Ok(())
-}
-
-#[test]
-fn out_of_bounds() {
-    let tree: MerkleTree<u32> = MerkleTree::from(vec![1, 2]);
-    assert_eq!(tree.leafs().len(), 2);
-
-    let proof = tree.get_proof(2);
-    assert!(proof.is_err());
-}
-
-#[test]
-fn contains() {
-    let mut tree: MerkleTree<u32> = MerkleTree::from(vec![1, 2]);
-
-    let elem = 3;
-    let hash = MerkleTree::<u32>::hash_leaf(&elem);
-
-    assert!(!tree.contains(&hash));
-
-    tree.add(elem);
-    assert!(tree.contains(&hash));
+    fn finish(&self) -> eyre::Result<()> {
+        match self.0.first() {
+            Some(arg) => bail!("unexpected argument {arg:?}"),
+            None => Ok(()),
+        }
+    }
 }
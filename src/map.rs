@@ -0,0 +1,189 @@
+//! a key value store committed to by a merkle tree, with proofs by key
+//!
+//! entries are kept sorted by the leaf hash of their key, so the tree only
+//! depends on the entries and not on the order they were inserted in. the
+//! leaf of an entry is the node over the hashes of its key and its value, and
+//! the tree is shaped like the one of a [`SortedMerkleTree`], without padding.
+//! keys are told apart by their hash.
+//!
+//! [`SortedMerkleTree`]: crate::SortedMerkleTree
+
+use alloc::vec::Vec;
+
+use crate::{
+    error::MerkleError,
+    hash::{compare, DefaultBuildHasher, Digest, LeafHasher, MerkleHasher},
+    proof::MerkleProof,
+    root::{LeafHash, Root},
+    tree::{build_levels, proof_path, PaddingStrategy},
+    OnceLock,
+};
+
+/// a map whose entries are the leafs of a merkle tree
+pub struct MerkleMap<K, V, S: MerkleHasher = DefaultBuildHasher> {
+    /// the entries sorted by the hash of their key
+    entries: Vec<(K, V)>,
+    /// hash of the key of every entry, sorted
+    keys: Vec<S::Digest>,
+    /// leaf of every entry
+    leafs: Vec<S::Digest>,
+    hasher: S,
+    levels: OnceLock<Vec<Vec<S::Digest>>>,
+}
+
+/// proof that a key is set to a value in a [`MerkleMap`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyedProof<S: MerkleHasher = DefaultBuildHasher> {
+    /// proof of the leaf of the entry
+    pub proof: MerkleProof<S>,
+}
+
+impl<K, V> MerkleMap<K, V>
+where
+    DefaultBuildHasher: LeafHasher<K> + LeafHasher<V>,
+{
+    pub fn new() -> Self {
+        Self::with_hasher(DefaultBuildHasher::default())
+    }
+}
+
+impl<K, V> Default for MerkleMap<K, V>
+where
+    DefaultBuildHasher: LeafHasher<K> + LeafHasher<V>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, S> MerkleMap<K, V, S>
+where
+    S: LeafHasher<K> + LeafHasher<V> + Clone,
+{
+    pub fn with_hasher(hasher: S) -> Self {
+        Self {
+            entries: Vec::new(),
+            keys: Vec::new(),
+            leafs: Vec::new(),
+            hasher,
+            levels: OnceLock::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// the entries sorted by the hash of their key
+    pub fn entries(&self) -> &[(K, V)] {
+        &self.entries
+    }
+
+    /// set `key` to `value`, returns the value it was set to before
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let hash = LeafHasher::<K>::hash_leaf(&self.hasher, &key);
+        let leaf = entry_leaf(&self.hasher, &hash, &value);
+        self.levels.take();
+        match self.search(&hash) {
+            Ok(index) => {
+                self.leafs[index] = leaf;
+                Some(core::mem::replace(&mut self.entries[index].1, value))
+            }
+            Err(index) => {
+                self.entries.insert(index, (key, value));
+                self.keys.insert(index, hash);
+                self.leafs.insert(index, leaf);
+                None
+            }
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let index = self.position(key)?;
+        Some(&self.entries[index].1)
+    }
+
+    /// unset `key`, returns the value it was set to
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let index = self.position(key)?;
+        self.keys.remove(index);
+        self.leafs.remove(index);
+        self.levels.take();
+        Some(self.entries.remove(index).1)
+    }
+
+    /// the root of the tree over the entries, an empty map has none
+    pub fn root(&self) -> Option<Root<S::Digest>> {
+        let levels = self.levels()?;
+        Some(Root(levels[levels.len() - 1][0]))
+    }
+
+    /// proof that `key` is set to its current value
+    /// keys that are not set are [`KeyNotFound`](MerkleError::KeyNotFound)
+    pub fn prove(&self, key: &K) -> Result<KeyedProof<S>, MerkleError> {
+        let index = self.position(key).ok_or(MerkleError::KeyNotFound)?;
+        let levels = self.levels().ok_or(MerkleError::KeyNotFound)?;
+        let (leaf, path) = proof_path(levels, PaddingStrategy::PromoteOdd, index)?;
+
+        let proof = MerkleProof {
+            path,
+            root: Root(levels[levels.len() - 1][0]),
+            leaf: LeafHash(leaf),
+            index,
+            width: levels[0].len(),
+            padding: PaddingStrategy::PromoteOdd,
+            hasher: self.hasher.clone(),
+        };
+        Ok(KeyedProof { proof })
+    }
+
+    fn position(&self, key: &K) -> Option<usize> {
+        let hash = LeafHasher::<K>::hash_leaf(&self.hasher, key);
+        self.search(&hash).ok()
+    }
+
+    fn search(&self, hash: &S::Digest) -> Result<usize, usize> {
+        self.keys.binary_search_by(|key| compare(key, hash))
+    }
+
+    fn levels(&self) -> Option<&[Vec<S::Digest>]> {
+        if self.leafs.is_empty() {
+            return None;
+        }
+        let levels = self.levels.get_or_init(|| {
+            build_levels(
+                &self.hasher,
+                PaddingStrategy::PromoteOdd,
+                self.leafs.clone(),
+            )
+        });
+        Some(levels)
+    }
+}
+
+impl<S: MerkleHasher> KeyedProof<S> {
+    /// check that the proof shows `key` set to `value` in the map with the
+    /// trusted `root`
+    pub fn verify<K, V>(&self, root: &Root<S::Digest>, key: &K, value: &V) -> bool
+    where
+        S: LeafHasher<K> + LeafHasher<V>,
+    {
+        let hasher = &self.proof.hasher;
+        let hash = LeafHasher::<K>::hash_leaf(hasher, key);
+        entry_leaf(hasher, &hash, value).ct_eq(&self.proof.leaf.0)
+            && self.proof.padding == PaddingStrategy::PromoteOdd
+            && self.proof.verify_against(root)
+    }
+}
+
+/// the leaf of the entry with the key of hash `key`
+fn entry_leaf<S, V>(hasher: &S, key: &S::Digest, value: &V) -> S::Digest
+where
+    S: LeafHasher<V>,
+{
+    hasher.hash_siblings(key, &hasher.hash_leaf(value))
+}
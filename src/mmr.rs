@@ -0,0 +1,248 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::{
+    error::MerkleError,
+    hash::{DefaultBuildHasher, Digest, LeafHasher, MerkleHasher},
+    root::{LeafHash, Root},
+};
+
+/// a merkle mountain range, an append only list of perfect trees
+///
+/// every node is stored at the position it was created at, numbered in post
+/// order, so positions never change once handed out. an append only hashes the
+/// parents it completes and the root is bagged from the peaks, the roots of
+/// the perfect trees, from right to left.
+///
+/// a proof leads from its leaf to the peak above it and carries all peaks of
+/// the range it was generated from. once later appends merge that peak into a
+/// bigger tree the proof only verifies against the old root, a new proof has
+/// to be generated for the new one, its path starts with the old path.
+pub struct Mmr<T, S: MerkleHasher = DefaultBuildHasher> {
+    nodes: Vec<S::Digest>,
+    hasher: S,
+    values: PhantomData<fn(&T)>,
+}
+
+/// proof that the leaf at `position` is part of a range of `size` nodes
+#[derive(Debug)]
+pub struct MmrProof<S: MerkleHasher = DefaultBuildHasher> {
+    pub position: usize,
+    /// number of nodes of the range the proof was generated from
+    pub size: usize,
+    pub leaf: LeafHash<S::Digest>,
+    /// siblings from the leaf up to its peak
+    pub path: Vec<S::Digest>,
+    /// peaks of the range from left to right
+    pub peaks: Vec<S::Digest>,
+    pub root: Root<S::Digest>,
+    pub hasher: S,
+}
+
+impl<T> Mmr<T>
+where
+    DefaultBuildHasher: LeafHasher<T>,
+{
+    pub fn new() -> Self {
+        Self::with_hasher(DefaultBuildHasher::default())
+    }
+}
+
+impl<T> Default for Mmr<T>
+where
+    DefaultBuildHasher: LeafHasher<T>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S> Mmr<T, S>
+where
+    S: LeafHasher<T> + Clone,
+{
+    pub fn with_hasher(hasher: S) -> Self {
+        Self {
+            nodes: Vec::new(),
+            hasher,
+            values: PhantomData,
+        }
+    }
+
+    /// number of nodes, leafs and inner nodes
+    pub fn size(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// append a value and return the position of its leaf
+    pub fn append(&mut self, value: &T) -> usize {
+        let position = self.nodes.len();
+        self.nodes.push(self.hasher.hash_leaf(value));
+
+        // complete every tree the new leaf is the last one of
+        let mut height = 0;
+        while height_of(self.nodes.len()) > height {
+            let right = self.nodes.len() - 1;
+            let left = right + 1 - (2 << height);
+            let parent = self
+                .hasher
+                .hash_siblings(&self.nodes[left], &self.nodes[right]);
+            self.nodes.push(parent);
+            height += 1;
+        }
+
+        position
+    }
+
+    /// the bagged peaks, an empty range has no root
+    pub fn root(&self) -> Option<Root<S::Digest>> {
+        let peaks: Vec<_> = peaks(self.nodes.len())
+            .expect("appends only leave whole trees")
+            .iter()
+            .map(|&peak| self.nodes[peak])
+            .collect();
+        bag(&self.hasher, &peaks).map(Root)
+    }
+
+    pub fn get_proof(&self, position: usize) -> Result<MmrProof<S>, MerkleError> {
+        let size = self.nodes.len();
+        if position >= size || height_of(position) != 0 {
+            return Err(MerkleError::IndexOutOfBounds {
+                index: position,
+                len: size,
+            });
+        }
+
+        let mut path = Vec::new();
+        let (mut node, mut height) = (position, 0);
+        while let Some((sibling, parent)) = family(node, height) {
+            if sibling >= size {
+                break;
+            }
+            path.push(self.nodes[sibling]);
+            node = parent;
+            height += 1;
+        }
+
+        let peaks: Vec<_> = peaks(size)
+            .expect("appends only leave whole trees")
+            .iter()
+            .map(|&peak| self.nodes[peak])
+            .collect();
+        Ok(MmrProof {
+            position,
+            size,
+            leaf: LeafHash(self.nodes[position]),
+            path,
+            root: Root(bag(&self.hasher, &peaks).expect("a range with a leaf has peaks")),
+            peaks,
+            hasher: self.hasher.clone(),
+        })
+    }
+}
+
+impl<S: MerkleHasher> MmrProof<S> {
+    /// check that the path leads from the leaf to one of the peaks and that
+    /// the peaks bag into the root of the proof
+    #[deprecated(note = "use `MmrProof::verify_against` with a trusted root")]
+    pub fn verify(&self) -> bool {
+        self.verify_against(&self.root)
+    }
+
+    /// check that the path leads from the leaf to one of the peaks and that
+    /// the peaks bag into a root the verifier trusts, the root stored in the
+    /// proof is ignored
+    pub fn verify_against(&self, root: &Root<S::Digest>) -> bool {
+        // a size that leaves nodes outside of whole trees is no range
+        let Some(positions) = peaks(self.size) else {
+            return false;
+        };
+        if positions.is_empty()
+            || positions.len() != self.peaks.len()
+            || self.position >= self.size
+            || height_of(self.position) != 0
+        {
+            return false;
+        }
+
+        let (mut node, mut acc) = (self.position, self.leaf.0);
+        for (height, sibling) in self.path.iter().enumerate() {
+            let Some((sibling_position, parent)) = family(node, height) else {
+                return false;
+            };
+            acc = if sibling_position < node {
+                self.hasher.hash_siblings(sibling, &acc)
+            } else {
+                self.hasher.hash_siblings(&acc, sibling)
+            };
+            node = parent;
+        }
+
+        match positions.iter().position(|&peak| peak == node) {
+            Some(peak) => {
+                let bagged = bag(&self.hasher, &self.peaks);
+                self.peaks[peak].ct_eq(&acc) & bagged.is_some_and(|bagged| bagged.ct_eq(&root.0))
+            }
+            None => false,
+        }
+    }
+}
+
+/// height of the node at `position`, leafs have height zero
+fn height_of(position: usize) -> usize {
+    // in one based positions the left most node of every height is all ones,
+    // every other node is found by jumping left over whole trees
+    let mut position = position as u64 + 1;
+    while position.wrapping_add(1) & position != 0 {
+        let bits = u64::BITS - position.leading_zeros();
+        position -= (1 << (bits - 1)) - 1;
+    }
+    (u64::BITS - position.leading_zeros()) as usize - 1
+}
+
+/// sibling and parent of the node at `position` with `height`, none if
+/// they lie beyond the positions a range can have
+fn family(position: usize, height: usize) -> Option<(usize, usize)> {
+    let offset = u32::try_from(height)
+        .ok()
+        .and_then(|height| 2usize.checked_shl(height))
+        .and_then(|size| size.checked_sub(1))?;
+    let next = position.checked_add(1)?;
+    if height_of(next) > height {
+        // right child, the parent directly follows it
+        Some((position.checked_sub(offset)?, next))
+    } else {
+        let sibling = position.checked_add(offset)?;
+        Some((sibling, sibling.checked_add(1)?))
+    }
+}
+
+/// positions of the peaks of a range of `size` nodes, from left to right
+/// none if the nodes don't split into perfect trees of different heights
+fn peaks(size: usize) -> Option<Vec<usize>> {
+    let mut peaks = Vec::new();
+    let (mut offset, mut remaining) = (0, size);
+    for height in (0..usize::BITS as usize - 1).rev() {
+        let tree = (2 << height) - 1;
+        if tree <= remaining {
+            peaks.push(offset + tree - 1);
+            offset += tree;
+            remaining -= tree;
+        }
+    }
+    (remaining == 0).then_some(peaks)
+}
+
+/// fold the peaks from right to left into a single root
+fn bag<S: MerkleHasher>(hasher: &S, peaks: &[S::Digest]) -> Option<S::Digest> {
+    let (last, rest) = peaks.split_last()?;
+    Some(
+        rest.iter()
+            .rev()
+            .fold(*last, |acc, peak| hasher.hash_siblings(peak, &acc)),
+    )
+}
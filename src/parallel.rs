@@ -0,0 +1,77 @@
+//! order preserving parallel maps built on scoped threads
+
+use std::{cell::Cell, thread};
+
+/// levels narrower than this are reduced on the calling thread, spawning
+/// threads costs more than hashing them
+pub(crate) const MIN_PARALLEL_WIDTH: usize = 1 << 12;
+
+thread_local! {
+    /// set on the threads spawned here, they already share the cores
+    static WORKER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// number of threads to split work over, one per core, or only the calling
+/// one on a thread spawned here, so nested work never spawns more threads
+/// than there are cores
+pub(crate) fn threads() -> usize {
+    if WORKER.get() {
+        return 1;
+    }
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// run `f` marked as a worker thread
+fn worker<O>(f: impl FnOnce() -> O) -> O {
+    WORKER.set(true);
+    f()
+}
+
+/// map every item on as many threads as there are cores
+pub(crate) fn map<I, O, F>(items: &[I], f: F) -> Vec<O>
+where
+    I: Sync,
+    O: Send,
+    F: Fn(&I) -> O + Sync,
+{
+    map_chunks(items, 1, |item| f(&item[0]))
+}
+
+/// map every chunk of `size` items on as many threads as there are cores
+/// each thread maps a contiguous run of chunks, so the output keeps the input
+/// order and equals `items.chunks(size).map(f)`
+pub(crate) fn map_chunks<I, O, F>(items: &[I], size: usize, f: F) -> Vec<O>
+where
+    I: Sync,
+    O: Send,
+    F: Fn(&[I]) -> O + Sync,
+{
+    let per_thread = items.len().div_ceil(size).div_ceil(threads()).max(1) * size;
+    if per_thread >= items.len() {
+        return items.chunks(size).map(f).collect();
+    }
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(per_thread)
+            .map(|run| scope.spawn(|| worker(|| run.chunks(size).map(&f).collect::<Vec<_>>())))
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("hashing thread panicked"))
+            .collect()
+    })
+}
+
+/// run both closures at once, the second one on the calling thread
+pub(crate) fn join<A, B>(a: impl FnOnce() -> A + Send, b: impl FnOnce() -> B) -> (A, B)
+where
+    A: Send,
+{
+    thread::scope(|scope| {
+        let a = scope.spawn(|| worker(a));
+        let b = b();
+        (a.join().expect("hashing thread panicked"), b)
+    })
+}
@@ -0,0 +1,503 @@
+use alloc::{format, string::ToString, vec::Vec};
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use crate::{
+    error::{MerkleError, ProofError},
+    hash::{DefaultBuildHasher, Digest, MerkleHasher, SortedPairs},
+    hex,
+    root::{LeafHash, Root},
+    tree::{PaddingStrategy, MAX_LEAFS},
+    verify::{directions, fold_path, path_len},
+};
+
+pub(crate) mod bytes;
+#[cfg(feature = "cbor")]
+mod cbor;
+mod compact;
+mod envelope;
+mod json;
+mod packed;
+#[cfg(feature = "std")]
+pub(crate) mod streaming;
+mod update;
+
+pub use compact::{CompactProof, CompactStep};
+pub use envelope::PROOF_FORMAT_VERSION;
+pub use packed::{PackedPath, MAX_PACKED_LEN};
+#[cfg(feature = "std")]
+pub use streaming::ProofReader;
+pub use update::UpdateProof;
+
+/// longest path a decoded proof may have, a tree of `usize::MAX` leafs is
+/// no higher than this. decoding rejects longer paths before allocating them
+pub const MAX_PATH_LEN: usize = usize::BITS as usize;
+
+/// side of the path on which a sibling sits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// compact string form used when proofs are written out
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Left => "left",
+            Direction::Right => "right",
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Direction {
+    type Err = MerkleError;
+
+    fn from_str(s: &str) -> Result<Self, MerkleError> {
+        match s {
+            "left" => Ok(Direction::Left),
+            "right" => Ok(Direction::Right),
+            _ => Err(MerkleError::InvalidDirection(format!("{s:?}"))),
+        }
+    }
+}
+
+/// compact integer form, `0` for left and `1` for right
+impl From<Direction> for u8 {
+    fn from(direction: Direction) -> u8 {
+        match direction {
+            Direction::Left => 0,
+            Direction::Right => 1,
+        }
+    }
+}
+
+impl TryFrom<u8> for Direction {
+    type Error = MerkleError;
+
+    fn try_from(value: u8) -> Result<Self, MerkleError> {
+        match value {
+            0 => Ok(Direction::Left),
+            1 => Ok(Direction::Right),
+            _ => Err(MerkleError::InvalidDirection(value.to_string())),
+        }
+    }
+}
+
+/// one step of the path from a leaf up to the root
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Step<D = u64> {
+    pub direction: Direction,
+    pub value: D,
+}
+
+/// proof that leaf is included in a tree with the given root at the given index
+/// the proof carries the hasher of the tree it was generated from, so that
+/// it is verified with the same algorithm
+/// proofs compare and hash by their contents, the hasher is not compared
+#[derive(Debug, Clone)]
+pub struct MerkleProof<S: MerkleHasher = DefaultBuildHasher> {
+    pub path: Vec<Step<S::Digest>>,
+    pub root: Root<S::Digest>,
+    pub leaf: LeafHash<S::Digest>,
+    /// position of the leaf in the tree, the directions of the path have to
+    /// agree with it
+    pub index: usize,
+    /// number of leafs of the tree, the length of the path has to agree with it
+    pub width: usize,
+    /// how the tree completes odd levels, it decides which levels have a step
+    pub padding: PaddingStrategy,
+    pub hasher: S,
+}
+
+impl<S: MerkleHasher> MerkleProof<S> {
+    /// check that the path leads from the leaf to the root of the proof and
+    /// that its shape agrees with the index of the leaf and the tree size
+    /// the path is folded with the hasher carried by the proof
+    ///
+    /// this only shows the proof is consistent, whoever built it also chose
+    /// its root. check against a root you trust with [`verify_against`]
+    ///
+    /// [`verify_against`]: MerkleProof::verify_against
+    pub fn verify(&self) -> bool {
+        self.verify_detailed().is_ok()
+    }
+
+    /// like [`verify`] but against a root the verifier trusts, the root stored
+    /// in the proof is ignored
+    ///
+    /// ```
+    /// use merkle::MerkleTree;
+    ///
+    /// let tree = MerkleTree::from(vec![1u32, 2, 3]);
+    /// let trusted = tree.root();
+    ///
+    /// let mut proof = tree.get_proof(1).unwrap();
+    /// assert!(proof.verify_against(&trusted));
+    ///
+    /// // a forged root does not help, the path still leads to the trusted one
+    /// proof.root.0 ^= 1;
+    /// assert!(proof.verify_against(&trusted));
+    /// assert!(!proof.verify());
+    /// ```
+    ///
+    /// [`verify`]: MerkleProof::verify
+    pub fn verify_against(&self, root: &Root<S::Digest>) -> bool {
+        self.check(root).is_ok()
+    }
+
+    /// like [`verify_against`] for several trusted roots, e.g. the last few
+    /// published ones, the position of the first root the proof leads to
+    ///
+    /// the path is folded once and compared with every root in constant time,
+    /// how long it takes does not depend on which root matched
+    ///
+    /// [`verify_against`]: MerkleProof::verify_against
+    pub fn verify_against_any(&self, roots: &[Root<S::Digest>]) -> Option<usize> {
+        let computed = self.fold().ok()?;
+        roots
+            .iter()
+            .enumerate()
+            .rev()
+            .fold(None, |found, (position, root)| {
+                let matched = computed.ct_eq(&root.0);
+                [found, Some(position)][usize::from(matched)]
+            })
+    }
+
+    /// like [`verify`] but for a tree of `width` leafs the verifier expects,
+    /// proofs from trees of any other size are rejected
+    ///
+    /// [`verify`]: MerkleProof::verify
+    pub fn verify_with_len(&self, width: usize) -> bool {
+        self.width == width && self.verify()
+    }
+
+    /// like [`verify`] but reports the first check that failed
+    ///
+    /// [`verify`]: MerkleProof::verify
+    pub fn verify_detailed(&self) -> Result<(), ProofError<S::Digest>> {
+        self.check(&self.root)
+    }
+
+    /// the index of the leaf as the directions of the path spell it out
+    ///
+    /// the path is read from the root down, every step tells on which side of
+    /// its parent the node lies, the levels on which [`PromoteOdd`] moved the
+    /// node up without a sibling have no step. the index does not depend on
+    /// the [`index`](MerkleProof::index) field, verification checks that both
+    /// agree
+    ///
+    /// a path with fewer or more steps than the tree of
+    /// [`width`](MerkleProof::width) leafs has levels is an error of the
+    /// same kind [`verify_detailed`](MerkleProof::verify_detailed) reports,
+    /// in the shorter case the missing steps count as leading left
+    ///
+    /// [`PromoteOdd`]: PaddingStrategy::PromoteOdd
+    pub fn implied_index(&self) -> Result<usize, ProofError<S::Digest>> {
+        if self.width == 0 {
+            return Err(ProofError::IndexOutOfBounds {
+                index: self.index,
+                width: 0,
+            });
+        }
+        let last = self.width - 1;
+        let height = (usize::BITS - last.leading_zeros()) as usize;
+        let path_length = |index| ProofError::PathLength {
+            expected: path_len(index, self.width, self.padding),
+            actual: self.path.len(),
+        };
+
+        // the node at the top has index 0 on a level of width 1
+        let mut steps = self.path.iter().rev();
+        let mut index = 0;
+        for level in (0..height).rev() {
+            let promoted = self.padding == PaddingStrategy::PromoteOdd
+                && index == last.checked_shr(level as u32 + 1).unwrap_or(0)
+                && (last >> level) & 1 == 0;
+            index *= 2;
+            if promoted {
+                continue;
+            }
+            match steps.next() {
+                Some(step) => index += usize::from(step.direction == Direction::Left),
+                None => return Err(path_length(index << level)),
+            }
+        }
+        if steps.next().is_some() {
+            return Err(path_length(index));
+        }
+        Ok(index)
+    }
+
+    /// join a proof from a leaf to the root of a subtree with the proof of that
+    /// subtree root in the whole tree, see
+    /// [`get_subtree_proof`](crate::MerkleTree::get_subtree_proof)
+    /// the result only verifies if the root of `self` is the leaf of `upper`
+    /// only trees padded with default values have complete subtrees whose
+    /// width gives the index of the leaf in the whole tree
+    pub fn chain(mut self, upper: MerkleProof<S>) -> Result<Self, MerkleError> {
+        for padding in [self.padding, upper.padding] {
+            if padding != PaddingStrategy::DefaultValue {
+                return Err(MerkleError::UnsupportedPadding(padding));
+            }
+        }
+        for proof in [&self, &upper] {
+            if proof.index >= proof.width {
+                return Err(MerkleError::IndexOutOfBounds {
+                    index: proof.index,
+                    len: proof.width,
+                });
+            }
+        }
+        let width = self.width.saturating_mul(upper.width);
+        if width > MAX_LEAFS {
+            return Err(MerkleError::TooLarge {
+                len: width,
+                max: MAX_LEAFS,
+            });
+        }
+        // both indices are in bounds, so the index is below the width
+        self.index += upper.index * self.width;
+        self.width = width;
+        self.path.extend(upper.path);
+        self.root = upper.root;
+        Ok(self)
+    }
+
+    pub(crate) fn check(&self, root: &Root<S::Digest>) -> Result<(), ProofError<S::Digest>> {
+        let computed = self.fold()?;
+        if !computed.ct_eq(&root.0) {
+            return Err(ProofError::RootMismatch {
+                computed,
+                expected: root.0,
+            });
+        }
+        Ok(())
+    }
+
+    /// the root the path leads to once its shape is checked
+    fn fold(&self) -> Result<S::Digest, ProofError<S::Digest>> {
+        if self.index >= self.width {
+            return Err(ProofError::IndexOutOfBounds {
+                index: self.index,
+                width: self.width,
+            });
+        }
+
+        let expected = path_len(self.index, self.width, self.padding);
+        if self.path.len() != expected {
+            return Err(ProofError::PathLength {
+                expected,
+                actual: self.path.len(),
+            });
+        }
+
+        if self.implied_index()? != self.index {
+            let step = self
+                .path
+                .iter()
+                .zip(directions(self.index, self.width, self.padding))
+                .position(|(step, direction)| step.direction != direction)
+                .unwrap_or_default();
+            return Err(ProofError::DirectionMismatch { step });
+        }
+
+        Ok(fold_path(&self.hasher, &self.leaf.0, &self.path))
+    }
+}
+
+impl<S: MerkleHasher> PartialEq for MerkleProof<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+            && self.root == other.root
+            && self.leaf == other.leaf
+            && self.index == other.index
+            && self.width == other.width
+            && self.padding == other.padding
+    }
+}
+
+impl<S: MerkleHasher> Eq for MerkleProof<S> {}
+
+impl<S: MerkleHasher> Hash for MerkleProof<S>
+where
+    S::Digest: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.path.hash(state);
+        self.root.hash(state);
+        self.leaf.hash(state);
+        self.index.hash(state);
+        self.width.hash(state);
+        self.padding.hash(state);
+    }
+}
+
+impl<S: MerkleHasher> MerkleProof<SortedPairs<S>> {
+    /// the sibling hashes from the leaf up, without directions
+    /// this is all openzeppelin and merkletreejs need to verify a proof, see
+    /// [`verify_sorted_pairs`](crate::verify::verify_sorted_pairs)
+    pub fn siblings(&self) -> Vec<S::Digest> {
+        self.path.iter().map(|step| step.value).collect()
+    }
+}
+
+/// one line for the leaf, one per step from the leaf up and one for the root
+/// `←` marks a sibling on the left, `→` one on the right, `{:#x}` prefixes
+/// the digests with `0x`
+/// ```text
+/// leaf 1 of 4: 3e23e816…
+/// ← ca978112…
+/// → bffe0b34…
+/// root: 14ede5e8…
+/// ```
+impl<S: MerkleHasher> fmt::LowerHex for MerkleProof<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = if f.alternate() { "0x" } else { "" };
+        write!(f, "leaf {} of {}: {prefix}", self.index, self.width)?;
+        hex::write(f, &self.leaf.0)?;
+        for step in &self.path {
+            let arrow = match step.direction {
+                Direction::Left => '←',
+                Direction::Right => '→',
+            };
+            write!(f, "\n{arrow} {prefix}")?;
+            hex::write(f, &step.value)?;
+        }
+        write!(f, "\nroot: {prefix}")?;
+        hex::write(f, &self.root.0)
+    }
+}
+
+/// the same lines as [`LowerHex`](fmt::LowerHex)
+impl<S: MerkleHasher> fmt::Display for MerkleProof<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+/// proof that several leafs are included in a tree with the given root
+/// sibling hashes shared between the paths of the leafs are only included once
+#[derive(Debug)]
+pub struct MerkleMultiProof<S: MerkleHasher = DefaultBuildHasher> {
+    /// indices and hashes of the proven leafs, sorted by index
+    pub leafs: Vec<(usize, S::Digest)>,
+    /// sibling hashes the verifier can't compute, in the order they're needed
+    pub decommitments: Vec<S::Digest>,
+    /// number of leafs of the tree
+    pub width: usize,
+    /// how the tree completes odd levels, verification has to do the same
+    pub padding: PaddingStrategy,
+    pub root: Root<S::Digest>,
+    pub hasher: S,
+}
+
+impl<S: MerkleHasher> MerkleMultiProof<S> {
+    /// check that the leafs lead to the root of the proof
+    /// the decommitments have to be used up exactly
+    /// odd nodes are completed according to the padding recorded in the proof
+    ///
+    /// like [`MerkleProof::verify`] this only shows the proof is consistent,
+    /// check against a root you trust with [`verify_against`]
+    ///
+    /// [`verify_against`]: MerkleMultiProof::verify_against
+    pub fn verify(&self) -> bool {
+        self.verify_against(&self.root)
+    }
+
+    /// like [`verify`] but against a root the verifier trusts, the root stored
+    /// in the proof is ignored
+    ///
+    /// [`verify`]: MerkleMultiProof::verify
+    pub fn verify_against(&self, root: &Root<S::Digest>) -> bool {
+        self.fold().is_some_and(|computed| computed.ct_eq(&root.0))
+    }
+
+    /// the root the leafs and decommitments hash up to
+    fn fold(&self) -> Option<S::Digest> {
+        let sorted = self.leafs.windows(2).all(|pair| pair[0].0 < pair[1].0);
+        let in_bounds = self.leafs.iter().all(|(index, _)| *index < self.width);
+        if self.leafs.is_empty() || !sorted || !in_bounds {
+            return None;
+        }
+
+        let mut nodes = self.leafs.clone();
+        let mut decommitments = self.decommitments.iter();
+        let mut width = self.width;
+
+        while width > 1 {
+            let mut parents = Vec::with_capacity(nodes.len());
+            let mut i = 0;
+            while i < nodes.len() {
+                let (index, hash) = nodes[i];
+                let parent = if index.is_multiple_of(2) {
+                    match nodes.get(i + 1) {
+                        Some(&(next, sibling)) if next == index + 1 => {
+                            i += 1;
+                            self.hasher.hash_siblings(&hash, &sibling)
+                        }
+                        _ if index + 1 < width => {
+                            self.hasher.hash_siblings(&hash, decommitments.next()?)
+                        }
+                        // the last node of an odd level
+                        _ => match self.padding {
+                            PaddingStrategy::PromoteOdd => hash,
+                            PaddingStrategy::DuplicateLast => {
+                                self.hasher.hash_siblings(&hash, &hash)
+                            }
+                            // levels padded with default values are never odd
+                            PaddingStrategy::DefaultValue => return None,
+                        },
+                    }
+                } else {
+                    self.hasher.hash_siblings(decommitments.next()?, &hash)
+                };
+                parents.push((index / 2, parent));
+                i += 1;
+            }
+
+            nodes = parents;
+            width = width.div_ceil(2);
+        }
+
+        match (decommitments.next(), &nodes[..]) {
+            (None, [(0, root)]) => Some(*root),
+            _ => None,
+        }
+    }
+}
+
+/// proof that a contiguous run of leafs is included in a tree
+/// the leafs and the index of the first one are given to
+/// [`verify_range`](crate::verify::verify_range) separately
+#[derive(Debug)]
+pub struct RangeProof<S: MerkleHasher = DefaultBuildHasher> {
+    /// hashes left and right of the range the verifier can't compute, in the
+    /// order they're needed
+    pub decommitments: Vec<S::Digest>,
+    /// number of leafs of the tree
+    pub width: usize,
+    /// how the tree completes odd levels, verification has to do the same
+    pub padding: PaddingStrategy,
+    pub hasher: S,
+}
+
+/// proof that a tree is an append only extension of an older version of it
+/// see RFC 6962 section 2.1.2, only trees shaped like RFC 6962 ones, i.e.
+/// padded with [`PaddingStrategy::PromoteOdd`], have consistency proofs
+#[derive(Debug)]
+pub struct ConsistencyProof<S: MerkleHasher = DefaultBuildHasher> {
+    /// roots of the subtrees needed to rebuild both roots
+    pub path: Vec<S::Digest>,
+    pub hasher: S,
+}
@@ -0,0 +1,191 @@
+//! compact binary encoding of proofs
+//!
+//! the layout is
+//! - the leaf digest
+//! - the root digest
+//! - the index of the leaf as an unsigned LEB128 varint
+//! - the number of leafs of the tree as an unsigned LEB128 varint
+//! - the padding strategy as one byte, `0` for default values, `1` for
+//!   duplicating the last node and `2` for promoting it
+//! - the number of steps as an unsigned LEB128 varint
+//! - one direction bit per step, packed least significant bit first into
+//!   `ceil(steps / 8)` bytes, `0` for left and `1` for right, unused bits zero
+//! - the sibling digest of every step, from the leaf up
+//!
+//! digests are written with [`Digest::write_bytes`].
+
+use alloc::vec::Vec;
+
+use super::{packed::pack_directions, MerkleProof, PackedPath, MAX_PACKED_LEN, MAX_PATH_LEN};
+use crate::{
+    error::MerkleError,
+    hash::{Digest, MerkleHasher},
+    root::{LeafHash, Root},
+    tree::PaddingStrategy,
+};
+
+impl<S: MerkleHasher> MerkleProof<S> {
+    /// encode the proof into its compact binary form
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let steps = self.path.len();
+        let len = S::Digest::LEN;
+        let mut out = Vec::with_capacity(2 * len + 31 + steps.div_ceil(8) + steps * len);
+
+        self.leaf.0.write_bytes(&mut out);
+        self.root.0.write_bytes(&mut out);
+        write_varint(&mut out, self.index as u64);
+        write_varint(&mut out, self.width as u64);
+        out.push(padding_byte(self.padding));
+        write_varint(&mut out, steps as u64);
+
+        // the bytes of the packed words, little endian, cut to the steps
+        for steps in self.path.chunks(MAX_PACKED_LEN) {
+            let word = pack_directions(steps).to_le_bytes();
+            out.extend_from_slice(&word[..steps.len().div_ceil(8)]);
+        }
+
+        for step in &self.path {
+            step.value.write_bytes(&mut out);
+        }
+
+        out
+    }
+
+    /// decode a proof from its compact binary form
+    /// the input has to contain exactly one proof, truncated input, trailing
+    /// bytes and paths longer than [`MAX_PATH_LEN`] are rejected
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MerkleError>
+    where
+        S: Default,
+    {
+        let len = S::Digest::LEN;
+        let mut reader = Reader { bytes };
+
+        let leaf = reader.digest::<S::Digest>()?;
+        let root = reader.digest::<S::Digest>()?;
+        let index = usize::try_from(reader.varint()?)
+            .map_err(|_| MerkleError::InvalidEncoding("leaf index is too large"))?;
+        let width = usize::try_from(reader.varint()?)
+            .map_err(|_| MerkleError::InvalidEncoding("tree size is too large"))?;
+        let padding = reader.padding()?;
+        let steps = usize::try_from(reader.varint()?)
+            .ok()
+            .filter(|steps| *steps <= MAX_PATH_LEN)
+            .ok_or(MerkleError::InvalidEncoding("proof path is too long"))?;
+
+        // check the size before allocating anything for the path
+        let expected = steps
+            .checked_mul(len)
+            .and_then(|values| values.checked_add(steps.div_ceil(8)))
+            .ok_or(MerkleError::InvalidEncoding("proof path is too long"))?;
+        let available = reader.bytes.len();
+        if available < expected {
+            return Err(MerkleError::Truncated {
+                needed: expected,
+                available,
+            });
+        }
+        if available > expected {
+            return Err(MerkleError::TrailingBytes(available - expected));
+        }
+
+        let mut word = [0; 8];
+        word[..steps.div_ceil(8)].copy_from_slice(reader.take(steps.div_ceil(8))?);
+        let directions = u64::from_le_bytes(word);
+        if directions.checked_shr(steps as u32).unwrap_or(0) != 0 {
+            return Err(MerkleError::InvalidEncoding(
+                "unused direction bits are set",
+            ));
+        }
+
+        let mut siblings = Vec::with_capacity(steps);
+        for _ in 0..steps {
+            siblings.push(reader.digest::<S::Digest>()?);
+        }
+        let path = PackedPath {
+            directions,
+            siblings,
+        }
+        .unpack();
+
+        Ok(MerkleProof {
+            path,
+            root: Root(root),
+            leaf: LeafHash(leaf),
+            index,
+            width,
+            padding,
+            hasher: S::default(),
+        })
+    }
+}
+
+pub(crate) fn padding_byte(padding: PaddingStrategy) -> u8 {
+    match padding {
+        PaddingStrategy::DefaultValue => 0,
+        PaddingStrategy::DuplicateLast => 1,
+        PaddingStrategy::PromoteOdd => 2,
+    }
+}
+
+pub(crate) fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+pub(crate) struct Reader<'a> {
+    pub(crate) bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn take(&mut self, n: usize) -> Result<&'a [u8], MerkleError> {
+        if self.bytes.len() < n {
+            return Err(MerkleError::Truncated {
+                needed: n,
+                available: self.bytes.len(),
+            });
+        }
+        let (head, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    pub(crate) fn digest<D: Digest>(&mut self) -> Result<D, MerkleError> {
+        let bytes = self.take(D::LEN)?;
+        D::from_slice(bytes).ok_or(MerkleError::InvalidEncoding("invalid digest"))
+    }
+
+    pub(crate) fn padding(&mut self) -> Result<PaddingStrategy, MerkleError> {
+        match self.take(1)?[0] {
+            0 => Ok(PaddingStrategy::DefaultValue),
+            1 => Ok(PaddingStrategy::DuplicateLast),
+            2 => Ok(PaddingStrategy::PromoteOdd),
+            _ => Err(MerkleError::InvalidEncoding("unknown padding strategy")),
+        }
+    }
+
+    pub(crate) fn varint(&mut self) -> Result<u64, MerkleError> {
+        let overflow = MerkleError::InvalidEncoding("varint does not fit into 64 bits");
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            let bits = u64::from(byte & 0x7f);
+            if shift == 63 && bits > 1 {
+                return Err(overflow);
+            }
+            value |= bits << shift;
+            if byte & 0x80 == 0 {
+                if byte == 0 && shift > 0 {
+                    return Err(MerkleError::InvalidEncoding(
+                        "varint is not minimally encoded",
+                    ));
+                }
+                return Ok(value);
+            }
+        }
+        Err(overflow)
+    }
+}
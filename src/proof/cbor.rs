@@ -0,0 +1,227 @@
+//! deterministic CBOR encoding of proofs, RFC 8949
+//!
+//! a proof is a map with text keys in the core deterministic order of
+//! section 4.2.1, shortest keys first
+//! ```text
+//! {"leaf": h'…', "path": [[0, h'…'], …], "root": h'…', "index": 5, "width": 8, "padding": 0}
+//! ```
+//! - digests are byte strings of [`Digest::write_bytes`]
+//! - every step of `path` is an array of the direction, `0` for left and `1`
+//!   for right, and the sibling digest, from the leaf up
+//! - `padding` is encoded like in the binary form, `0` for default values,
+//!   `1` for duplicating the last node and `2` for promoting it
+//!
+//! only this exact layout is accepted: integers and lengths in their shortest
+//! form, no indefinite lengths, no tags and no other keys.
+
+use alloc::vec::Vec;
+
+use super::{bytes::padding_byte, Direction, MerkleProof, Step, MAX_PATH_LEN};
+use crate::{
+    error::MerkleError,
+    hash::{Digest, MerkleHasher},
+    root::{LeafHash, Root},
+    tree::PaddingStrategy,
+};
+
+const UNSIGNED: u8 = 0;
+const BYTES: u8 = 2;
+const TEXT: u8 = 3;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+
+const KEYS: [&str; 6] = ["leaf", "path", "root", "index", "width", "padding"];
+
+impl<S: MerkleHasher> MerkleProof<S> {
+    /// encode the proof into its deterministic CBOR form
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let len = S::Digest::LEN;
+        let mut out = Vec::with_capacity(64 + 3 * len + self.path.len() * (len + 3));
+
+        write_head(&mut out, MAP, KEYS.len() as u64);
+        write_text(&mut out, "leaf");
+        write_digest(&mut out, &self.leaf.0);
+        write_text(&mut out, "path");
+        write_head(&mut out, ARRAY, self.path.len() as u64);
+        for step in &self.path {
+            write_head(&mut out, ARRAY, 2);
+            write_head(&mut out, UNSIGNED, u8::from(step.direction).into());
+            write_digest(&mut out, &step.value);
+        }
+        write_text(&mut out, "root");
+        write_digest(&mut out, &self.root.0);
+        write_text(&mut out, "index");
+        write_head(&mut out, UNSIGNED, self.index as u64);
+        write_text(&mut out, "width");
+        write_head(&mut out, UNSIGNED, self.width as u64);
+        write_text(&mut out, "padding");
+        write_head(&mut out, UNSIGNED, padding_byte(self.padding).into());
+
+        out
+    }
+
+    /// decode a proof from its CBOR form
+    /// the input has to contain exactly one proof, truncated input, trailing
+    /// bytes and paths longer than [`MAX_PATH_LEN`] are rejected
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, MerkleError>
+    where
+        S: Default,
+    {
+        let mut reader = Reader { bytes };
+        if reader.head(MAP)? != KEYS.len() as u64 {
+            return Err(MerkleError::InvalidEncoding("a proof is a map of 6 keys"));
+        }
+
+        reader.key("leaf")?;
+        let leaf = LeafHash(reader.digest()?);
+        reader.key("path")?;
+        let steps = reader.head(ARRAY)?;
+        if steps > MAX_PATH_LEN as u64 {
+            return Err(MerkleError::InvalidEncoding("proof path is too long"));
+        }
+        let mut path = Vec::with_capacity(steps as usize);
+        for _ in 0..steps {
+            if reader.head(ARRAY)? != 2 {
+                return Err(MerkleError::InvalidEncoding(
+                    "a step is an array of 2 items",
+                ));
+            }
+            let direction = u8::try_from(reader.head(UNSIGNED)?)
+                .map_err(|_| MerkleError::InvalidEncoding("unknown direction"))?;
+            let direction = Direction::try_from(direction)?;
+            let value = reader.digest()?;
+            path.push(Step { direction, value });
+        }
+        reader.key("root")?;
+        let root = Root(reader.digest()?);
+        reader.key("index")?;
+        let index = usize::try_from(reader.head(UNSIGNED)?)
+            .map_err(|_| MerkleError::InvalidEncoding("leaf index is too large"))?;
+        reader.key("width")?;
+        let width = usize::try_from(reader.head(UNSIGNED)?)
+            .map_err(|_| MerkleError::InvalidEncoding("tree size is too large"))?;
+        reader.key("padding")?;
+        let padding = match reader.head(UNSIGNED)? {
+            0 => PaddingStrategy::DefaultValue,
+            1 => PaddingStrategy::DuplicateLast,
+            2 => PaddingStrategy::PromoteOdd,
+            _ => return Err(MerkleError::InvalidEncoding("unknown padding strategy")),
+        };
+
+        if !reader.bytes.is_empty() {
+            return Err(MerkleError::TrailingBytes(reader.bytes.len()));
+        }
+        Ok(MerkleProof {
+            path,
+            root,
+            leaf,
+            index,
+            width,
+            padding,
+            hasher: S::default(),
+        })
+    }
+}
+
+/// the initial byte of an item and its argument in the shortest form
+fn write_head(out: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+    match value {
+        0..=23 => out.push(major | value as u8),
+        24..=0xff => out.extend_from_slice(&[major | 24, value as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend_from_slice(&(value as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend_from_slice(&(value as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+}
+
+fn write_text(out: &mut Vec<u8>, text: &str) {
+    write_head(out, TEXT, text.len() as u64);
+    out.extend_from_slice(text.as_bytes());
+}
+
+fn write_digest<D: Digest>(out: &mut Vec<u8>, digest: &D) {
+    write_head(out, BYTES, D::LEN as u64);
+    digest.write_bytes(out);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], MerkleError> {
+        if self.bytes.len() < n {
+            return Err(MerkleError::Truncated {
+                needed: n,
+                available: self.bytes.len(),
+            });
+        }
+        let (head, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    /// the argument of an item of the `major` type, it has to be in its
+    /// shortest form
+    fn head(&mut self, major: u8) -> Result<u64, MerkleError> {
+        let initial = self.take(1)?[0];
+        if initial >> 5 != major {
+            return Err(MerkleError::InvalidEncoding(match major {
+                UNSIGNED => "expected an unsigned integer",
+                BYTES => "expected a byte string",
+                TEXT => "expected a text string",
+                ARRAY => "expected an array",
+                _ => "expected a map",
+            }));
+        }
+        let n = match initial & 0x1f {
+            info @ 0..=23 => return Ok(info.into()),
+            24 => 1,
+            25 => 2,
+            26 => 4,
+            27 => 8,
+            _ => {
+                return Err(MerkleError::InvalidEncoding(
+                    "indefinite or reserved length",
+                ))
+            }
+        };
+        let value = self
+            .take(n)?
+            .iter()
+            .fold(0u64, |value, byte| value << 8 | u64::from(*byte));
+        // the smallest value that doesn't fit into the next shorter form
+        let min = if n == 1 { 24 } else { 1 << (4 * n) };
+        if value < min {
+            return Err(MerkleError::InvalidEncoding(
+                "integer is not in its shortest form",
+            ));
+        }
+        Ok(value)
+    }
+
+    fn key(&mut self, key: &str) -> Result<(), MerkleError> {
+        let len = self.head(TEXT)?;
+        if len != key.len() as u64 || self.take(key.len())? != key.as_bytes() {
+            return Err(MerkleError::InvalidEncoding("unexpected key"));
+        }
+        Ok(())
+    }
+
+    fn digest<D: Digest>(&mut self) -> Result<D, MerkleError> {
+        if self.head(BYTES)? != D::LEN as u64 {
+            return Err(MerkleError::InvalidEncoding("digest has the wrong length"));
+        }
+        D::from_slice(self.take(D::LEN)?).ok_or(MerkleError::InvalidEncoding("invalid digest"))
+    }
+}
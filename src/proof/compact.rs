@@ -0,0 +1,155 @@
+//! proofs that leave out the siblings made of padding only
+//!
+//! in a sparsely filled tree most siblings of a path are roots of subtrees
+//! that hold nothing but padding leafs. both sides can compute those from the
+//! padding leaf, so a compact proof marks them with their height instead.
+
+use alloc::vec::Vec;
+
+use super::{MerkleProof, Step};
+use crate::{
+    error::MerkleError,
+    hash::{DefaultBuildHasher, EmptyHashes, MerkleHasher},
+    root::{LeafHash, Root},
+    tree::PaddingStrategy,
+    verify::path_levels,
+};
+
+/// one step of a compact path
+#[derive(Debug)]
+pub enum CompactStep<D = u64> {
+    /// a sibling the verifier has to be given
+    Sibling(Step<D>),
+    /// the sibling is an empty subtree of `height`, its side follows from the
+    /// index of the leaf
+    Empty { height: usize },
+}
+
+/// a [`MerkleProof`] whose padding siblings are replaced by their height
+#[derive(Debug)]
+pub struct CompactProof<S: MerkleHasher = DefaultBuildHasher> {
+    pub path: Vec<CompactStep<S::Digest>>,
+    pub root: Root<S::Digest>,
+    pub leaf: LeafHash<S::Digest>,
+    pub index: usize,
+    pub width: usize,
+    pub padding: PaddingStrategy,
+    pub hasher: S,
+}
+
+impl<S: MerkleHasher> MerkleProof<S> {
+    /// replace the siblings that equal the root of an empty subtree of their
+    /// height by a marker
+    pub fn compact(self, empty: &EmptyHashes<S::Digest>) -> CompactProof<S> {
+        let levels = path_levels(self.index, self.width, self.padding);
+        let path = self
+            .path
+            .into_iter()
+            .zip(
+                levels
+                    .map(|(height, _)| height)
+                    .chain(core::iter::repeat(usize::MAX)),
+            )
+            .map(|(step, height)| match empty.get(height) {
+                Some(hash) if *hash == step.value => CompactStep::Empty { height },
+                _ => CompactStep::Sibling(step),
+            })
+            .collect();
+
+        CompactProof {
+            path,
+            root: self.root,
+            leaf: self.leaf,
+            index: self.index,
+            width: self.width,
+            padding: self.padding,
+            hasher: self.hasher,
+        }
+    }
+}
+
+impl<S: MerkleHasher> CompactProof<S> {
+    /// put the empty subtree roots back in
+    /// a marker for another height than the one of its step, or for a height
+    /// missing from `empty`, is an error
+    pub fn expand(self, empty: &EmptyHashes<S::Digest>) -> Result<MerkleProof<S>, MerkleError> {
+        Ok(MerkleProof {
+            path: self.expanded_path(empty)?,
+            root: self.root,
+            leaf: self.leaf,
+            index: self.index,
+            width: self.width,
+            padding: self.padding,
+            hasher: self.hasher,
+        })
+    }
+
+    /// number of hashes in the path, the markers excluded
+    pub fn siblings(&self) -> usize {
+        self.path
+            .iter()
+            .filter(|step| matches!(step, CompactStep::Sibling(_)))
+            .count()
+    }
+
+    /// expand the proof with `empty` and [`verify`](MerkleProof::verify) it
+    /// against its own root
+    #[deprecated(note = "use `CompactProof::verify_against` with a trusted root")]
+    pub fn verify(&self, empty: &EmptyHashes<S::Digest>) -> bool
+    where
+        S: Clone,
+    {
+        self.verify_against(&self.root, empty)
+    }
+
+    /// expand the proof with `empty` and check it against a root the
+    /// verifier trusts, see [`MerkleProof::verify_against`]
+    pub fn verify_against(&self, root: &Root<S::Digest>, empty: &EmptyHashes<S::Digest>) -> bool
+    where
+        S: Clone,
+    {
+        let Ok(path) = self.expanded_path(empty) else {
+            return false;
+        };
+        let proof = MerkleProof {
+            path,
+            root: self.root,
+            leaf: self.leaf,
+            index: self.index,
+            width: self.width,
+            padding: self.padding,
+            hasher: self.hasher.clone(),
+        };
+        proof.verify_against(root)
+    }
+
+    fn expanded_path(
+        &self,
+        empty: &EmptyHashes<S::Digest>,
+    ) -> Result<Vec<Step<S::Digest>>, MerkleError> {
+        let mut levels = path_levels(self.index, self.width, self.padding);
+        self.path
+            .iter()
+            .map(|step| match step {
+                CompactStep::Sibling(step) => {
+                    levels.next();
+                    Ok(Step {
+                        direction: step.direction,
+                        value: step.value,
+                    })
+                }
+                CompactStep::Empty { height } => match levels.next() {
+                    Some((level, direction)) if level == *height => Ok(Step {
+                        direction,
+                        value: *empty.get(level).ok_or(MerkleError::InvalidEncoding(
+                            "empty subtree higher than the table",
+                        ))?,
+                    }),
+                    _ => Err(MerkleError::InvalidEncoding(
+                        "empty subtree at the wrong height",
+                    )),
+                },
+            })
+            .collect()
+    }
+}
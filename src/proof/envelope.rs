@@ -0,0 +1,77 @@
+//! versioned binary envelope around proofs, for proofs that are stored or
+//! sent between builds of the crate
+//!
+//! the layout is
+//! - the magic bytes `MRKP` and the format version as one byte
+//! - the length of a digest as an unsigned LEB128 varint
+//! - the hash of two all zero digests as siblings, which tells hash backends
+//!   of the same digest length apart
+//! - the proof in the layout of its format version
+//!
+//! version 1 holds the compact binary form of [`MerkleProof::to_bytes`].
+
+use alloc::vec::Vec;
+
+use super::{
+    bytes::{write_varint, Reader},
+    MerkleProof,
+};
+use crate::{
+    error::MerkleError,
+    hash::{zero, Digest, MerkleHasher},
+};
+
+pub(super) const MAGIC: &[u8; 4] = b"MRKP";
+/// format version [`MerkleProof::encode`] writes
+pub const PROOF_FORMAT_VERSION: u8 = 1;
+
+impl<S: MerkleHasher> MerkleProof<S> {
+    /// encode the proof in the latest format version, with the envelope that
+    /// names the version and the hash backend
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(16 + S::Digest::LEN);
+        out.extend_from_slice(MAGIC);
+        out.push(PROOF_FORMAT_VERSION);
+        write_varint(&mut out, S::Digest::LEN as u64);
+        fingerprint(&self.hasher).write_bytes(&mut out);
+        out.extend_from_slice(&self.to_bytes());
+        out
+    }
+
+    /// decode a proof written by [`encode`] of any supported format version
+    /// a version after the latest one is
+    /// [`UnsupportedVersion`](MerkleError::UnsupportedVersion), a proof of
+    /// another hash backend [`WrongBackend`](MerkleError::WrongBackend)
+    ///
+    /// [`encode`]: MerkleProof::encode
+    pub fn decode(bytes: &[u8]) -> Result<Self, MerkleError>
+    where
+        S: Default,
+    {
+        let mut reader = Reader { bytes };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(MerkleError::InvalidEncoding("not an encoded proof"));
+        }
+        match reader.take(1)?[0] {
+            1 => {}
+            version => {
+                return Err(MerkleError::UnsupportedVersion {
+                    version,
+                    latest: PROOF_FORMAT_VERSION,
+                })
+            }
+        }
+
+        if reader.varint()? != S::Digest::LEN as u64 {
+            return Err(MerkleError::WrongBackend);
+        }
+        if reader.digest::<S::Digest>()? != fingerprint(&S::default()) {
+            return Err(MerkleError::WrongBackend);
+        }
+        Self::from_bytes(reader.bytes)
+    }
+}
+
+pub(super) fn fingerprint<S: MerkleHasher>(hasher: &S) -> S::Digest {
+    hasher.hash_siblings(&zero(), &zero())
+}
@@ -0,0 +1,296 @@
+//! canonical json encoding of proofs
+//!
+//! a proof is written as a single line object with the fields in this order
+//! ```json
+//! {"leaf":"0x…","root":"0x…","index":5,"width":8,"padding":"default_value","path":[{"dir":"left","hash":"0x…"}]}
+//! ```
+//! - digests are the `0x` prefixed lowercase hex of [`Digest::write_bytes`],
+//!   whatever the width of the digest
+//! - `index` and `width` are plain decimal integers
+//! - `padding` is one of `default_value`, `duplicate_last` or `promote_odd`
+//! - `path` lists the steps from the leaf up, `dir` is `left` or `right`
+//!
+//! parsing accepts whitespace between tokens and uppercase hex, every field
+//! is required and unknown fields are rejected.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write;
+
+use super::{Direction, MerkleProof, Step, MAX_PATH_LEN};
+use crate::{
+    error::MerkleError,
+    hash::{Digest, MerkleHasher},
+    hex,
+    root::{LeafHash, Root},
+    tree::PaddingStrategy,
+};
+
+impl<S: MerkleHasher> MerkleProof<S> {
+    /// encode the proof into its canonical json form
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        out.push_str(r#"{"leaf":""#);
+        write_hex(&mut out, &self.leaf.0);
+        out.push_str(r#"","root":""#);
+        write_hex(&mut out, &self.root.0);
+        let _ = write!(
+            out,
+            r#"","index":{},"width":{},"padding":"{}","path":["#,
+            self.index,
+            self.width,
+            padding_name(self.padding)
+        );
+        for (i, step) in self.path.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(out, r#"{{"dir":"{}","hash":""#, step.direction);
+            write_hex(&mut out, &step.value);
+            out.push_str(r#""}"#);
+        }
+        out.push_str("]}");
+        out
+    }
+
+    /// decode a proof from its json form
+    pub fn from_json(json: &str) -> Result<Self, MerkleError>
+    where
+        S: Default,
+    {
+        let mut parser = Parser { rest: json };
+        let mut proof = parser.object()?;
+        parser.skip_whitespace();
+        if !parser.rest.is_empty() {
+            return Err(invalid("trailing characters after the proof"));
+        }
+
+        let leaf = LeafHash(read_hex(&proof.take("leaf")?.string("leaf")?, "leaf")?);
+        let root = Root(read_hex(&proof.take("root")?.string("root")?, "root")?);
+        let index = proof.take("index")?.number("index")?;
+        let width = proof.take("width")?.number("width")?;
+        let padding = match proof.take("padding")?.string("padding")?.as_str() {
+            "default_value" => PaddingStrategy::DefaultValue,
+            "duplicate_last" => PaddingStrategy::DuplicateLast,
+            "promote_odd" => PaddingStrategy::PromoteOdd,
+            other => return Err(invalid(format!("unknown padding strategy {other:?}"))),
+        };
+        let steps = match proof.take("path")? {
+            Value::Array(steps) => steps,
+            _ => return Err(invalid("\"path\" has to be an array")),
+        };
+        proof.finish()?;
+        if steps.len() > MAX_PATH_LEN {
+            return Err(invalid(format!(
+                "\"path\" has more than {MAX_PATH_LEN} steps"
+            )));
+        }
+
+        let path = steps
+            .into_iter()
+            .enumerate()
+            .map(|(i, step)| {
+                let Value::Object(mut step) = step else {
+                    return Err(invalid(format!("step {i} has to be an object")));
+                };
+                let direction: Direction = step.take("dir")?.string("dir")?.parse()?;
+                let value = read_hex(&step.take("hash")?.string("hash")?, "hash")
+                    .map_err(|err| at_step(err, i))?;
+                step.finish()?;
+                Ok(Step { direction, value })
+            })
+            .collect::<Result<_, MerkleError>>()?;
+
+        Ok(MerkleProof {
+            path,
+            root,
+            leaf,
+            index,
+            width,
+            padding,
+            hasher: S::default(),
+        })
+    }
+}
+
+fn padding_name(padding: PaddingStrategy) -> &'static str {
+    match padding {
+        PaddingStrategy::DefaultValue => "default_value",
+        PaddingStrategy::DuplicateLast => "duplicate_last",
+        PaddingStrategy::PromoteOdd => "promote_odd",
+    }
+}
+
+fn invalid(reason: impl ToString) -> MerkleError {
+    MerkleError::InvalidJson(reason.to_string())
+}
+
+fn at_step(err: MerkleError, step: usize) -> MerkleError {
+    match err {
+        MerkleError::InvalidJson(reason) => invalid(format!("step {step}: {reason}")),
+        err => err,
+    }
+}
+
+fn write_hex<D: Digest>(out: &mut String, digest: &D) {
+    out.push_str("0x");
+    let _ = hex::write(out, digest);
+}
+
+fn read_hex<D: Digest>(s: &str, field: &str) -> Result<D, MerkleError> {
+    let digits = s
+        .strip_prefix("0x")
+        .ok_or_else(|| invalid(format!("{field:?} is missing the 0x prefix")))?;
+    hex::parse(digits).map_err(|reason| invalid(format!("{field:?} {reason}")))
+}
+
+/// the subset of json values proofs are made of
+enum Value {
+    String(String),
+    Number(usize),
+    Array(Vec<Value>),
+    Object(Object),
+}
+
+impl Value {
+    fn string(self, field: &str) -> Result<String, MerkleError> {
+        match self {
+            Value::String(s) => Ok(s),
+            _ => Err(invalid(format!("{field:?} has to be a string"))),
+        }
+    }
+
+    fn number(self, field: &str) -> Result<usize, MerkleError> {
+        match self {
+            Value::Number(n) => Ok(n),
+            _ => Err(invalid(format!("{field:?} has to be a number"))),
+        }
+    }
+}
+
+struct Object(Vec<(String, Value)>);
+
+impl Object {
+    fn take(&mut self, key: &str) -> Result<Value, MerkleError> {
+        let at = self.0.iter().position(|(name, _)| name == key);
+        let at = at.ok_or_else(|| invalid(format!("missing field {key:?}")))?;
+        Ok(self.0.remove(at).1)
+    }
+
+    /// reject the fields that were not taken
+    fn finish(self) -> Result<(), MerkleError> {
+        match self.0.first() {
+            Some((key, _)) => Err(invalid(format!("unknown field {key:?}"))),
+            None => Ok(()),
+        }
+    }
+}
+
+struct Parser<'a> {
+    rest: &'a str,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        self.rest = self.rest.trim_start_matches([' ', '\t', '\n', '\r']);
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        match self.rest.strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), MerkleError> {
+        if self.eat(c) {
+            return Ok(());
+        }
+        match self.rest.chars().next() {
+            Some(found) => Err(invalid(format!("expected {c:?} but found {found:?}"))),
+            None => Err(invalid(format!("expected {c:?} but the input ended"))),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, MerkleError> {
+        self.skip_whitespace();
+        match self.rest.chars().next() {
+            Some('"') => self.string().map(Value::String),
+            Some('{') => self.object().map(Value::Object),
+            Some('[') => {
+                self.expect('[')?;
+                let mut values = Vec::new();
+                if !self.eat(']') {
+                    loop {
+                        values.push(self.value()?);
+                        if self.eat(']') {
+                            break;
+                        }
+                        self.expect(',')?;
+                    }
+                }
+                Ok(Value::Array(values))
+            }
+            Some('0'..='9') => {
+                let len = self
+                    .rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(self.rest.len());
+                let (digits, rest) = self.rest.split_at(len);
+                self.rest = rest;
+                if digits.len() > 1 && digits.starts_with('0') {
+                    return Err(invalid(format!("number {digits} has leading zeros")));
+                }
+                let number = digits
+                    .parse()
+                    .map_err(|_| invalid(format!("number {digits} is too large")))?;
+                Ok(Value::Number(number))
+            }
+            Some(found) => Err(invalid(format!("unexpected {found:?}"))),
+            None => Err(invalid("unexpected end of input")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Object, MerkleError> {
+        self.expect('{')?;
+        let mut fields: Vec<(String, Value)> = Vec::new();
+        if !self.eat('}') {
+            loop {
+                self.skip_whitespace();
+                let key = self.string()?;
+                if fields.iter().any(|(name, _)| *name == key) {
+                    return Err(invalid(format!("duplicate field {key:?}")));
+                }
+                self.expect(':')?;
+                fields.push((key, self.value()?));
+                if self.eat('}') {
+                    break;
+                }
+                self.expect(',')?;
+            }
+        }
+        Ok(Object(fields))
+    }
+
+    /// proofs never contain escaped characters, so escapes are rejected
+    fn string(&mut self) -> Result<String, MerkleError> {
+        self.expect('"')?;
+        let end = self
+            .rest
+            .find(['"', '\\'])
+            .ok_or_else(|| invalid("unterminated string"))?;
+        let (s, rest) = self.rest.split_at(end);
+        if rest.starts_with('\\') {
+            return Err(invalid("escaped characters are not supported"));
+        }
+        self.rest = &rest[1..];
+        Ok(s.to_string())
+    }
+}
@@ -0,0 +1,93 @@
+//! paths with their directions packed into one word
+//!
+//! bit `i` of the word is the side of the sibling at level `i` from the leaf
+//! up, `0` for left and `1` for right like [`u8::from`] of a [`Direction`].
+//! a path of [`MAX_PATH_LEN`](super::MAX_PATH_LEN) steps or less always fits.
+
+use alloc::vec::Vec;
+
+use super::{Direction, MerkleProof, Step};
+use crate::hash::MerkleHasher;
+
+/// most steps a packed path can have
+pub const MAX_PACKED_LEN: usize = u64::BITS as usize;
+
+/// a path as direction bits and the sibling digests from the leaf up
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PackedPath<D = u64> {
+    /// the side of every sibling, bits beyond the last sibling are zero
+    pub directions: u64,
+    pub siblings: Vec<D>,
+}
+
+impl<D: Copy> PackedPath<D> {
+    /// pack the directions of `path`, none if it has more than
+    /// [`MAX_PACKED_LEN`] steps
+    pub fn pack(path: &[Step<D>]) -> Option<Self> {
+        if path.len() > MAX_PACKED_LEN {
+            return None;
+        }
+        Some(Self {
+            directions: pack_directions(path),
+            siblings: path.iter().map(|step| step.value).collect(),
+        })
+    }
+
+    /// the steps of the path, the inverse of [`pack`](PackedPath::pack)
+    pub fn unpack(&self) -> Vec<Step<D>> {
+        self.steps().collect()
+    }
+
+    /// the steps of the path without collecting them
+    pub fn steps(&self) -> impl Iterator<Item = Step<D>> + '_ {
+        self.siblings
+            .iter()
+            .enumerate()
+            .map(|(level, &value)| Step {
+                direction: self.direction(level),
+                value,
+            })
+    }
+
+    /// side of the sibling at `level`, levels beyond the word are left
+    pub fn direction(&self, level: usize) -> Direction {
+        let bit = u32::try_from(level)
+            .ok()
+            .and_then(|level| self.directions.checked_shr(level))
+            .unwrap_or(0);
+        if bit & 1 == 1 {
+            Direction::Right
+        } else {
+            Direction::Left
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.siblings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.siblings.is_empty()
+    }
+}
+
+impl<S: MerkleHasher> MerkleProof<S> {
+    /// the directions of the path as one word, see [`PackedPath`]
+    /// none for paths longer than [`MAX_PACKED_LEN`], no tree is that high
+    pub fn packed_directions(&self) -> Option<u64> {
+        (self.path.len() <= MAX_PACKED_LEN).then(|| pack_directions(&self.path))
+    }
+
+    /// the path with its directions packed, see [`PackedPath::pack`]
+    pub fn packed_path(&self) -> Option<PackedPath<S::Digest>> {
+        PackedPath::pack(&self.path)
+    }
+}
+
+/// the direction bits of at most [`MAX_PACKED_LEN`] steps
+pub(crate) fn pack_directions<D>(path: &[Step<D>]) -> u64 {
+    debug_assert!(path.len() <= MAX_PACKED_LEN, "a word holds 64 directions");
+    path.iter().enumerate().fold(0, |word, (level, step)| {
+        word | u64::from(u8::from(step.direction)) << level
+    })
+}
@@ -0,0 +1,301 @@
+//! proofs written to and read from byte streams one step at a time
+//!
+//! the bytes are the [envelope](MerkleProof::encode) of the proof, so a proof
+//! written with [`write_to`](MerkleProof::write_to) decodes with
+//! [`decode`](MerkleProof::decode) and the bytes of `encode` read with a
+//! [`ProofReader`]. the reader takes the header in [`new`](ProofReader::new)
+//! and yields the siblings as they arrive, nothing after the last one is read.
+//!
+//! malformed input is an [`InvalidData`](io::ErrorKind::InvalidData) error
+//! holding the [`MerkleError`], input that ends too early an
+//! [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) error. reads are small, a
+//! [`BufReader`](std::io::BufReader) in front of unbuffered readers pays off.
+
+use std::io::{self, Read, Write};
+
+use alloc::{vec, vec::Vec};
+
+use super::{
+    bytes::{padding_byte, write_varint, Reader},
+    envelope::{fingerprint, MAGIC},
+    packed::pack_directions,
+    Direction, MerkleProof, Step, MAX_PACKED_LEN, MAX_PATH_LEN, PROOF_FORMAT_VERSION,
+};
+use crate::{
+    error::MerkleError,
+    hash::{DefaultBuildHasher, Digest, MerkleHasher},
+    root::{LeafHash, Root},
+    tree::PaddingStrategy,
+    verify::directions,
+};
+
+impl<S: MerkleHasher> MerkleProof<S> {
+    /// write the [envelope](MerkleProof::encode) of the proof to `writer`,
+    /// the path one sibling at a time
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        let mut out = Vec::with_capacity(48 + 2 * S::Digest::LEN);
+        out.extend_from_slice(MAGIC);
+        out.push(PROOF_FORMAT_VERSION);
+        write_varint(&mut out, S::Digest::LEN as u64);
+        fingerprint(&self.hasher).write_bytes(&mut out);
+        self.leaf.0.write_bytes(&mut out);
+        self.root.0.write_bytes(&mut out);
+        write_varint(&mut out, self.index as u64);
+        write_varint(&mut out, self.width as u64);
+        out.push(padding_byte(self.padding));
+        write_varint(&mut out, self.path.len() as u64);
+        for steps in self.path.chunks(MAX_PACKED_LEN) {
+            let word = pack_directions(steps).to_le_bytes();
+            out.extend_from_slice(&word[..steps.len().div_ceil(8)]);
+        }
+        writer.write_all(&out)?;
+
+        for step in &self.path {
+            out.clear();
+            step.value.write_bytes(&mut out);
+            writer.write_all(&out)?;
+        }
+        Ok(())
+    }
+}
+
+/// the steps of a proof read from a byte stream as they are needed
+pub struct ProofReader<R, S: MerkleHasher = DefaultBuildHasher> {
+    reader: R,
+    leaf: LeafHash<S::Digest>,
+    root: Root<S::Digest>,
+    index: usize,
+    width: usize,
+    padding: PaddingStrategy,
+    len: usize,
+    directions: u64,
+    /// steps read so far
+    read: usize,
+    /// room for the bytes of one digest
+    buf: Vec<u8>,
+    hasher: S,
+}
+
+impl<R: Read, S: MerkleHasher + Default> ProofReader<R, S> {
+    /// read the header of a proof up to its first sibling
+    /// proofs of another backend or format version are rejected before the
+    /// leaf is read
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut buf = vec![0; MAGIC.len().max(S::Digest::LEN)];
+        reader.read_exact(&mut buf[..MAGIC.len()])?;
+        if buf[..MAGIC.len()] != MAGIC[..] {
+            return Err(invalid(MerkleError::InvalidEncoding(
+                "not an encoded proof",
+            )));
+        }
+        match read_byte(&mut reader)? {
+            1 => {}
+            version => {
+                return Err(invalid(MerkleError::UnsupportedVersion {
+                    version,
+                    latest: PROOF_FORMAT_VERSION,
+                }))
+            }
+        }
+        let hasher = S::default();
+        if read_varint(&mut reader)? != S::Digest::LEN as u64 {
+            return Err(invalid(MerkleError::WrongBackend));
+        }
+        if read_digest::<S::Digest>(&mut reader, &mut buf)? != fingerprint(&hasher) {
+            return Err(invalid(MerkleError::WrongBackend));
+        }
+
+        let leaf = read_digest(&mut reader, &mut buf)?;
+        let root = read_digest(&mut reader, &mut buf)?;
+        let index = usize::try_from(read_varint(&mut reader)?)
+            .map_err(|_| invalid(MerkleError::InvalidEncoding("leaf index is too large")))?;
+        let width = usize::try_from(read_varint(&mut reader)?)
+            .map_err(|_| invalid(MerkleError::InvalidEncoding("tree size is too large")))?;
+        let padding = Reader {
+            bytes: &[read_byte(&mut reader)?],
+        }
+        .padding()
+        .map_err(invalid)?;
+        let len = usize::try_from(read_varint(&mut reader)?)
+            .ok()
+            .filter(|len| *len <= MAX_PATH_LEN)
+            .ok_or_else(|| invalid(MerkleError::InvalidEncoding("proof path is too long")))?;
+
+        let mut word = [0; 8];
+        reader.read_exact(&mut word[..len.div_ceil(8)])?;
+        let directions = u64::from_le_bytes(word);
+        if directions.checked_shr(len as u32).unwrap_or(0) != 0 {
+            return Err(invalid(MerkleError::InvalidEncoding(
+                "unused direction bits are set",
+            )));
+        }
+
+        Ok(Self {
+            reader,
+            leaf: LeafHash(leaf),
+            root: Root(root),
+            index,
+            width,
+            padding,
+            len,
+            directions,
+            read: 0,
+            buf,
+            hasher,
+        })
+    }
+}
+
+impl<R, S: MerkleHasher> ProofReader<R, S> {
+    /// the leaf the proof claims
+    pub fn leaf(&self) -> LeafHash<S::Digest> {
+        self.leaf
+    }
+
+    /// the root the proof claims, see [`verify_streaming`] for checking it
+    /// against a trusted one
+    ///
+    /// [`verify_streaming`]: crate::verify::verify_streaming
+    pub fn root(&self) -> Root<S::Digest> {
+        self.root
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn padding(&self) -> PaddingStrategy {
+        self.padding
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hasher
+    }
+
+    /// number of steps of the path, read or not
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// the direction of every step, known before any sibling is read
+    pub fn directions(&self) -> impl Iterator<Item = Direction> + '_ {
+        (0..self.len).map(|level| direction(self.directions, level))
+    }
+
+    /// the reader, positioned after the steps read so far
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read, S: MerkleHasher> ProofReader<R, S> {
+    /// fold the rest of the path onto `leaf`, for a reader that was not
+    /// advanced this is the root the path leads to
+    pub fn fold(mut self, leaf: &S::Digest) -> io::Result<S::Digest> {
+        let mut acc = *leaf;
+        while self.read < self.len {
+            let step = self.read_step()?;
+            acc = match step.direction {
+                Direction::Left => self.hasher.hash_siblings(&step.value, &acc),
+                Direction::Right => self.hasher.hash_siblings(&acc, &step.value),
+            };
+        }
+        Ok(acc)
+    }
+
+    fn read_step(&mut self) -> io::Result<Step<S::Digest>> {
+        let level = self.read;
+        // after a failed read the position in the stream is unknown
+        self.read = self.len;
+        let value = read_digest(&mut self.reader, &mut self.buf)?;
+        self.read = level + 1;
+        Ok(Step {
+            direction: direction(self.directions, level),
+            value,
+        })
+    }
+}
+
+/// the remaining steps, after an error there are none
+impl<R: Read, S: MerkleHasher> Iterator for ProofReader<R, S> {
+    type Item = io::Result<Step<S::Digest>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        (self.read < self.len).then(|| self.read_step())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.len - self.read;
+        (left, Some(left))
+    }
+}
+
+/// check that the path `reader` yields leads from `leaf` to `root` and that
+/// its shape agrees with the index and tree size it claims, like
+/// [`MerkleProof::verify_against`]
+/// the shape is checked before any sibling is read and the path is folded as
+/// it arrives, only one sibling is in memory at a time
+pub fn verify_streaming<R: Read, S: MerkleHasher>(
+    leaf: &LeafHash<S::Digest>,
+    root: &Root<S::Digest>,
+    reader: ProofReader<R, S>,
+) -> io::Result<bool> {
+    let shape = reader.index < reader.width
+        && reader
+            .directions()
+            .eq(directions(reader.index, reader.width, reader.padding));
+    if !shape {
+        return Ok(false);
+    }
+    Ok(reader.fold(&leaf.0)?.ct_eq(&root.0))
+}
+
+fn direction(directions: u64, level: usize) -> Direction {
+    if directions >> level & 1 == 1 {
+        Direction::Right
+    } else {
+        Direction::Left
+    }
+}
+
+fn invalid(err: MerkleError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+fn read_byte(reader: &mut impl Read) -> io::Result<u8> {
+    let mut byte = [0];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn read_digest<D: Digest>(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<D> {
+    let bytes = &mut buf[..D::LEN];
+    reader.read_exact(bytes)?;
+    D::from_slice(bytes).ok_or_else(|| invalid(MerkleError::InvalidEncoding("invalid digest")))
+}
+
+/// read the bytes of one varint and decode them like the binary form does
+fn read_varint(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 10];
+    for len in 1..=bytes.len() {
+        bytes[len - 1] = read_byte(reader)?;
+        if bytes[len - 1] & 0x80 == 0 {
+            return Reader {
+                bytes: &bytes[..len],
+            }
+            .varint()
+            .map_err(invalid);
+        }
+    }
+    Err(invalid(MerkleError::InvalidEncoding(
+        "varint does not fit into 64 bits",
+    )))
+}
@@ -0,0 +1,80 @@
+//! proofs that a single leaf changed, from which the new root follows
+//!
+//! the siblings on the path of a leaf don't depend on the leaf itself, so the
+//! path that shows the old leaf under the old root also leads from the new
+//! leaf to the new root. a verifier that only holds the current root checks
+//! the old leaf and folds the new one up the same path. the one exception is
+//! the last node of an odd level under [`DuplicateLast`], its sibling is a
+//! copy of itself and changes with it.
+//!
+//! [`DuplicateLast`]: PaddingStrategy::DuplicateLast
+
+use super::{Direction, MerkleProof};
+use crate::{
+    error::{MerkleError, ProofError},
+    hash::{DefaultBuildHasher, LeafHasher, MaybeSync, MerkleHasher},
+    root::{LeafHash, Root},
+    tree::{MerkleTree, PaddingStrategy},
+    verify::fold_path,
+};
+
+/// proof that the leaf at [`index`](MerkleProof::index) of `proof` was
+/// replaced by `new_leaf`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateProof<S: MerkleHasher = DefaultBuildHasher> {
+    /// proof of the old leaf, its root is the one before the update
+    pub proof: MerkleProof<S>,
+    /// the leaf after the update
+    pub new_leaf: LeafHash<S::Digest>,
+}
+
+impl<S: MerkleHasher> UpdateProof<S> {
+    /// check the old leaf against the trusted `old_root` and return the root
+    /// after the update, the root stored in the proof is ignored
+    pub fn apply(
+        &self,
+        old_root: &Root<S::Digest>,
+    ) -> Result<Root<S::Digest>, ProofError<S::Digest>> {
+        self.proof.check(old_root)?;
+        if self.proof.padding != PaddingStrategy::DuplicateLast {
+            return Ok(Root(fold_path(
+                &self.proof.hasher,
+                &self.new_leaf.0,
+                &self.proof.path,
+            )));
+        }
+
+        // every level has a step, so the position follows the path
+        let (mut node, mut index, mut width) =
+            (self.new_leaf.0, self.proof.index, self.proof.width);
+        for step in &self.proof.path {
+            let sibling = if index == width - 1 && index & 1 == 0 {
+                node
+            } else {
+                step.value
+            };
+            node = match step.direction {
+                Direction::Left => self.proof.hasher.hash_siblings(&sibling, &node),
+                Direction::Right => self.proof.hasher.hash_siblings(&node, &sibling),
+            };
+            index /= 2;
+            width = width.div_ceil(2);
+        }
+        Ok(Root(node))
+    }
+}
+
+impl<T: Default + MaybeSync, S: LeafHasher<T> + Clone> MerkleTree<T, S> {
+    /// like [`update`](MerkleTree::update) but returns the proof a verifier
+    /// that only knows the old root needs to compute the new one
+    pub fn update_with_proof(
+        &mut self,
+        index: usize,
+        value: T,
+    ) -> Result<UpdateProof<S>, MerkleError> {
+        let proof = self.get_proof(index)?;
+        let new_leaf = LeafHash(self.hasher().hash_leaf(&value));
+        self.update(index, value)?;
+        Ok(UpdateProof { proof, new_leaf })
+    }
+}
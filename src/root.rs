@@ -0,0 +1,114 @@
+use alloc::format;
+use core::{fmt, str::FromStr};
+
+use crate::{error::MerkleError, hash::Digest, hex};
+
+/// the root of a tree, kept apart from leaf hashes and other digests
+///
+/// it formats as zero padded hex and parses back from it. `{}` and `{:x}`
+/// print the digits only, `{:#x}` prefixes them with `0x`. parsing accepts
+/// both forms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Root<D = u64>(pub D);
+
+impl<D> Root<D> {
+    pub fn into_inner(self) -> D {
+        self.0
+    }
+}
+
+impl<D: Digest> Root<D> {
+    /// equality in constant time, see [`Digest::ct_eq`]
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl<D> From<D> for Root<D> {
+    fn from(digest: D) -> Self {
+        Self(digest)
+    }
+}
+
+impl<D: Digest> fmt::LowerHex for Root<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        hex::write(f, &self.0)
+    }
+}
+
+impl<D: Digest> fmt::Display for Root<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        hex::write(f, &self.0)
+    }
+}
+
+impl<D: Digest> FromStr for Root<D> {
+    type Err = MerkleError;
+
+    fn from_str(s: &str) -> Result<Self, MerkleError> {
+        let digits = s.strip_prefix("0x").unwrap_or(s);
+        hex::parse(digits)
+            .map(Root)
+            .map_err(|reason| MerkleError::InvalidHex(format!("root {reason}")))
+    }
+}
+
+/// the hash of a leaf, kept apart from roots and inner nodes
+///
+/// it formats and parses like [`Root`]. a proof can't be checked against its
+/// own leaf:
+/// ```compile_fail
+/// let tree = merkle::MerkleTree::from(vec![1u32, 2, 3]);
+/// let proof = tree.get_proof(0).unwrap();
+/// proof.verify_against(&proof.leaf);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct LeafHash<D = u64>(pub D);
+
+impl<D> LeafHash<D> {
+    pub fn into_inner(self) -> D {
+        self.0
+    }
+}
+
+impl<D: Digest> LeafHash<D> {
+    /// equality in constant time, see [`Digest::ct_eq`]
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl<D> From<D> for LeafHash<D> {
+    fn from(digest: D) -> Self {
+        Self(digest)
+    }
+}
+
+impl<D: Digest> fmt::LowerHex for LeafHash<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        hex::write(f, &self.0)
+    }
+}
+
+impl<D: Digest> fmt::Display for LeafHash<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        hex::write(f, &self.0)
+    }
+}
+
+impl<D: Digest> FromStr for LeafHash<D> {
+    type Err = MerkleError;
+
+    fn from_str(s: &str) -> Result<Self, MerkleError> {
+        let digits = s.strip_prefix("0x").unwrap_or(s);
+        hex::parse(digits)
+            .map(LeafHash)
+            .map_err(|reason| MerkleError::InvalidHex(format!("leaf hash {reason}")))
+    }
+}
@@ -0,0 +1,192 @@
+//! trees over salted values, for commit reveal schemes and private membership
+//!
+//! the leaf of a value is the hash of a random salt followed by the value, so
+//! that nobody can find out whether a value is in the tree by hashing guesses.
+//! the prover reveals the salt of its leaf together with the proof.
+
+use alloc::vec::Vec;
+
+use crate::{
+    error::MerkleError,
+    hash::{DefaultBuildHasher, Digest, LeafHasher, MerkleHasher},
+    hashed::HashedMerkleTree,
+    proof::MerkleProof,
+    root::Root,
+    tree::PaddingStrategy,
+    OnceLock,
+};
+
+/// bytes of a salt
+const SALT_LEN: usize = 32;
+
+/// random bytes hashed in front of a value
+pub type Salt = [u8; SALT_LEN];
+
+/// a merkle tree that stores the leaf hashes of salted values and their salts,
+/// the values themselves are not kept
+pub struct SaltedMerkleTree<S: MerkleHasher = DefaultBuildHasher> {
+    tree: HashedMerkleTree<S>,
+    /// salt of every leaf, by index
+    salts: Vec<Salt>,
+}
+
+impl<S: MerkleHasher + Default + LeafHasher<[u8]>> Default for SaltedMerkleTree<S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl SaltedMerkleTree {
+    /// an empty tree with the default hasher
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S: MerkleHasher + LeafHasher<[u8]>> SaltedMerkleTree<S> {
+    /// an empty tree with the given hasher
+    pub fn with_hasher(hasher: S) -> Self {
+        let empty = hasher.hash_padding();
+        let tree = HashedMerkleTree::new(
+            Vec::new(),
+            empty,
+            hasher,
+            PaddingStrategy::default(),
+            OnceLock::new(),
+        );
+        Self {
+            tree,
+            salts: Vec::new(),
+        }
+    }
+
+    /// change how the tree is completed when its size is not a power of two
+    pub fn with_padding(mut self, padding: PaddingStrategy) -> Self {
+        self.tree = self.tree.with_padding(padding);
+        self
+    }
+
+    /// append a value under a fresh random salt and return the salt
+    /// salts come from the randomly seeded keys std uses for hash maps, pass
+    /// salts of a vetted random number generator to [`add_salted_with`] where
+    /// that is required
+    ///
+    /// [`add_salted_with`]: SaltedMerkleTree::add_salted_with
+    #[cfg(feature = "std")]
+    pub fn add_salted(&mut self, value: impl AsRef<[u8]>) -> Salt {
+        let salt = random_salt();
+        self.add_salted_with(value, salt);
+        salt
+    }
+
+    /// append a value under the given salt
+    pub fn add_salted_with(&mut self, value: impl AsRef<[u8]>, salt: Salt) {
+        let leaf = salted_leaf(self.tree.hasher(), &salt, value.as_ref());
+        self.tree.add_hash(leaf);
+        self.salts.push(salt);
+    }
+
+    /// salt of the leaf at `index`
+    pub fn salt(&self, index: usize) -> Option<&Salt> {
+        self.salts.get(index)
+    }
+
+    /// number of salted values, padding excluded
+    pub fn len(&self) -> usize {
+        self.salts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.salts.is_empty()
+    }
+
+    pub fn root(&self) -> Root<S::Digest> {
+        self.tree.root()
+    }
+
+    /// proof for the value at `index` together with its salt
+    /// padding leafs have no salt and so no salted proof
+    pub fn get_proof(&self, index: usize) -> Result<SaltedProof<S>, MerkleError>
+    where
+        S: Clone,
+    {
+        let salt = *self.salts.get(index).ok_or(MerkleError::IndexOutOfBounds {
+            index,
+            len: self.salts.len(),
+        })?;
+        let proof = self.tree.get_proof(index)?;
+        Ok(SaltedProof { salt, proof })
+    }
+}
+
+/// proof that a value hashed under `salt` is a leaf of a tree
+#[derive(Debug)]
+pub struct SaltedProof<S: MerkleHasher = DefaultBuildHasher> {
+    pub salt: Salt,
+    pub proof: MerkleProof<S>,
+}
+
+impl<S: MerkleHasher + LeafHasher<[u8]>> SaltedProof<S> {
+    /// check that `value` hashed under the salt is the leaf of the proof and
+    /// that the proof leads to its own root
+    #[deprecated(note = "use `SaltedProof::verify_against` with a trusted root")]
+    pub fn verify(&self, value: impl AsRef<[u8]>) -> bool {
+        self.verify_against(&self.proof.root, value)
+    }
+
+    /// check that `value` hashed under the salt is the leaf of the proof and
+    /// that the proof leads to a root the verifier trusts
+    pub fn verify_against(&self, root: &Root<S::Digest>, value: impl AsRef<[u8]>) -> bool {
+        salted_leaf(&self.proof.hasher, &self.salt, value.as_ref()).ct_eq(&self.proof.leaf.0)
+            && self.proof.verify_against(root)
+    }
+
+    /// the salt followed by the [binary form](MerkleProof::to_bytes) of the
+    /// proof
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.salt.to_vec();
+        out.extend_from_slice(&self.proof.to_bytes());
+        out
+    }
+
+    /// decode a salted proof from its binary form
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MerkleError>
+    where
+        S: Default,
+    {
+        if bytes.len() < SALT_LEN {
+            return Err(MerkleError::Truncated {
+                needed: SALT_LEN,
+                available: bytes.len(),
+            });
+        }
+        let (salt, proof) = bytes.split_at(SALT_LEN);
+        Ok(Self {
+            salt: salt.try_into().expect("split at the salt length"),
+            proof: MerkleProof::from_bytes(proof)?,
+        })
+    }
+}
+
+/// the leaf hash of `salt || value`
+fn salted_leaf<S: LeafHasher<[u8]>>(hasher: &S, salt: &Salt, value: &[u8]) -> S::Digest {
+    let mut bytes = Vec::with_capacity(salt.len() + value.len());
+    bytes.extend_from_slice(salt);
+    bytes.extend_from_slice(value);
+    hasher.hash_leaf(&bytes[..])
+}
+
+/// 32 bytes of siphash outputs under the random keys of a fresh `RandomState`
+#[cfg(feature = "std")]
+fn random_salt() -> Salt {
+    use std::hash::{BuildHasher, Hasher, RandomState};
+
+    let state = RandomState::new();
+    let mut salt = Salt::default();
+    for (i, chunk) in salt.chunks_mut(8).enumerate() {
+        let mut hasher = state.build_hasher();
+        hasher.write_usize(i);
+        chunk.copy_from_slice(&hasher.finish().to_le_bytes());
+    }
+    salt
+}
@@ -0,0 +1,264 @@
+//! a commitment to a set or multiset of values that insertion order does not
+//! change
+//!
+//! the leafs are the hashes of the values, kept sorted like the ones of a
+//! [`SortedMerkleTree`], so two sets holding the same values share their root
+//! however the values were added. proofs are made for a value instead of an
+//! index. whether a value may be added twice is chosen when the set is built,
+//! see [`Duplicates`].
+//!
+//! [`SortedMerkleTree`]: crate::SortedMerkleTree
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::{
+    error::MerkleError,
+    hash::{compare, DefaultBuildHasher, Digest, LeafHasher, MerkleHasher},
+    proof::MerkleProof,
+    root::{LeafHash, Root},
+    tree::{build_levels, proof_path, PaddingStrategy},
+    OnceLock,
+};
+
+/// what inserting a value that is already in a [`MerkleSet`] does
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Duplicates {
+    /// the insert fails with [`DuplicateValue`](MerkleError::DuplicateValue)
+    #[default]
+    Reject,
+    /// every copy gets a leaf of its own, the set is a multiset
+    Allow,
+}
+
+/// a set of values whose root only depends on the values it holds
+pub struct MerkleSet<T, S: MerkleHasher = DefaultBuildHasher> {
+    /// the values sorted by their leaf hash
+    values: Vec<T>,
+    /// leaf hash of every value, sorted
+    hashes: Vec<S::Digest>,
+    hasher: S,
+    duplicates: Duplicates,
+    levels: OnceLock<Vec<Vec<S::Digest>>>,
+}
+
+/// proof that a value is in a [`MerkleSet`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueProof<S: MerkleHasher = DefaultBuildHasher> {
+    /// proof of the leaf of the value
+    pub proof: MerkleProof<S>,
+}
+
+impl<T> MerkleSet<T>
+where
+    DefaultBuildHasher: LeafHasher<T>,
+{
+    pub fn new(duplicates: Duplicates) -> Self {
+        Self::with_hasher(DefaultBuildHasher::default(), duplicates)
+    }
+}
+
+/// a set that rejects duplicates
+impl<T> Default for MerkleSet<T>
+where
+    DefaultBuildHasher: LeafHasher<T>,
+{
+    fn default() -> Self {
+        Self::new(Duplicates::default())
+    }
+}
+
+impl<T, S> MerkleSet<T, S>
+where
+    S: LeafHasher<T> + Clone,
+{
+    pub fn with_hasher(hasher: S, duplicates: Duplicates) -> Self {
+        Self {
+            values: Vec::new(),
+            hashes: Vec::new(),
+            hasher,
+            duplicates,
+            levels: OnceLock::new(),
+        }
+    }
+
+    pub fn duplicates(&self) -> Duplicates {
+        self.duplicates
+    }
+
+    /// number of values, every copy of a value counts
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// the values sorted by their leaf hash
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// add `value` at the position of its hash, copies of a value sit next
+    /// to each other
+    pub fn insert(&mut self, value: T) -> Result<(), MerkleError> {
+        let hash = self.hasher.hash_leaf(&value);
+        let index = self.upper_bound(&hash);
+        if self.duplicates == Duplicates::Reject && self.holds(index, &hash) {
+            return Err(MerkleError::DuplicateValue);
+        }
+        self.values.insert(index, value);
+        self.hashes.insert(index, hash);
+        self.levels.take();
+        Ok(())
+    }
+
+    /// add all `values` like a loop of [`insert`](MerkleSet::insert) would,
+    /// they are sorted once and merged into the set
+    /// a set that rejects duplicates is left as it was if one of the values
+    /// is already in it or given twice
+    pub fn insert_all(&mut self, values: impl IntoIterator<Item = T>) -> Result<(), MerkleError> {
+        let mut added: Vec<_> = values
+            .into_iter()
+            .map(|value| (self.hasher.hash_leaf(&value), value))
+            .collect();
+        added.sort_by(|a, b| compare(&a.0, &b.0));
+        if self.duplicates == Duplicates::Reject {
+            let twice = added.windows(2).any(|pair| pair[0].0 == pair[1].0);
+            let held = added
+                .iter()
+                .any(|(hash, _)| self.holds(self.upper_bound(hash), hash));
+            if twice || held {
+                return Err(MerkleError::DuplicateValue);
+            }
+        }
+        if added.is_empty() {
+            return Ok(());
+        }
+
+        let len = self.values.len() + added.len();
+        let mut values = Vec::with_capacity(len);
+        let mut hashes = Vec::with_capacity(len);
+        let mut held = core::mem::take(&mut self.hashes)
+            .into_iter()
+            .zip(core::mem::take(&mut self.values))
+            .peekable();
+        let mut added = added.into_iter().peekable();
+        // values already held come before added copies of them
+        while let Some((hash, value)) = match (held.peek(), added.peek()) {
+            (Some(old), Some(new)) if compare(&new.0, &old.0) == Ordering::Less => added.next(),
+            (Some(_), _) => held.next(),
+            (None, _) => added.next(),
+        } {
+            hashes.push(hash);
+            values.push(value);
+        }
+
+        self.values = values;
+        self.hashes = hashes;
+        self.levels.take();
+        Ok(())
+    }
+
+    /// remove one copy of `value`, returns whether there was one
+    pub fn remove(&mut self, value: &T) -> bool {
+        let Some(index) = self.position(value) else {
+            return false;
+        };
+        self.values.remove(index);
+        self.hashes.remove(index);
+        self.levels.take();
+        true
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.position(value).is_some()
+    }
+
+    /// number of copies of `value` in the set
+    pub fn count(&self, value: &T) -> usize {
+        let hash = self.hasher.hash_leaf(value);
+        let start = self.lower_bound(&hash);
+        self.upper_bound(&hash) - start
+    }
+
+    /// the root of the tree over the values, an empty set has none
+    pub fn root(&self) -> Option<Root<S::Digest>> {
+        let levels = self.levels()?;
+        Some(Root(levels[levels.len() - 1][0]))
+    }
+
+    /// proof that `value` is in the set, for a value held more than once it
+    /// proves the first copy
+    /// values that are not in the set are
+    /// [`ValueNotFound`](MerkleError::ValueNotFound)
+    pub fn prove(&self, value: &T) -> Result<ValueProof<S>, MerkleError> {
+        let index = self.position(value).ok_or(MerkleError::ValueNotFound)?;
+        let levels = self.levels().ok_or(MerkleError::ValueNotFound)?;
+        let (leaf, path) = proof_path(levels, PaddingStrategy::PromoteOdd, index)?;
+
+        let proof = MerkleProof {
+            path,
+            root: Root(levels[levels.len() - 1][0]),
+            leaf: LeafHash(leaf),
+            index,
+            width: levels[0].len(),
+            padding: PaddingStrategy::PromoteOdd,
+            hasher: self.hasher.clone(),
+        };
+        Ok(ValueProof { proof })
+    }
+
+    /// index of the first copy of `value`
+    fn position(&self, value: &T) -> Option<usize> {
+        let hash = self.hasher.hash_leaf(value);
+        let index = self.lower_bound(&hash);
+        self.hashes
+            .get(index)
+            .is_some_and(|leaf| *leaf == hash)
+            .then_some(index)
+    }
+
+    /// whether the leaf before `index`, the end of the copies of `hash`, is
+    /// one of them
+    fn holds(&self, index: usize, hash: &S::Digest) -> bool {
+        index > 0 && self.hashes[index - 1] == *hash
+    }
+
+    fn lower_bound(&self, hash: &S::Digest) -> usize {
+        self.hashes
+            .partition_point(|leaf| compare(leaf, hash) == Ordering::Less)
+    }
+
+    fn upper_bound(&self, hash: &S::Digest) -> usize {
+        self.hashes
+            .partition_point(|leaf| compare(leaf, hash) != Ordering::Greater)
+    }
+
+    fn levels(&self) -> Option<&[Vec<S::Digest>]> {
+        if self.hashes.is_empty() {
+            return None;
+        }
+        let levels = self.levels.get_or_init(|| {
+            build_levels(
+                &self.hasher,
+                PaddingStrategy::PromoteOdd,
+                self.hashes.clone(),
+            )
+        });
+        Some(levels)
+    }
+}
+
+impl<S: MerkleHasher> ValueProof<S> {
+    /// check that the proof shows `value` in the set with the trusted `root`
+    pub fn verify<T>(&self, root: &Root<S::Digest>, value: &T) -> bool
+    where
+        S: LeafHasher<T>,
+    {
+        self.proof.hasher.hash_leaf(value).ct_eq(&self.proof.leaf.0)
+            && self.proof.padding == PaddingStrategy::PromoteOdd
+            && self.proof.verify_against(root)
+    }
+}
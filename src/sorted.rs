@@ -0,0 +1,211 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::{
+    error::MerkleError,
+    hash::{compare, DefaultBuildHasher, Digest, LeafHasher, MerkleHasher},
+    proof::MerkleProof,
+    root::{LeafHash, Root},
+    tree::{build_levels, proof_path, refresh_levels, PaddingStrategy},
+    OnceLock,
+};
+
+/// a merkle tree over a set of values whose leafs are sorted by their hash
+///
+/// a value that is not in the set would sit between two adjacent leafs, so
+/// proving both of them shows that it is absent. digests are ordered by their
+/// encoded bytes and the tree is shaped like RFC 6962 ones, without padding
+/// leafs that would break the order.
+pub struct SortedMerkleTree<T, S: MerkleHasher = DefaultBuildHasher> {
+    values: Vec<T>,
+    /// leaf hashes of the values, sorted and without duplicates
+    hashes: Vec<S::Digest>,
+    hasher: S,
+    levels: OnceLock<Vec<Vec<S::Digest>>>,
+}
+
+/// proof that a value is or is not in a [`SortedMerkleTree`]
+#[derive(Debug)]
+pub enum SetProof<S: MerkleHasher = DefaultBuildHasher> {
+    /// the value is the leaf of the proof
+    Inclusion(MerkleProof<S>),
+    /// the value would sit between two adjacent leafs, `left` is missing if
+    /// it would be the first leaf and `right` if it would be the last
+    Exclusion {
+        left: Option<MerkleProof<S>>,
+        right: Option<MerkleProof<S>>,
+    },
+}
+
+impl<T> SortedMerkleTree<T>
+where
+    DefaultBuildHasher: LeafHasher<T>,
+{
+    pub fn new() -> Self {
+        Self::with_hasher(DefaultBuildHasher::default())
+    }
+}
+
+impl<T> Default for SortedMerkleTree<T>
+where
+    DefaultBuildHasher: LeafHasher<T>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S> SortedMerkleTree<T, S>
+where
+    S: LeafHasher<T> + Clone,
+{
+    pub fn with_hasher(hasher: S) -> Self {
+        Self {
+            values: Vec::new(),
+            hashes: Vec::new(),
+            hasher,
+            levels: OnceLock::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// the values sorted by their leaf hash
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// add a value at the position of its hash
+    /// returns false if an equal value is already in the set
+    pub fn insert(&mut self, value: T) -> bool {
+        let hash = self.hasher.hash_leaf(&value);
+        match self.search(&hash) {
+            Ok(_) => false,
+            Err(index) => {
+                self.values.insert(index, value);
+                self.hashes.insert(index, hash);
+                // the leafs right of the new one moved, only their paths change
+                if let Some(levels) = self.levels.get_mut() {
+                    levels[0].insert(index, hash);
+                    let len = self.hashes.len();
+                    let padding = PaddingStrategy::PromoteOdd;
+                    refresh_levels(&self.hasher, padding, levels, len, index, &mut None, || {
+                        hash
+                    });
+                }
+                true
+            }
+        }
+    }
+
+    /// the root of the tree, an empty set has none
+    pub fn root(&self) -> Option<Root<S::Digest>> {
+        let levels = self.levels()?;
+        Some(Root(levels[levels.len() - 1][0]))
+    }
+
+    /// prove that `value` is in the set or that it is not
+    pub fn contains_proof(&self, value: &T) -> Result<SetProof<S>, MerkleError> {
+        let hash = self.hasher.hash_leaf(value);
+        let len = self.hashes.len();
+        if len == 0 {
+            return Err(MerkleError::EmptyTree);
+        }
+
+        match self.search(&hash) {
+            Ok(index) => Ok(SetProof::Inclusion(self.proof(index)?)),
+            Err(index) => Ok(SetProof::Exclusion {
+                left: index
+                    .checked_sub(1)
+                    .map(|left| self.proof(left))
+                    .transpose()?,
+                right: (index < len).then(|| self.proof(index)).transpose()?,
+            }),
+        }
+    }
+
+    fn proof(&self, index: usize) -> Result<MerkleProof<S>, MerkleError> {
+        let levels = self.levels().ok_or(MerkleError::EmptyTree)?;
+        let (leaf, path) = proof_path(levels, PaddingStrategy::PromoteOdd, index)?;
+
+        Ok(MerkleProof {
+            path,
+            root: Root(levels[levels.len() - 1][0]),
+            leaf: LeafHash(leaf),
+            index,
+            width: levels[0].len(),
+            padding: PaddingStrategy::PromoteOdd,
+            hasher: self.hasher.clone(),
+        })
+    }
+
+    fn search(&self, hash: &S::Digest) -> Result<usize, usize> {
+        self.hashes.binary_search_by(|leaf| compare(leaf, hash))
+    }
+
+    fn levels(&self) -> Option<&[Vec<S::Digest>]> {
+        if self.hashes.is_empty() {
+            return None;
+        }
+        let levels = self.levels.get_or_init(|| {
+            build_levels(
+                &self.hasher,
+                PaddingStrategy::PromoteOdd,
+                self.hashes.clone(),
+            )
+        });
+        Some(levels)
+    }
+}
+
+impl<S: MerkleHasher> SetProof<S> {
+    /// check the proof for `value` against the `root` of a set the verifier
+    /// trusts, the roots stored in the proofs are ignored
+    /// an inclusion proof has to prove the hash of the value, an exclusion
+    /// proof two adjacent leafs of the tree that straddle it
+    pub fn verify<T>(&self, root: &Root<S::Digest>, value: &T) -> bool
+    where
+        S: LeafHasher<T>,
+    {
+        let sorted_proof = |proof: &MerkleProof<S>| {
+            proof.padding == PaddingStrategy::PromoteOdd && proof.verify_against(root)
+        };
+        match self {
+            SetProof::Inclusion(proof) => {
+                proof.hasher.hash_leaf(value).ct_eq(&proof.leaf.0) && sorted_proof(proof)
+            }
+            SetProof::Exclusion { left, right } => {
+                let (left, right) = (left.as_ref(), right.as_ref());
+                if !left.into_iter().chain(right).all(sorted_proof) {
+                    return false;
+                }
+
+                match (left, right) {
+                    (Some(left), Some(right)) => {
+                        let hash = left.hasher.hash_leaf(value);
+                        left.width == right.width
+                            && left.index + 1 == right.index
+                            && compare(&left.leaf.0, &hash) == Ordering::Less
+                            && compare(&hash, &right.leaf.0) == Ordering::Less
+                    }
+                    (None, Some(first)) => {
+                        let hash = first.hasher.hash_leaf(value);
+                        first.index == 0 && compare(&hash, &first.leaf.0) == Ordering::Less
+                    }
+                    (Some(last), None) => {
+                        let hash = last.hasher.hash_leaf(value);
+                        last.index + 1 == last.width
+                            && compare(&last.leaf.0, &hash) == Ordering::Less
+                    }
+                    (None, None) => false,
+                }
+            }
+        }
+    }
+}
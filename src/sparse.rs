@@ -0,0 +1,229 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::marker::PhantomData;
+
+use crate::{
+    error::MerkleError,
+    hash::{DefaultBuildHasher, Digest, EmptyHashes, LeafHasher, MerkleHasher},
+    root::{LeafHash, Root},
+};
+
+/// a merkle tree with one leaf for every possible key
+///
+/// a key is placed at the leaf its hash points to, bit by bit from the most
+/// significant one, so the tree is as deep as the digest is wide. leafs that
+/// were never set hold the hashed default value, the roots of untouched
+/// subtrees are precomputed and only the nodes above stored keys are kept.
+///
+/// this allows proving that a key is absent, not only that it is present.
+/// a key set to the default value can't be told apart from an absent one.
+pub struct SparseMerkleTree<K, V, S: MerkleHasher = DefaultBuildHasher> {
+    /// non empty nodes by height and by the key bits above them
+    nodes: BTreeMap<(usize, Vec<u8>), S::Digest>,
+    /// `zeros[h]` is the root of an empty subtree of height `h`
+    zeros: EmptyHashes<S::Digest>,
+    hasher: S,
+    entries: PhantomData<fn(&K, &V)>,
+}
+
+/// proof that the leaf at the position of a key holds a value or is empty
+#[derive(Debug)]
+pub struct SparseMerkleProof<S: MerkleHasher = DefaultBuildHasher> {
+    /// hash of the key, its bits are the path from the root to the leaf
+    pub key: S::Digest,
+    pub leaf: LeafHash<S::Digest>,
+    /// siblings from the leaf up to the root
+    pub siblings: Vec<S::Digest>,
+    pub root: Root<S::Digest>,
+    pub hasher: S,
+}
+
+impl<K, V> SparseMerkleTree<K, V>
+where
+    V: Default,
+    DefaultBuildHasher: LeafHasher<K> + LeafHasher<V>,
+{
+    pub fn new() -> Self {
+        Self::with_hasher(DefaultBuildHasher::default())
+    }
+}
+
+impl<K, V> Default for SparseMerkleTree<K, V>
+where
+    V: Default,
+    DefaultBuildHasher: LeafHasher<K> + LeafHasher<V>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, S> SparseMerkleTree<K, V, S>
+where
+    V: Default,
+    S: LeafHasher<K> + LeafHasher<V> + Clone,
+{
+    pub fn with_hasher(hasher: S) -> Self {
+        let empty = LeafHasher::<V>::hash_leaf(&hasher, &V::default());
+        let zeros = EmptyHashes::new(&hasher, empty, depth::<S::Digest>());
+
+        Self {
+            nodes: BTreeMap::new(),
+            zeros,
+            hasher,
+            entries: PhantomData,
+        }
+    }
+
+    /// set the leaf of `key` to `value`, rehashing the path above it
+    pub fn insert(&mut self, key: &K, value: &V) {
+        let key = bytes(&LeafHasher::<K>::hash_leaf(&self.hasher, key));
+        let mut node = LeafHasher::<V>::hash_leaf(&self.hasher, value);
+
+        let depth = depth::<S::Digest>();
+        for height in 0..depth {
+            self.nodes
+                .insert((height, prefix(&key, depth - height)), node);
+
+            let sibling = self.node(height, &flip(&key, depth - height - 1));
+            node = if bit(&key, depth - height - 1) {
+                self.hasher.hash_siblings(&sibling, &node)
+            } else {
+                self.hasher.hash_siblings(&node, &sibling)
+            };
+        }
+        self.nodes.insert((depth, Vec::new()), node);
+    }
+
+    pub fn root(&self) -> Root<S::Digest> {
+        Root(self.node(depth::<S::Digest>(), &[]))
+    }
+
+    /// prove that `key` is set
+    pub fn get_proof(&self, key: &K) -> Result<SparseMerkleProof<S>, MerkleError> {
+        let proof = self.proof(key);
+        if proof.leaf.0 == self.zeros[0] {
+            return Err(MerkleError::KeyNotFound);
+        }
+        Ok(proof)
+    }
+
+    /// prove that `key` is not set, its leaf holds the hashed default value
+    pub fn get_non_membership_proof(&self, key: &K) -> Result<SparseMerkleProof<S>, MerkleError> {
+        let proof = self.proof(key);
+        if proof.leaf.0 != self.zeros[0] {
+            return Err(MerkleError::KeyExists);
+        }
+        Ok(proof)
+    }
+
+    fn proof(&self, key: &K) -> SparseMerkleProof<S> {
+        let hashed = LeafHasher::<K>::hash_leaf(&self.hasher, key);
+        let key = bytes(&hashed);
+        let depth = depth::<S::Digest>();
+
+        let siblings = (0..depth)
+            .map(|height| self.node(height, &flip(&key, depth - height - 1)))
+            .collect();
+
+        SparseMerkleProof {
+            key: hashed,
+            leaf: LeafHash(self.node(0, &key)),
+            siblings,
+            root: self.root(),
+            hasher: self.hasher.clone(),
+        }
+    }
+
+    /// the node at `height` above the leaf of `key`
+    fn node(&self, height: usize, key: &[u8]) -> S::Digest {
+        let depth = depth::<S::Digest>();
+        self.nodes
+            .get(&(height, prefix(key, depth - height)))
+            .copied()
+            .unwrap_or(self.zeros[height])
+    }
+}
+
+impl<S: MerkleHasher> SparseMerkleProof<S> {
+    /// check that the siblings lead from the leaf to the root of the proof
+    /// along the key
+    ///
+    /// whoever built the proof also chose its root, check against a root you
+    /// trust with [`verify_against`](SparseMerkleProof::verify_against)
+    pub fn verify(&self) -> bool {
+        self.verify_against(&self.root)
+    }
+
+    /// like [`verify`](SparseMerkleProof::verify) but against a root the
+    /// verifier trusts, the root stored in the proof is ignored
+    pub fn verify_against(&self, root: &Root<S::Digest>) -> bool {
+        let key = bytes(&self.key);
+        let depth = depth::<S::Digest>();
+        if self.siblings.len() != depth {
+            return false;
+        }
+
+        let mut acc = self.leaf.0;
+        for (height, sibling) in self.siblings.iter().enumerate() {
+            acc = if bit(&key, depth - height - 1) {
+                self.hasher.hash_siblings(sibling, &acc)
+            } else {
+                self.hasher.hash_siblings(&acc, sibling)
+            };
+        }
+
+        acc.ct_eq(&root.0)
+    }
+
+    /// check that the proof shows `key` set to `value` in the tree of `root`
+    pub fn verify_membership<K, V>(&self, root: &Root<S::Digest>, key: &K, value: &V) -> bool
+    where
+        S: LeafHasher<K> + LeafHasher<V>,
+    {
+        LeafHasher::<K>::hash_leaf(&self.hasher, key) == self.key
+            && LeafHasher::<V>::hash_leaf(&self.hasher, value) == self.leaf.0
+            && self.verify_against(root)
+    }
+
+    /// check that the proof shows `key` unset in the tree of `root`, i.e.
+    /// holding `V::default()`
+    pub fn verify_non_membership<K, V>(&self, root: &Root<S::Digest>, key: &K) -> bool
+    where
+        V: Default,
+        S: LeafHasher<K> + LeafHasher<V>,
+    {
+        self.verify_membership(root, key, &V::default())
+    }
+}
+
+/// number of bits of a digest, the depth of a sparse tree
+fn depth<D: Digest>() -> usize {
+    D::LEN * 8
+}
+
+fn bytes<D: Digest>(digest: &D) -> Vec<u8> {
+    let mut out = Vec::with_capacity(D::LEN);
+    digest.write_bytes(&mut out);
+    out
+}
+
+/// bit `i` of the key, counted from the most significant bit of the first byte
+fn bit(key: &[u8], i: usize) -> bool {
+    key[i / 8] >> (7 - i % 8) & 1 == 1
+}
+
+/// the key with bit `i` flipped
+fn flip(key: &[u8], i: usize) -> Vec<u8> {
+    let mut key = key.to_vec();
+    key[i / 8] ^= 1 << (7 - i % 8);
+    key
+}
+
+/// the first `bits` bits of the key, the remaining ones cleared
+fn prefix(key: &[u8], bits: usize) -> Vec<u8> {
+    let mut prefix = key[..bits.div_ceil(8)].to_vec();
+    if !bits.is_multiple_of(8) {
+        prefix[bits / 8] &= 0xff << (8 - bits % 8);
+    }
+    prefix
+}
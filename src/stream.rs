@@ -0,0 +1,144 @@
+//! building trees from values that arrive asynchronously
+//!
+//! [`Stream`] and [`AsyncRead`] have the shape of the traits of the same name
+//! in `futures`, a wrapper of a few lines adapts a stream or reader of any
+//! runtime. values are hashed as they arrive, nothing is spawned and no
+//! runtime is required.
+
+use core::{
+    future::poll_fn,
+    hash::Hash,
+    pin::{pin, Pin},
+    task::{Context, Poll},
+};
+
+use crate::{
+    hash::{DefaultBuildHasher, LeafHasher},
+    hashed::HashedMerkleTree,
+    incremental::IncrementalMerkleTree,
+    root::Root,
+    tree::MerkleTree,
+};
+
+/// a source of values that become available over time
+pub trait Stream {
+    type Item;
+
+    /// the next value, `None` once the stream has ended
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>>;
+}
+
+impl<S: Stream + Unpin + ?Sized> Stream for &mut S {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut **self).poll_next(cx)
+    }
+}
+
+/// a source of bytes that become available over time
+#[cfg(feature = "std")]
+pub trait AsyncRead {
+    /// read into `buf` and return the number of bytes read, `0` at the end
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>>;
+}
+
+#[cfg(feature = "std")]
+impl AsyncRead for &[u8] {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(std::io::Read::read(&mut *self, buf))
+    }
+}
+
+/// a stream that is always ready with the next value of an iterator
+pub struct Iter<I>(I);
+
+/// turn an iterator into a [`Stream`]
+pub fn iter<I: IntoIterator>(values: I) -> Iter<I::IntoIter> {
+    Iter(values.into_iter())
+}
+
+impl<I: Iterator + Unpin> Stream for Iter<I> {
+    type Item = I::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<I::Item>> {
+        Poll::Ready(self.0.next())
+    }
+}
+
+impl<T: Hash + Default> MerkleTree<T> {
+    /// build a tree from the hashes of the values of `stream`, see
+    /// [`from_stream_with_hasher`]
+    ///
+    /// [`from_stream_with_hasher`]: MerkleTree::from_stream_with_hasher
+    pub async fn from_stream<E>(
+        stream: impl Stream<Item = Result<T, E>>,
+    ) -> Result<HashedMerkleTree, E> {
+        Self::from_stream_with_hasher(stream, DefaultBuildHasher::default()).await
+    }
+
+    /// the root of the tree over the values of `stream`, see
+    /// [`root_of_stream_with_hasher`]
+    ///
+    /// [`root_of_stream_with_hasher`]: MerkleTree::root_of_stream_with_hasher
+    pub async fn root_of_stream<E>(stream: impl Stream<Item = Result<T, E>>) -> Result<Root, E> {
+        Self::root_of_stream_with_hasher(stream, DefaultBuildHasher::default()).await
+    }
+}
+
+impl<T: Default, S: LeafHasher<T>> MerkleTree<T, S> {
+    /// hash the values of `stream` as they arrive and build a tree over their
+    /// hashes, like [`from_hashed_iter`]
+    /// the first error of the stream is returned, use
+    /// [`IncrementalMerkleTree::add_stream`] to keep the values before it
+    ///
+    /// [`from_hashed_iter`]: MerkleTree::from_hashed_iter
+    pub async fn from_stream_with_hasher<E>(
+        stream: impl Stream<Item = Result<T, E>>,
+        hasher: S,
+    ) -> Result<HashedMerkleTree<S>, E> {
+        let mut stream = pin!(stream);
+        let mut hashes = alloc::vec::Vec::new();
+        while let Some(value) = poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+            hashes.push(hasher.hash_leaf(&value?));
+        }
+        Ok(Self::from_leaf_hashes_with_hasher(hashes, hasher))
+    }
+
+    /// the root of the tree over the values of `stream` in `O(log n)` memory,
+    /// like [`root_of_iter`]
+    ///
+    /// [`root_of_iter`]: MerkleTree::root_of_iter
+    pub async fn root_of_stream_with_hasher<E>(
+        stream: impl Stream<Item = Result<T, E>>,
+        hasher: S,
+    ) -> Result<Root<S::Digest>, E> {
+        let mut tree = IncrementalMerkleTree::with_hasher(hasher);
+        tree.add_stream(stream).await?;
+        Ok(tree.root())
+    }
+}
+
+impl<T: Default, S: LeafHasher<T>> IncrementalMerkleTree<T, S> {
+    /// append the values of `stream` as they arrive
+    /// on an error of the stream the values before it stay appended, so the
+    /// tree can be continued with the rest of the values
+    pub async fn add_stream<E>(
+        &mut self,
+        stream: impl Stream<Item = Result<T, E>>,
+    ) -> Result<(), E> {
+        let mut stream = pin!(stream);
+        while let Some(value) = poll_fn(|cx| stream.as_mut().poll_next(cx)).await {
+            self.add(value?);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,1381 @@
+use alloc::{vec, vec::Vec};
+use core::{fmt, hash::Hash, ops::Range};
+
+use crate::{
+    error::{CasError, MerkleError},
+    hash::{DefaultBuildHasher, Digest, Domain, EmptyHashes, LeafHasher, MaybeSync, MerkleHasher},
+    hashed::HashedMerkleTree,
+    incremental::IncrementalMerkleTree,
+    proof::{ConsistencyProof, Direction, MerkleMultiProof, MerkleProof, RangeProof, Step},
+    root::{LeafHash, Root},
+    OnceLock,
+};
+
+mod checkpoint;
+mod dot;
+mod merge;
+mod observe;
+mod render;
+mod value;
+
+use checkpoint::Checkpoints;
+use observe::Observers;
+
+pub use checkpoint::Checkpoint;
+pub use value::ValueMut;
+
+/// how levels that are not a power of two wide are completed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PaddingStrategy {
+    /// pad the leafs with the [padding leaf](MerkleHasher::hash_padding) of the
+    /// hasher up to the next power of two
+    #[default]
+    DefaultValue,
+    /// don't pad the leafs, pair the odd node of a level with itself
+    /// this is the tree shape bitcoin uses
+    DuplicateLast,
+    /// don't pad, carry the odd node of a level up to the next one unhashed
+    /// this is the tree shape of RFC 6962
+    PromoteOdd,
+}
+
+/// clones share nothing, changing a clone leaves the original as it was and
+/// notifies none of its [observers](MerkleTree::on_root_change)
+#[derive(Clone)]
+pub struct MerkleTree<T, S: MerkleHasher = DefaultBuildHasher> {
+    values: Vec<T>,
+    hasher: S,
+    padding: PaddingStrategy,
+    /// every level of the tree from the leafs up to the root, computed on
+    /// first use, changes to the values only rehash the nodes above them
+    levels: OnceLock<Vec<Vec<S::Digest>>>,
+    /// roots of empty subtrees hashed while updating the cached levels, they
+    /// only depend on the hasher and are kept for the next update
+    empties: Option<EmptyHashes<S::Digest>>,
+    observers: Observers<S::Digest>,
+    checkpoints: Checkpoints<S::Digest>,
+    /// versions one of whose values changed after they were reached, see
+    /// [`root_at`](MerkleTree::root_at)
+    stale: Range<usize>,
+}
+
+/// the stored values and the padding strategy, the nodes are not shown
+impl<T: fmt::Debug, S: MerkleHasher> fmt::Debug for MerkleTree<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MerkleTree")
+            .field("values", &self.values)
+            .field("padding", &self.padding)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> From<Vec<T>> for MerkleTree<T>
+where
+    T: Hash,
+{
+    fn from(values: Vec<T>) -> Self {
+        Self::with_hasher(values, DefaultBuildHasher::default())
+    }
+}
+
+impl<T> FromIterator<T> for MerkleTree<T>
+where
+    T: Hash,
+{
+    /// the storage is allocated once for the lower bound of the size hint
+    fn from_iter<I: IntoIterator<Item = T>>(values: I) -> Self {
+        Self::from(values.into_iter().collect::<Vec<_>>())
+    }
+}
+
+/// appends all values at once like [`add_all`](MerkleTree::add_all)
+impl<T: Default + MaybeSync, S: LeafHasher<T> + Clone> Extend<T> for MerkleTree<T, S> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, values: I) {
+        self.add_all(values);
+    }
+}
+
+impl<T, S: MerkleHasher> MerkleTree<T, S> {
+    /// build a tree whose leafs and nodes are hashed with the given hasher factory
+    pub fn with_hasher(values: Vec<T>, hasher: S) -> Self {
+        Self {
+            values,
+            hasher,
+            padding: PaddingStrategy::default(),
+            levels: OnceLock::new(),
+            empties: None,
+            observers: Observers::default(),
+            checkpoints: Checkpoints::default(),
+            stale: 0..0,
+        }
+    }
+
+    /// an empty tree with room for `capacity` values before its storage has
+    /// to grow
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        Self::with_hasher(Vec::with_capacity(capacity), hasher)
+    }
+
+    /// change how the tree is completed when its size is not a power of two
+    pub fn with_padding(mut self, padding: PaddingStrategy) -> Self {
+        self.padding = padding;
+        self.levels.take();
+        self.checkpoints = Checkpoints::default();
+        self
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hasher
+    }
+
+    pub fn padding(&self) -> PaddingStrategy {
+        self.padding
+    }
+
+    /// the stored values in insertion order, padding excluded
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.values.iter()
+    }
+
+    /// the stored values in insertion order, padding excluded
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.values.get(index)
+    }
+
+    /// number of stored values, padding excluded
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// number of values the tree can hold before its storage has to grow
+    pub fn capacity(&self) -> usize {
+        self.values.capacity()
+    }
+
+    /// make room for at least `additional` more values, so that appending
+    /// them does not move the stored values
+    pub fn reserve(&mut self, additional: usize) {
+        self.values.reserve(additional);
+    }
+
+    /// number of leafs including padding, the width proofs are checked with
+    /// an empty tree has a single padding leaf
+    pub fn padded_len(&self) -> usize {
+        width(self.padding, self.values.len())
+    }
+
+    /// number of levels above the leafs, the length of the longest proof path
+    pub fn height(&self) -> usize {
+        self.padded_len().next_power_of_two().trailing_zeros() as usize
+    }
+}
+
+impl<T, S: MerkleHasher> IntoIterator for MerkleTree<T, S> {
+    type Item = T;
+    type IntoIter = alloc::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+impl<'a, T, S: MerkleHasher> IntoIterator for &'a MerkleTree<T, S> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Hash + Default> MerkleTree<T> {
+    /// an empty tree with the default hasher and room for `capacity` values,
+    /// see [`with_capacity_and_hasher`]
+    ///
+    /// [`with_capacity_and_hasher`]: MerkleTree::with_capacity_and_hasher
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, DefaultBuildHasher::default())
+    }
+
+    /// build a tree whose leaf layer is `hashes`, they are used as they are and
+    /// not hashed again
+    /// padding leafs are added like for values, so a tree built from the hashes
+    /// of values equals the tree over the values
+    pub fn from_leaf_hashes(hashes: Vec<u64>) -> HashedMerkleTree {
+        Self::from_leaf_hashes_with_hasher(hashes, DefaultBuildHasher::default())
+    }
+
+    /// build a tree from the hashes of `values`, hashed one by one as they
+    /// arrive, only the leaf hashes are kept and no value is stored
+    pub fn from_hashed_iter(values: impl IntoIterator<Item = T>) -> HashedMerkleTree {
+        Self::from_hashed_iter_with_hasher(values, DefaultBuildHasher::default())
+    }
+
+    /// the root of the tree over `values` without building the tree, only the
+    /// `O(log n)` roots of an [`IncrementalMerkleTree`] are kept
+    pub fn root_of_iter(values: impl IntoIterator<Item = T>) -> Root {
+        Self::root_of_iter_with_hasher(values, DefaultBuildHasher::default())
+    }
+
+    /// the roots of empty subtrees up to `height` of trees over `T`, see
+    /// [`EmptyHashes`]
+    pub fn empty_subtree_hashes(height: usize) -> EmptyHashes<u64> {
+        Self::empty_subtree_hashes_with_hasher(&DefaultBuildHasher::default(), height)
+    }
+}
+
+impl<T: Default, S: LeafHasher<T>> MerkleTree<T, S> {
+    /// like [`from_leaf_hashes`] with digests of the given backend
+    ///
+    /// [`from_leaf_hashes`]: MerkleTree::from_leaf_hashes
+    pub fn from_leaf_hashes_with_hasher(hashes: Vec<S::Digest>, hasher: S) -> HashedMerkleTree<S> {
+        let empty = hasher.hash_padding();
+        HashedMerkleTree::new(
+            hashes,
+            empty,
+            hasher,
+            PaddingStrategy::default(),
+            OnceLock::new(),
+        )
+    }
+
+    /// like [`from_hashed_iter`] with the given hasher
+    ///
+    /// [`from_hashed_iter`]: MerkleTree::from_hashed_iter
+    pub fn from_hashed_iter_with_hasher(
+        values: impl IntoIterator<Item = T>,
+        hasher: S,
+    ) -> HashedMerkleTree<S> {
+        let hashes = values
+            .into_iter()
+            .map(|value| hasher.hash_leaf(&value))
+            .collect();
+        Self::from_leaf_hashes_with_hasher(hashes, hasher)
+    }
+
+    /// like [`root_of_iter`] with the given hasher
+    ///
+    /// [`root_of_iter`]: MerkleTree::root_of_iter
+    pub fn root_of_iter_with_hasher(
+        values: impl IntoIterator<Item = T>,
+        hasher: S,
+    ) -> Root<S::Digest> {
+        let mut tree = IncrementalMerkleTree::with_hasher(hasher);
+        values.into_iter().for_each(|value| tree.add(value));
+        tree.root()
+    }
+
+    /// like [`empty_subtree_hashes`] with the given hasher
+    ///
+    /// [`empty_subtree_hashes`]: MerkleTree::empty_subtree_hashes
+    pub fn empty_subtree_hashes_with_hasher(hasher: &S, height: usize) -> EmptyHashes<S::Digest> {
+        EmptyHashes::new(hasher, hasher.hash_padding(), height)
+    }
+}
+
+impl<T> MerkleTree<T, Domain> {
+    /// build a tree hashed with the default hasher under the domain `tag`,
+    /// see [`Domain`]
+    pub fn with_domain(values: Vec<T>, tag: &[u8]) -> Self {
+        Self::with_hasher(values, Domain::new(DefaultBuildHasher::default(), tag))
+    }
+}
+
+#[cfg(feature = "rfc6962")]
+impl<T> MerkleTree<T, crate::hash::Rfc6962> {
+    /// build a certificate transparency (RFC 6962) compatible tree
+    pub fn rfc6962(values: Vec<T>) -> Self {
+        Self::with_hasher(values, crate::hash::Rfc6962).with_padding(PaddingStrategy::PromoteOdd)
+    }
+}
+
+impl<T, S> MerkleTree<T, S>
+where
+    T: Default + MaybeSync,
+    S: LeafHasher<T> + Clone,
+{
+    /// append `value`, cached levels are kept and only the path of the new
+    /// leaf is hashed, plus the empty subtrees of the new half when the tree
+    /// outgrows its width
+    pub fn add(&mut self, value: T) {
+        let cached = self.values.len();
+        self.values.push(value);
+        self.rebuild_levels(cached, cached);
+        self.notify();
+    }
+
+    /// append all `values` like a loop of [`add`] would
+    /// only the new values are hashed as leafs, the cached leaf hashes are
+    /// kept and only the nodes above the new leafs are hashed, in a single
+    /// pass
+    ///
+    /// [`add`]: MerkleTree::add
+    pub fn add_all(&mut self, values: impl IntoIterator<Item = T>) {
+        let cached = self.values.len();
+        self.values.extend(values);
+        self.rebuild_levels(cached, cached);
+        self.notify();
+    }
+
+    /// replace the value stored at `index`
+    /// only stored values can be updated, not padding
+    /// cached levels are kept, only the path from the leaf to the root is rehashed
+    pub fn update(&mut self, index: usize, value: T) -> Result<(), MerkleError> {
+        let len = self.values.len();
+        let slot = self
+            .values
+            .get_mut(index)
+            .ok_or(MerkleError::IndexOutOfBounds { index, len })?;
+        *slot = value;
+        self.rehash_value(index);
+        self.notify();
+
+        Ok(())
+    }
+
+    /// replace the values at the indices of `changes`, like a loop of
+    /// [`update`] but every changed leaf and every node above one is hashed
+    /// only once. when an index appears more than once the last value for it
+    /// is kept. nothing changes if any index is out of bounds
+    ///
+    /// [`update`]: MerkleTree::update
+    pub fn update_all(&mut self, changes: &[(usize, T)]) -> Result<(), MerkleError>
+    where
+        T: Clone,
+    {
+        let len = self.values.len();
+        if let Some(&(index, _)) = changes.iter().find(|(index, _)| *index >= len) {
+            return Err(MerkleError::IndexOutOfBounds { index, len });
+        }
+        for (index, value) in changes {
+            self.values[*index] = value.clone();
+        }
+        if let Some(first) = changes.iter().map(|(index, _)| *index).min() {
+            self.forget_from(first, len);
+        }
+
+        if let Some(mut levels) = self.levels.take() {
+            let mut dirty: Vec<usize> = changes.iter().map(|(index, _)| *index).collect();
+            dirty.sort_unstable();
+            dirty.dedup();
+            for &index in &dirty {
+                levels[0][index] = self.hasher.hash_leaf(&self.values[index]);
+            }
+            update_paths(&self.hasher, self.padding, &mut levels, dirty);
+            self.levels = OnceLock::from(levels);
+        }
+        self.notify();
+        Ok(())
+    }
+
+    /// replace the value at `index` with `new` only if it is still equal to
+    /// `expected`, otherwise the error has the hash of the stored value and
+    /// nothing changes, not even the cached levels
+    pub fn compare_and_update(
+        &mut self,
+        index: usize,
+        expected: &T,
+        new: T,
+    ) -> Result<(), CasError<S::Digest>>
+    where
+        T: PartialEq,
+    {
+        let len = self.values.len();
+        let current = self
+            .values
+            .get(index)
+            .ok_or(CasError::IndexOutOfBounds { index, len })?;
+        if current != expected {
+            let actual = match self.levels.get() {
+                Some(levels) => levels[0][index],
+                None => self.hasher.hash_leaf(current),
+            };
+            return Err(CasError::Mismatch { index, actual });
+        }
+        self.values[index] = new;
+        self.rehash_value(index);
+        self.notify();
+        Ok(())
+    }
+
+    /// hash the path of the stored value at `index` again after it changed
+    fn rehash_value(&mut self, index: usize) {
+        self.forget_from(index, self.values.len());
+        if let Some(mut levels) = self.levels.take() {
+            let leaf = self.hasher.hash_leaf(&self.values[index]);
+            update_path(&self.hasher, self.padding, &mut levels, index, leaf);
+            self.levels = OnceLock::from(levels);
+        }
+    }
+
+    /// forget the checkpoints and versions that hold a value from `index` on
+    /// after it changed, the tree held `len` values before the change
+    /// versions reached later, e.g. after a truncate, get their roots anew
+    fn forget_from(&mut self, index: usize, len: usize) {
+        self.checkpoints.forget_from(index);
+        let lost = index + 1..len + 1;
+        let stale = match (self.stale.is_empty(), lost.is_empty()) {
+            (_, true) => self.stale.clone(),
+            (true, false) => lost,
+            // the versions between two rewrites are forgotten as well
+            (false, false) => self.stale.start.min(lost.start)..self.stale.end.max(lost.end),
+        };
+        self.stale = stale.start..stale.end.min(self.values.len() + 1);
+    }
+
+    /// remove the last value, the root becomes the one of the tree without it
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.values.pop()?;
+        let len = self.values.len();
+        self.rebuild_levels(len, len);
+        self.notify();
+        Some(value)
+    }
+
+    /// drop all values from `len` on, nothing changes if the tree holds no
+    /// more than `len` values
+    /// the root becomes the one of a tree built from the remaining values
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.values.len() {
+            return;
+        }
+        self.values.truncate(len);
+        self.rebuild_levels(len, len);
+        self.notify();
+    }
+
+    /// remove the value at `index` and shift all values after it down by one
+    ///
+    /// every later value changes its index, proofs generated before for any
+    /// of them no longer verify against the new root. only stored values can
+    /// be removed, not padding
+    pub fn remove(&mut self, index: usize) -> Result<T, MerkleError> {
+        let len = self.values.len();
+        if index >= len {
+            return Err(MerkleError::IndexOutOfBounds { index, len });
+        }
+        let value = self.values.remove(index);
+        self.forget_from(index, len);
+
+        if let Some(mut levels) = self.levels.take() {
+            levels[0].remove(index);
+            self.levels = OnceLock::from(levels);
+            self.rebuild_levels(len - 1, index);
+        }
+        self.notify();
+        Ok(value)
+    }
+
+    /// update the cached levels after the values from `dirty` on changed
+    /// the first `cached` leaf hashes are kept, the values after them are
+    /// hashed, and only the nodes above the changed leafs are hashed again.
+    /// the padding follows the new size of the tree
+    fn rebuild_levels(&mut self, cached: usize, dirty: usize) {
+        self.forget_from(dirty, dirty);
+        if let Some(mut levels) = self.levels.take() {
+            let leafs = &mut levels[0];
+            leafs.truncate(cached);
+            let added = self.values[cached..].iter();
+            leafs.extend(added.map(|value| self.hasher.hash_leaf(value)));
+            let len = self.values.len();
+            let empty = || self.hasher.hash_padding();
+            let empties = &mut self.empties;
+            refresh_levels(
+                &self.hasher,
+                self.padding,
+                &mut levels,
+                len,
+                dirty,
+                empties,
+                empty,
+            );
+            self.levels = OnceLock::from(levels);
+        }
+    }
+
+    /// drop the stored values and keep only their leaf hashes
+    /// the cached levels are kept, roots and proofs stay the same
+    pub fn finalize(self) -> HashedMerkleTree<S> {
+        let empty = self.hasher.hash_padding();
+        let leafs = self.cached_levels()[0][..self.values.len()].to_vec();
+
+        HashedMerkleTree::new(leafs, empty, self.hasher, self.padding, self.levels)
+    }
+
+    /// write the leaf hashes to the file at `path`, the values are not
+    /// written. see [`HashedMerkleTree::save`]
+    #[cfg(feature = "std")]
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let empty = self.hasher.hash_padding();
+        let leafs = &self.cached_levels()[0][..self.values.len()];
+        let bytes = crate::hashed::file::encode(&self.hasher, self.padding, leafs, &empty, None);
+        std::fs::write(path, bytes)
+    }
+
+    /// read the leaf hashes a tree of values of type `T` saved, see
+    /// [`HashedMerkleTree::load`]
+    /// a file with another padding leaf than the one of `S` is rejected
+    #[cfg(feature = "std")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> std::io::Result<HashedMerkleTree<S>>
+    where
+        S: Default,
+    {
+        let tree = HashedMerkleTree::<S>::load(path)?;
+        if tree.empty() != S::default().hash_padding() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                MerkleError::InvalidEncoding("file was written with another padding leaf"),
+            ));
+        }
+        Ok(tree)
+    }
+
+    /// hash the stored values one by one in insertion order, padding excluded
+    pub fn leaf_hashes(&self) -> impl Iterator<Item = S::Digest> + '_ {
+        self.values.iter().map(|value| self.hasher.hash_leaf(value))
+    }
+
+    /// like [`leaf_hashes`] but followed by the padding leafs, yields the
+    /// same hashes as [`leafs`]
+    ///
+    /// [`leaf_hashes`]: MerkleTree::leaf_hashes
+    /// [`leafs`]: MerkleTree::leafs
+    pub fn padded_leaf_hashes(&self) -> impl Iterator<Item = S::Digest> + '_ {
+        let padding = width(self.padding, self.values.len()) - self.values.len();
+        let empty = (padding > 0).then(|| self.hasher.hash_padding());
+
+        self.leaf_hashes().chain(
+            empty
+                .into_iter()
+                .flat_map(move |empty| core::iter::repeat_n(empty, padding)),
+        )
+    }
+
+    /// return the leafs of the merkle tree
+    /// the leafs are hashed values or padding leafs
+    /// when padding with default values the number of leafs always equals the
+    /// smallest power of two that is greater than the number of values stored in
+    /// the tree, otherwise it equals the number of values
+    /// an empty tree always has a single padding leaf
+    pub fn leafs(&self) -> &[S::Digest] {
+        &self.cached_levels()[0]
+    }
+
+    /// every level of the tree from the leafs up to the root, the last one
+    /// holds the root only
+    /// the first level is [`leafs`](MerkleTree::leafs), each level above is
+    /// half as wide and rounded up, the odd node of a level is completed
+    /// like [`padding`](MerkleTree::padding) says. the levels are hashed on
+    /// first use and cached like for [`root`](MerkleTree::root)
+    pub fn levels(&self) -> impl ExactSizeIterator<Item = &[S::Digest]> + DoubleEndedIterator + '_ {
+        self.cached_levels().iter().map(Vec::as_slice)
+    }
+
+    /// the node at `index` of `level`, level `0` are the leafs and
+    /// [`height`](MerkleTree::height) the root
+    pub fn node(&self, level: usize, index: usize) -> Option<S::Digest> {
+        self.cached_levels().get(level)?.get(index).copied()
+    }
+
+    pub fn root(&self) -> Root<S::Digest> {
+        let levels = self.cached_levels();
+        Root(levels[levels.len() - 1][0])
+    }
+
+    /// whether both trees commit to the same root, trees over different
+    /// values can share a root if their leaf hashes are equal
+    pub fn same_root<U: Default + MaybeSync>(&self, other: &MerkleTree<U, S>) -> bool
+    where
+        S: LeafHasher<U>,
+    {
+        self.root() == other.root()
+    }
+
+    /// proof that the value at `index` is part of the tree
+    /// indices at or beyond [`len`](MerkleTree::len) are out of bounds, even
+    /// where padding fills the leaf layer, see
+    /// [`get_padding_proof`](MerkleTree::get_padding_proof)
+    pub fn get_proof(&self, index: usize) -> Result<MerkleProof<S>, MerkleError> {
+        let len = self.values.len();
+        if index >= len {
+            return Err(MerkleError::IndexOutOfBounds { index, len });
+        }
+        self.proof_from(self.cached_levels(), index)
+    }
+
+    /// proof that the padding leaf at `index` is part of the tree, i.e. that
+    /// no value was written there
+    /// leafs that hold a value are [`NotPadding`](MerkleError::NotPadding)
+    pub fn get_padding_proof(&self, index: usize) -> Result<MerkleProof<S>, MerkleError> {
+        let len = self.values.len();
+        if index < len {
+            return Err(MerkleError::NotPadding { index, len });
+        }
+        self.proof_from(self.cached_levels(), index)
+    }
+
+    /// proof that the node at `index` of `level` is part of the tree, the leaf
+    /// of the proof is that node
+    /// it is the root of the subtree over the leafs `index << level` up to
+    /// `(index + 1) << level`, level `0` gives ordinary leaf proofs
+    pub fn get_subtree_proof(
+        &self,
+        level: usize,
+        index: usize,
+    ) -> Result<MerkleProof<S>, MerkleError> {
+        let levels = self.cached_levels();
+        if level >= levels.len() {
+            return Err(MerkleError::IndexOutOfBounds {
+                index: level,
+                len: levels.len(),
+            });
+        }
+        self.proof_from(&levels[level..], index)
+    }
+
+    /// like [`get_subtree_proof`] for the subtree whose leafs are `range`, a
+    /// range that is not a power of two wide and aligned to its width is an
+    /// error
+    ///
+    /// [`get_subtree_proof`]: MerkleTree::get_subtree_proof
+    pub fn get_subtree_proof_for(
+        &self,
+        range: Range<usize>,
+    ) -> Result<MerkleProof<S>, MerkleError> {
+        let (level, index) = subtree_position(range, self.padded_len())?;
+        self.get_subtree_proof(level, index)
+    }
+
+    /// number of values added so far, every [`add`] starts a new version
+    ///
+    /// [`add`]: MerkleTree::add
+    pub fn version(&self) -> usize {
+        self.values.len()
+    }
+
+    /// the root of the tree as it was when it held its first `version` values
+    ///
+    /// only the current values are kept, the roots of old versions are
+    /// rebuilt from them. a version one of whose values was changed or
+    /// removed after it was reached would get another root than it had, it
+    /// is [`StaleVersion`](MerkleError::StaleVersion) instead. the current
+    /// version is stale too until the next [`add`](MerkleTree::add)
+    pub fn root_at(&self, version: usize) -> Result<Root<S::Digest>, MerkleError> {
+        self.check_version(version)?;
+        let leafs = self.cached_levels()[0][..version].to_vec();
+        let empty = || self.hasher.hash_padding();
+        Ok(Root(fold_root(&self.hasher, self.padding, leafs, empty)))
+    }
+
+    /// a proof for the value at `index` against the root of `version`, see
+    /// [`root_at`](MerkleTree::root_at) for the versions that have one
+    pub fn get_proof_at(
+        &self,
+        index: usize,
+        version: usize,
+    ) -> Result<MerkleProof<S>, MerkleError> {
+        let levels = self.levels_at(version)?;
+        if index >= version {
+            return Err(MerkleError::IndexOutOfBounds {
+                index,
+                len: version,
+            });
+        }
+        self.proof_from(&levels, index)
+    }
+
+    /// the roots of empty subtrees up to the height of the tree, for
+    /// [compact proofs](MerkleProof::compact)
+    pub fn empty_hashes(&self) -> EmptyHashes<S::Digest> {
+        EmptyHashes::new(&self.hasher, self.hasher.hash_padding(), self.height())
+    }
+
+    /// generate a separate proof for each of the given leafs
+    /// all indices are checked before any path is read, the proofs equal the
+    /// ones returned by [`get_proof`]
+    ///
+    /// [`get_proof`]: MerkleTree::get_proof
+    pub fn proofs_for(&self, indices: &[usize]) -> Result<Vec<MerkleProof<S>>, MerkleError> {
+        let len = self.values.len();
+        if let Some(&index) = indices.iter().find(|&&index| index >= len) {
+            return Err(MerkleError::IndexOutOfBounds { index, len });
+        }
+
+        indices.iter().map(|&index| self.get_proof(index)).collect()
+    }
+
+    /// generate a single proof for several leafs
+    /// indices may be given in any order and contain duplicates, sibling hashes
+    /// shared between the individual paths are only included once
+    pub fn get_multi_proof(&self, indices: &[usize]) -> Result<MerkleMultiProof<S>, MerkleError> {
+        if indices.is_empty() {
+            return Err(MerkleError::NoIndices);
+        }
+        let levels = self.cached_levels();
+
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+
+        let len = self.values.len();
+        let mut leafs = Vec::with_capacity(indices.len());
+        for &index in &indices {
+            if index >= len {
+                return Err(MerkleError::IndexOutOfBounds { index, len });
+            }
+            leafs.push((index, levels[0][index]));
+        }
+
+        let mut proof = MerkleMultiProof {
+            leafs,
+            decommitments: Vec::new(),
+            width: levels[0].len(),
+            padding: self.padding,
+            root: self.root(),
+            hasher: self.hasher.clone(),
+        };
+
+        for hashes in &levels[..levels.len() - 1] {
+            let mut i = 0;
+            while i < indices.len() {
+                let index = indices[i];
+                let sibling = index ^ 1;
+
+                // both siblings are known, their parent can be computed
+                if index.is_multiple_of(2) && indices.get(i + 1) == Some(&sibling) {
+                    i += 2;
+                    continue;
+                }
+
+                // an odd node without sibling is promoted or paired with itself,
+                // the verifier can do either on its own
+                if let Some(&value) = hashes.get(sibling) {
+                    proof.decommitments.push(value);
+                }
+                i += 1;
+            }
+
+            indices.iter_mut().for_each(|index| *index /= 2);
+            indices.dedup();
+        }
+
+        Ok(proof)
+    }
+
+    /// generate a single proof for the contiguous leafs of `range`
+    /// it holds at most two hashes per level, the ones left and right of the
+    /// range, the verifier recomputes everything in between from the leafs
+    pub fn get_range_proof(&self, range: Range<usize>) -> Result<RangeProof<S>, MerkleError> {
+        let levels = self.cached_levels();
+        Ok(RangeProof {
+            decommitments: range_decommitments(levels, self.values.len(), range)?,
+            width: levels[0].len(),
+            padding: self.padding,
+            hasher: self.hasher.clone(),
+        })
+    }
+
+    /// prove that this tree extends its first `old_len` values by appending
+    /// only trees padded with [`PaddingStrategy::PromoteOdd`] are supported, the
+    /// proof follows RFC 6962 section 2.1.2
+    pub fn consistency_proof(&self, old_len: usize) -> Result<ConsistencyProof<S>, MerkleError> {
+        if self.padding != PaddingStrategy::PromoteOdd {
+            return Err(MerkleError::UnsupportedPadding(self.padding));
+        }
+        let new_len = self.values.len();
+        if old_len == 0 || old_len > new_len {
+            return Err(MerkleError::ConsistencySize { old_len, new_len });
+        }
+
+        let mut path = Vec::new();
+        self.subproof(
+            old_len,
+            &self.cached_levels()[0][..new_len],
+            true,
+            &mut path,
+        );
+
+        Ok(ConsistencyProof {
+            path,
+            hasher: self.hasher.clone(),
+        })
+    }
+
+    /// SUBPROOF of RFC 6962 for the first `m` of the given leafs
+    fn subproof(&self, m: usize, leafs: &[S::Digest], complete: bool, path: &mut Vec<S::Digest>) {
+        let n = leafs.len();
+        if m == n {
+            if !complete {
+                path.push(self.subtree_root(leafs));
+            }
+            return;
+        }
+
+        let k = split(n);
+        if m <= k {
+            self.subproof(m, &leafs[..k], complete, path);
+            path.push(self.subtree_root(&leafs[k..]));
+        } else {
+            self.subproof(m - k, &leafs[k..], false, path);
+            path.push(self.subtree_root(&leafs[..k]));
+        }
+    }
+
+    /// root of the RFC 6962 shaped tree over the given leafs
+    fn subtree_root(&self, leafs: &[S::Digest]) -> S::Digest {
+        if leafs.len() == 1 {
+            return leafs[0];
+        }
+        let k = split(leafs.len());
+        self.hasher.hash_siblings(
+            &self.subtree_root(&leafs[..k]),
+            &self.subtree_root(&leafs[k..]),
+        )
+    }
+
+    /// indices of the values whose leaf hashes differ from the ones of `other`
+    /// only subtrees whose roots differ are descended into, so `k` changed
+    /// values cost `O(k log n)` comparisons once both trees are hashed
+    /// both trees need the same length and padding, compare a prefix with
+    /// [`root_at`](MerkleTree::root_at) to tell appends apart
+    pub fn diff(&self, other: &Self) -> Result<Vec<usize>, MerkleError> {
+        if self.len() != other.len() {
+            return Err(MerkleError::LengthMismatch {
+                len: self.len(),
+                other: other.len(),
+            });
+        }
+        if self.padding != other.padding {
+            return Err(MerkleError::PaddingMismatch {
+                padding: self.padding,
+                other: other.padding,
+            });
+        }
+        Ok(diff_levels(
+            self.cached_levels(),
+            other.cached_levels(),
+            self.len(),
+        ))
+    }
+
+    /// check whether the given leaf hash is part of the tree
+    pub fn contains(&self, hash: &S::Digest) -> bool {
+        self.cached_levels()[0].contains(hash)
+    }
+
+    /// check whether a value with the same leaf hash is stored in the tree
+    /// padding leafs don't count, see [`position_of`]
+    ///
+    /// [`position_of`]: MerkleTree::position_of
+    pub fn contains_value(&self, value: &T) -> bool {
+        self.position_of(value).is_some()
+    }
+
+    /// index of the first stored value whose leaf hash equals the one of
+    /// `value`
+    /// values are compared by their hash, not with `Eq`, so two values that
+    /// hash the same are found at the same position
+    pub fn position_of(&self, value: &T) -> Option<usize> {
+        let hash = self.hasher.hash_leaf(value);
+        self.cached_levels()[0][..self.values.len()]
+            .iter()
+            .position(|leaf| *leaf == hash)
+    }
+
+    /// indices of all stored values whose leaf hash equals the one of `value`
+    pub fn positions_of(&self, value: &T) -> Vec<usize> {
+        positions(
+            &self.cached_levels()[0][..self.values.len()],
+            self.hasher.hash_leaf(value),
+        )
+    }
+
+    /// the proof of the first stored value that hashes like `value`
+    pub fn get_proof_by_value(&self, value: &T) -> Result<MerkleProof<S>, MerkleError> {
+        let index = self.position_of(value).ok_or(MerkleError::ValueNotFound)?;
+        self.get_proof(index)
+    }
+
+    /// check that the proof path leads from its leaf to its root
+    #[deprecated(note = "use `MerkleProof::verify_against` with a trusted root")]
+    pub fn verify_proof(proof: &MerkleProof<S>) -> bool {
+        proof.verify()
+    }
+
+    /// check that the leafs of the multiproof lead to its root
+    #[deprecated(note = "use `MerkleMultiProof::verify_against` with a trusted root")]
+    pub fn verify_multi_proof(proof: &MerkleMultiProof<S>) -> bool {
+        proof.verify()
+    }
+
+    fn proof_from(
+        &self,
+        levels: &[Vec<S::Digest>],
+        index: usize,
+    ) -> Result<MerkleProof<S>, MerkleError> {
+        let (leaf, path) = proof_path(levels, self.padding, index)?;
+
+        Ok(MerkleProof {
+            path,
+            root: Root(levels[levels.len() - 1][0]),
+            leaf: LeafHash(leaf),
+            index,
+            width: levels[0].len(),
+            padding: self.padding,
+            hasher: self.hasher.clone(),
+        })
+    }
+
+    /// an error for versions after the latest one and for stale ones
+    fn check_version(&self, version: usize) -> Result<(), MerkleError> {
+        let latest = self.values.len();
+        if version > latest {
+            return Err(MerkleError::UnknownVersion { version, latest });
+        }
+        if self.stale.contains(&version) {
+            return Err(MerkleError::StaleVersion { version });
+        }
+        Ok(())
+    }
+
+    /// the levels of the tree over the first `version` values, built from the
+    /// cached leaf hashes
+    fn levels_at(&self, version: usize) -> Result<Vec<Vec<S::Digest>>, MerkleError> {
+        self.check_version(version)?;
+        let leafs = self.cached_levels()[0][..version].to_vec();
+        let empty = || self.hasher.hash_padding();
+        Ok(build_padded_levels(
+            &self.hasher,
+            self.padding,
+            leafs,
+            empty,
+        ))
+    }
+
+    /// the cached levels of the tree, leafs first and root last
+    /// values are hashed by reference, the padding leaf is hashed only once
+    pub(crate) fn cached_levels(&self) -> &[Vec<S::Digest>] {
+        self.levels.get_or_init(|| {
+            let leafs = hash_leafs(&self.hasher, &self.values);
+            let empty = || self.hasher.hash_padding();
+            build_padded_levels(&self.hasher, self.padding, leafs, empty)
+        })
+    }
+}
+
+/// the largest power of two smaller than `n`, where RFC 6962 splits a tree
+fn split(n: usize) -> usize {
+    1 << (n - 1).ilog2()
+}
+
+/// indices of the leafs equal to `hash`
+pub(crate) fn positions<D: Digest>(leafs: &[D], hash: D) -> Vec<usize> {
+    leafs
+        .iter()
+        .enumerate()
+        .filter(|(_, leaf)| **leaf == hash)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// the most leafs a tree can have, padding included, it is the largest power
+/// of two a `usize` holds
+pub const MAX_LEAFS: usize = 1 << (usize::BITS - 1);
+
+/// like [`width`] for a `len` that is not known to fit, a tree wider than
+/// `max` leafs, or than [`MAX_LEAFS`], is [`TooLarge`](MerkleError::TooLarge)
+pub(crate) fn checked_width(
+    padding: PaddingStrategy,
+    len: usize,
+    max: usize,
+) -> Result<usize, MerkleError> {
+    let max = max.min(MAX_LEAFS);
+    if len > max {
+        return Err(MerkleError::TooLarge { len, max });
+    }
+    // at most `MAX_LEAFS` now, padding it to a power of two can't overflow
+    let width = width(padding, len);
+    if width > max {
+        return Err(MerkleError::TooLarge { len, max });
+    }
+    Ok(width)
+}
+
+/// number of leafs of a tree storing `len` values, padding included
+/// `len` has to be at most [`MAX_LEAFS`], see [`checked_width`]
+pub(crate) fn width(padding: PaddingStrategy, len: usize) -> usize {
+    match padding {
+        PaddingStrategy::DefaultValue => len.next_power_of_two(),
+        PaddingStrategy::DuplicateLast | PaddingStrategy::PromoteOdd => len.max(1),
+    }
+}
+
+/// hash the leaf level up to the root, leafs first and root last
+pub(crate) fn build_levels<S: MerkleHasher>(
+    hasher: &S,
+    padding: PaddingStrategy,
+    leafs: Vec<S::Digest>,
+) -> Vec<Vec<S::Digest>> {
+    let mut levels = vec![leafs];
+    while levels[levels.len() - 1].len() > 1 {
+        let parents = parents(hasher, padding, &levels[levels.len() - 1]);
+        levels.push(parents);
+    }
+    levels
+}
+
+/// like [`build_levels`] over `leafs` padded with the padding leaf `empty` to
+/// the width of the tree
+/// nodes above padding only are taken from [`EmptyHashes`] built along the way
+/// instead of being hashed, `empty` is only called if there is padding
+pub(crate) fn build_padded_levels<S: MerkleHasher>(
+    hasher: &S,
+    padding: PaddingStrategy,
+    mut leafs: Vec<S::Digest>,
+    empty: impl FnOnce() -> S::Digest,
+) -> Vec<Vec<S::Digest>> {
+    let size = width(padding, leafs.len());
+    if size == leafs.len() {
+        return build_levels(hasher, padding, leafs);
+    }
+    let empty = empty();
+    let mut filled = leafs.len();
+    leafs.resize(size, empty);
+    if padding != PaddingStrategy::DefaultValue {
+        return build_levels(hasher, padding, leafs);
+    }
+
+    let mut empties = EmptyHashes::new(hasher, empty, 0);
+    let mut levels = vec![leafs];
+    while levels[levels.len() - 1].len() > 1 {
+        let height = levels.len();
+        let below = &levels[height - 1];
+        // the parents of the filled nodes, the last one may pair with padding
+        filled = filled.div_ceil(2);
+        let mut parents = hash_level(hasher, &below[..2 * filled]);
+        if parents.len() < below.len() / 2 {
+            empties.extend_to(hasher, height);
+            parents.resize(below.len() / 2, empties[height]);
+        }
+        levels.push(parents);
+    }
+    levels
+}
+
+/// the root of the tree over `leafs`, like the top of [`build_padded_levels`]
+/// every level is folded into the front of `leafs`, parent `j` overwrites
+/// node `j` once both children are read, so no level is allocated
+pub(crate) fn fold_root<S: MerkleHasher>(
+    hasher: &S,
+    padding: PaddingStrategy,
+    mut leafs: Vec<S::Digest>,
+    empty: impl FnOnce() -> S::Digest,
+) -> S::Digest {
+    let mut size = width(padding, leafs.len());
+    if leafs.is_empty() {
+        return empty();
+    }
+
+    // padding only subtrees are looked up, only the last filled node of a
+    // level may pair with one
+    let mut empties = (size > leafs.len()).then(|| EmptyHashes::new(hasher, empty(), 0));
+    let mut height = 0;
+    while size > 1 {
+        let len = leafs.len();
+        if let Some(empties) = empties.as_mut().filter(|_| !len.is_multiple_of(2)) {
+            empties.extend_to(hasher, height);
+            leafs.push(empties[height]);
+        }
+        for j in 0..len.div_ceil(2) {
+            leafs[j] = parent(hasher, padding, &leafs[2 * j], leafs.get(2 * j + 1));
+        }
+        leafs.truncate(len.div_ceil(2));
+        size = size.div_ceil(2);
+        height += 1;
+    }
+    leafs[0]
+}
+
+/// replace the leaf at `index` and rehash its path up to the root
+pub(crate) fn update_path<S: MerkleHasher>(
+    hasher: &S,
+    padding: PaddingStrategy,
+    levels: &mut [Vec<S::Digest>],
+    index: usize,
+    leaf: S::Digest,
+) {
+    let mut index = index;
+    levels[0][index] = leaf;
+
+    for level in 1..levels.len() {
+        let left = index & !1;
+        let below = &levels[level - 1];
+        let parent = parent(hasher, padding, &below[left], below.get(left + 1));
+        index /= 2;
+        levels[level][index] = parent;
+    }
+}
+
+/// rehash the nodes above the leafs at the sorted, distinct indices of
+/// `dirty`, which are already replaced, every node is hashed once
+pub(crate) fn update_paths<S: MerkleHasher>(
+    hasher: &S,
+    padding: PaddingStrategy,
+    levels: &mut [Vec<S::Digest>],
+    mut dirty: Vec<usize>,
+) {
+    for level in 1..levels.len() {
+        for index in &mut dirty {
+            *index /= 2;
+        }
+        dirty.dedup();
+        let (below, above) = levels.split_at_mut(level);
+        let (below, nodes) = (&below[level - 1], &mut above[0]);
+        for &index in &dirty {
+            nodes[index] = parent(hasher, padding, &below[2 * index], below.get(2 * index + 1));
+        }
+    }
+}
+
+/// bring cached `levels` up to date after the leafs from `dirty` on changed,
+/// the leaf layer has to start with the `len` leaf hashes of the tree
+/// only the nodes above a changed leaf are hashed again, nodes above padding
+/// only are taken from `empties`, which is filled on first use with the
+/// padding leaf `empty` and kept for later updates. the leaf layer is padded
+/// to the width of the tree, levels are added or dropped when it crosses a
+/// power of two
+pub(crate) fn refresh_levels<S: MerkleHasher>(
+    hasher: &S,
+    padding: PaddingStrategy,
+    levels: &mut Vec<Vec<S::Digest>>,
+    len: usize,
+    dirty: usize,
+    empties: &mut Option<EmptyHashes<S::Digest>>,
+    empty: impl FnOnce() -> S::Digest,
+) {
+    let size = width(padding, len);
+    let layer = &mut levels[0];
+    layer.truncate(len);
+    let mut empties = if size > len {
+        let empties = empties.get_or_insert_with(|| EmptyHashes::new(hasher, empty(), 0));
+        layer.resize(size, empties[0]);
+        Some(empties)
+    } else {
+        None
+    };
+
+    let (mut clean, mut filled, mut height) = (dirty, len, 1);
+    while levels[height - 1].len() > 1 {
+        let nodes = levels[height - 1].len().div_ceil(2);
+        clean /= 2;
+        filled = filled.div_ceil(2);
+        if levels.len() == height {
+            levels.push(Vec::with_capacity(nodes));
+        }
+        let (below, above) = levels.split_at_mut(height);
+        let (below, level) = (&below[height - 1], &mut above[0]);
+        level.truncate(clean);
+        for index in level.len()..nodes {
+            let node = match &mut empties {
+                Some(empties) if index >= filled => {
+                    empties.extend_to(hasher, height);
+                    empties[height]
+                }
+                _ => parent(hasher, padding, &below[2 * index], below.get(2 * index + 1)),
+            };
+            level.push(node);
+        }
+        height += 1;
+    }
+    levels.truncate(height);
+}
+
+/// level and index of the node whose subtree has the leafs of `range` in a
+/// tree of `width` leafs
+pub(crate) fn subtree_position(
+    range: Range<usize>,
+    width: usize,
+) -> Result<(usize, usize), MerkleError> {
+    let size = range.len();
+    if !size.is_power_of_two() || !range.start.is_multiple_of(size) {
+        return Err(MerkleError::UnalignedSubtree {
+            start: range.start,
+            end: range.end,
+        });
+    }
+    if range.end > width {
+        return Err(MerkleError::IndexOutOfBounds {
+            index: range.end - 1,
+            len: width,
+        });
+    }
+    Ok((size.trailing_zeros() as usize, range.start / size))
+}
+
+/// indices below `len` of the leafs that differ between two trees of the same
+/// shape, descending only into the nodes that differ
+pub(crate) fn diff_levels<D: Digest>(ours: &[Vec<D>], theirs: &[Vec<D>], len: usize) -> Vec<usize> {
+    let top = ours.len() - 1;
+    let mut changed = Vec::new();
+    // nodes still to compare, as level and index, leftmost on top
+    let mut pending = vec![(top, 0)];
+    while let Some((level, index)) = pending.pop() {
+        if ours[level][index] == theirs[level][index] {
+            continue;
+        }
+        if level == 0 {
+            if index < len {
+                changed.push(index);
+            }
+            continue;
+        }
+        // a promoted node has no right child
+        let right = 2 * index + 1;
+        if right < ours[level - 1].len() {
+            pending.push((level - 1, right));
+        }
+        pending.push((level - 1, 2 * index));
+    }
+    changed
+}
+
+/// the leaf at `index` and the sibling path from it up to the root
+pub(crate) fn proof_path<D: Digest>(
+    levels: &[Vec<D>],
+    padding: PaddingStrategy,
+    index: usize,
+) -> Result<(D, Vec<Step<D>>), MerkleError> {
+    let leaf = *levels[0].get(index).ok_or(MerkleError::IndexOutOfBounds {
+        index,
+        len: levels[0].len(),
+    })?;
+    let mut index = index;
+    let mut path = Vec::new();
+
+    for hashes in &levels[..levels.len() - 1] {
+        let sibling = if index.is_multiple_of(2) {
+            index + 1
+        } else {
+            index - 1
+        };
+        let direction = if index > sibling {
+            Direction::Left
+        } else {
+            Direction::Right
+        };
+        match hashes.get(sibling) {
+            Some(&value) => path.push(Step { direction, value }),
+            // the odd node is paired with itself
+            None if padding == PaddingStrategy::DuplicateLast => path.push(Step {
+                direction,
+                value: hashes[index],
+            }),
+            // a promoted node has no sibling on this level
+            None => {}
+        }
+
+        index /= 2;
+    }
+
+    Ok((leaf, path))
+}
+
+/// the hashes next to `range` on every level that can't be computed from the
+/// leafs of the range, left one before right one and levels from the leafs up
+/// the range has to lie within the first `len` leafs, the stored values
+pub(crate) fn range_decommitments<D: Digest>(
+    levels: &[Vec<D>],
+    len: usize,
+    range: Range<usize>,
+) -> Result<Vec<D>, MerkleError> {
+    if range.is_empty() {
+        return Err(MerkleError::NoIndices);
+    }
+    if range.end > len {
+        return Err(MerkleError::IndexOutOfBounds {
+            index: range.end - 1,
+            len,
+        });
+    }
+
+    let (mut start, mut end) = (range.start, range.end);
+    let mut decommitments = Vec::new();
+    for hashes in &levels[..levels.len() - 1] {
+        if start % 2 == 1 {
+            decommitments.push(hashes[start - 1]);
+        }
+        // the odd node of a level has no sibling to decommit
+        if end % 2 == 1 && end < hashes.len() {
+            decommitments.push(hashes[end]);
+        }
+        start /= 2;
+        end = end.div_ceil(2);
+    }
+    Ok(decommitments)
+}
+
+/// hash a level into the level above it
+/// an odd node is completed according to the padding strategy, levels of
+/// trees padded with default values are padded before and never odd. should
+/// one be, its odd node is carried up like a promoted one
+pub(crate) fn parents<S: MerkleHasher>(
+    hasher: &S,
+    padding: PaddingStrategy,
+    hashes: &[S::Digest],
+) -> Vec<S::Digest> {
+    let (pairs, odd) = hashes.split_at(hashes.len() & !1);
+    let mut parents = hash_level(hasher, pairs);
+    if let Some(odd) = odd.first() {
+        parents.push(parent(hasher, padding, odd, None));
+    }
+    parents
+}
+
+/// the leaf hashes of `values`, on all cores with the `parallel` feature
+/// once there are enough of them
+pub(crate) fn hash_leafs<T: MaybeSync, S: LeafHasher<T>>(
+    hasher: &S,
+    values: &[T],
+) -> Vec<S::Digest> {
+    #[cfg(feature = "parallel")]
+    if values.len() >= crate::parallel::MIN_PARALLEL_WIDTH {
+        return crate::parallel::map(values, |value| hasher.hash_leaf(value));
+    }
+    values.iter().map(|value| hasher.hash_leaf(value)).collect()
+}
+
+/// the parents of the sibling pairs of `pairs`, like
+/// [`hash_pairs`](MerkleHasher::hash_pairs) but wide levels are split into
+/// one run per core with the `parallel` feature
+pub(crate) fn hash_level<S: MerkleHasher>(hasher: &S, pairs: &[S::Digest]) -> Vec<S::Digest> {
+    #[cfg(feature = "parallel")]
+    if pairs.len() >= crate::parallel::MIN_PARALLEL_WIDTH {
+        let run = 2 * pairs.len().div_ceil(2 * crate::parallel::threads());
+        let runs = crate::parallel::map_chunks(pairs, run, |run| hasher.hash_pairs(run));
+        return runs.concat();
+    }
+    hasher.hash_pairs(pairs)
+}
+
+/// hash two siblings into their parent, completing an odd node without
+/// sibling according to the padding strategy
+fn parent<S: MerkleHasher>(
+    hasher: &S,
+    padding: PaddingStrategy,
+    left: &S::Digest,
+    right: Option<&S::Digest>,
+) -> S::Digest {
+    match (right, padding) {
+        (Some(right), _) => hasher.hash_siblings(left, right),
+        (None, PaddingStrategy::DuplicateLast) => hasher.hash_siblings(left, left),
+        (None, _) => *left,
+    }
+}
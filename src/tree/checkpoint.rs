@@ -0,0 +1,137 @@
+//! marks a tree can be rewound to after speculative appends
+//!
+//! appends after a checkpoint only change the nodes right of its last leaf,
+//! so the tree at the checkpoint is the tree truncated to its length with the
+//! last filled node of every level as it was. a checkpoint keeps those nodes
+//! and the padding next to them, rewinding puts them back without hashing.
+//! a change to any of the values a checkpoint covers, including rewinding or
+//! truncating past it, forgets it.
+
+use alloc::vec::Vec;
+
+use super::MerkleTree;
+use crate::{
+    error::MerkleError,
+    hash::{LeafHasher, MaybeSync},
+};
+
+/// a length a [`MerkleTree`] can be rewound to, see
+/// [`checkpoint`](MerkleTree::checkpoint)
+/// it only belongs to the tree that made it and its clones
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    id: u64,
+    len: usize,
+}
+
+impl Checkpoint {
+    /// number of values the tree held at the checkpoint
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// the nodes of one level from the first one a later append can change on
+#[derive(Clone)]
+struct Edge<D> {
+    /// the node over the last leafs that don't fill a complete subtree
+    node: Option<D>,
+    /// the node over padding only and the width it is repeated up to
+    padding: Option<(D, usize)>,
+}
+
+/// the checkpoints of a tree that are still valid, oldest first
+#[derive(Clone)]
+pub(crate) struct Checkpoints<D> {
+    next: u64,
+    /// the edge of every level, if the levels were cached at the checkpoint
+    saved: Vec<(Checkpoint, Option<Vec<Edge<D>>>)>,
+}
+
+impl<D> Default for Checkpoints<D> {
+    fn default() -> Self {
+        Self {
+            next: 0,
+            saved: Vec::new(),
+        }
+    }
+}
+
+impl<D> Checkpoints<D> {
+    /// forget the checkpoints that cover the value at `index`
+    pub(super) fn forget_from(&mut self, index: usize) {
+        self.saved.retain(|(checkpoint, _)| checkpoint.len <= index);
+    }
+}
+
+impl<T: Default + MaybeSync, S: LeafHasher<T> + Clone> MerkleTree<T, S> {
+    /// mark the current length to [`rewind`](MerkleTree::rewind) to later
+    /// only the `O(log n)` nodes on the right edge of the cached levels are
+    /// kept, nothing is hashed
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let len = self.values.len();
+        let checkpoint = Checkpoint {
+            id: self.checkpoints.next,
+            len,
+        };
+        self.checkpoints.next += 1;
+
+        let edges = self.levels.get().map(|levels| {
+            let mut edges = Vec::with_capacity(levels.len());
+            for (height, level) in levels.iter().enumerate() {
+                let (clean, filled) = (len >> height, len.div_ceil(1 << height));
+                edges.push(Edge {
+                    node: (filled > clean).then(|| level[clean]),
+                    padding: (level.len() > filled).then(|| (level[filled], level.len())),
+                });
+            }
+            edges
+        });
+        self.checkpoints.saved.push((checkpoint, edges));
+        checkpoint
+    }
+
+    /// drop the values appended after `checkpoint`, the root and proofs
+    /// become the ones of the tree at the checkpoint again
+    ///
+    /// the checkpoint stays valid and later ones are forgotten. a checkpoint
+    /// that was forgotten, because the tree was rewound or truncated past it
+    /// or one of the values it covers changed, gives
+    /// [`StaleCheckpoint`](MerkleError::StaleCheckpoint). if the levels were
+    /// not cached at the checkpoint the path of the last value is hashed again
+    pub fn rewind(&mut self, checkpoint: Checkpoint) -> Result<(), MerkleError> {
+        let saved = |(saved, _): &(Checkpoint, _)| *saved == checkpoint;
+        if !self.checkpoints.saved.iter().any(saved) {
+            return Err(MerkleError::StaleCheckpoint {
+                len: checkpoint.len,
+            });
+        }
+        let len = checkpoint.len;
+        if len == self.values.len() {
+            return Ok(());
+        }
+
+        self.values.truncate(len);
+        self.forget_from(len, len);
+        let edges = self.checkpoints.saved.iter().find(|entry| saved(entry));
+        match (edges, self.levels.get_mut()) {
+            (Some((_, Some(edges))), Some(levels)) => {
+                levels.resize_with(edges.len(), Vec::new);
+                for (height, (level, edge)) in levels.iter_mut().zip(edges).enumerate() {
+                    level.truncate(len >> height);
+                    level.extend(edge.node);
+                    if let Some((padding, width)) = edge.padding {
+                        level.resize(width, padding);
+                    }
+                }
+            }
+            _ => self.rebuild_levels(len, len),
+        }
+        self.notify();
+        Ok(())
+    }
+}
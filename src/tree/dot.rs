@@ -0,0 +1,114 @@
+//! graphviz export of the levels of a tree
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+
+use super::MerkleTree;
+use crate::{
+    hash::{LeafHasher, MaybeSync},
+    hex,
+    proof::MerkleProof,
+};
+
+/// fill of the nodes on the path from the leaf to the root
+const PATH_COLOR: &str = "lightblue";
+/// fill of the siblings a verifier hashes the path with
+const SIBLING_COLOR: &str = "orange";
+
+impl<T, S> MerkleTree<T, S>
+where
+    T: Default + MaybeSync,
+    S: LeafHasher<T> + Clone,
+{
+    /// a DOT digraph with one node per hash of every level, labelled with the
+    /// first 8 hex digits of the hash, and edges from parents to children
+    /// leafs are labelled with their index, padding leafs are dashed
+    pub fn to_dot(&self) -> String {
+        self.dot(None)
+    }
+
+    /// like [`to_dot`] but the nodes a verifier of the proof touches are
+    /// filled, the path from the leaf up in light blue and the siblings in
+    /// orange. the proof has to be one of this tree
+    ///
+    /// [`to_dot`]: MerkleTree::to_dot
+    pub fn to_dot_with_proof(&self, proof: &MerkleProof<S>) -> String {
+        self.dot(Some(proof.index))
+    }
+
+    fn dot(&self, highlight: Option<usize>) -> String {
+        let levels = self.cached_levels();
+        let (path, siblings) = match highlight.filter(|&index| index < levels[0].len()) {
+            Some(index) => touched(levels, index),
+            None => (Vec::new(), Vec::new()),
+        };
+
+        let mut out = String::from("digraph merkle {\n");
+        out.push_str("    node [shape=box, fontname=monospace];\n");
+        for (level, hashes) in levels.iter().enumerate().rev() {
+            for (index, hash) in hashes.iter().enumerate() {
+                let mut label = hex::short(hash, 8);
+
+                let mut styles = Vec::new();
+                if level == 0 {
+                    let padding = index >= self.values.len();
+                    label = if padding {
+                        styles.push("dashed");
+                        alloc::format!("#{index} padding\\n{label}")
+                    } else {
+                        alloc::format!("#{index}\\n{label}")
+                    };
+                }
+                let fill = if path.contains(&(level, index)) {
+                    Some(PATH_COLOR)
+                } else if siblings.contains(&(level, index)) {
+                    Some(SIBLING_COLOR)
+                } else {
+                    None
+                };
+                if fill.is_some() {
+                    styles.push("filled");
+                }
+
+                let _ = write!(out, "    n{level}_{index} [label=\"{label}\"");
+                if !styles.is_empty() {
+                    let _ = write!(out, ", style=\"{}\"", styles.join(","));
+                }
+                if let Some(fill) = fill {
+                    let _ = write!(out, ", fillcolor={fill}");
+                }
+                out.push_str("];\n");
+            }
+        }
+
+        for (level, hashes) in levels.iter().enumerate().skip(1) {
+            let children = levels[level - 1].len();
+            for index in 0..hashes.len() {
+                for child in [2 * index, 2 * index + 1]
+                    .into_iter()
+                    .filter(|&c| c < children)
+                {
+                    let _ = writeln!(out, "    n{level}_{index} -> n{}_{child};", level - 1);
+                }
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// a node of the tree as its level, leafs are level 0, and its index in it
+type Node = (usize, usize);
+
+/// the nodes from the leaf at `index` up to the root and their siblings
+fn touched<D>(levels: &[Vec<D>], mut index: usize) -> (Vec<Node>, Vec<Node>) {
+    let (mut path, mut siblings) = (Vec::new(), Vec::new());
+    for (level, hashes) in levels.iter().enumerate() {
+        path.push((level, index));
+        if level + 1 < levels.len() && index ^ 1 < hashes.len() {
+            siblings.push((level, index ^ 1));
+        }
+        index /= 2;
+    }
+    (path, siblings)
+}
@@ -0,0 +1,134 @@
+//! joining trees over consecutive runs of values, e.g. shards hashed on
+//! different machines
+//!
+//! when the left tree is complete, i.e. holds a power of two values, and the
+//! right one is no wider, every node of both trees is a node of the joined
+//! tree and only the path above the right root is hashed. otherwise the leaf
+//! hashes are reused and the levels above them are built again.
+
+use alloc::{vec, vec::Vec};
+
+use super::{build_padded_levels, parent, MerkleTree, PaddingStrategy};
+use crate::{
+    error::MerkleError,
+    hash::{EmptyHashes, LeafHasher, MaybeSync},
+    OnceLock,
+};
+
+impl<T: Default + MaybeSync, S: LeafHasher<T> + Clone> MerkleTree<T, S> {
+    /// the tree over the values of `left` followed by the values of `right`
+    /// both trees need the same padding, the hasher and the
+    /// [observers](MerkleTree::on_root_change) of `left` are kept and told
+    /// about the joined root
+    pub fn merge(left: Self, right: Self) -> Result<Self, MerkleError> {
+        if left.padding != right.padding {
+            return Err(MerkleError::PaddingMismatch {
+                padding: left.padding,
+                other: right.padding,
+            });
+        }
+        if right.values.is_empty() {
+            return Ok(left);
+        }
+        if left.values.is_empty() {
+            let mut merged = Self {
+                observers: left.observers,
+                ..right
+            };
+            merged.notify();
+            return Ok(merged);
+        }
+
+        let (len, other) = (left.values.len(), right.values.len());
+        let levels = if len.is_power_of_two() && other <= len {
+            left.join_complete(&right)
+        } else {
+            let mut leafs = left.cached_levels()[0][..len].to_vec();
+            leafs.extend_from_slice(&right.cached_levels()[0][..other]);
+            let empty = || left.hasher.hash_padding();
+            build_padded_levels(&left.hasher, left.padding, leafs, empty)
+        };
+
+        let mut values = left.values;
+        values.extend(right.values);
+        let mut merged = Self {
+            values,
+            hasher: left.hasher,
+            padding: left.padding,
+            levels: OnceLock::from(levels),
+            empties: left.empties,
+            observers: left.observers,
+            checkpoints: left.checkpoints,
+            stale: left.stale,
+        };
+        merged.notify();
+        Ok(merged)
+    }
+
+    /// the tree over the values of all `trees` in order
+    /// neighbours are merged pairwise, so shards of the same power of two size
+    /// are joined without hashing anything below their roots
+    pub fn merge_all(trees: Vec<Self>) -> Result<Self, MerkleError>
+    where
+        S: Default,
+    {
+        let mut trees = trees;
+        while trees.len() > 1 {
+            let mut merged = Vec::with_capacity(trees.len().div_ceil(2));
+            let mut pairs = trees.into_iter();
+            while let Some(left) = pairs.next() {
+                merged.push(match pairs.next() {
+                    Some(right) => Self::merge(left, right)?,
+                    None => left,
+                });
+            }
+            trees = merged;
+        }
+        Ok(trees
+            .pop()
+            .unwrap_or_else(|| Self::with_hasher(Vec::new(), S::default())))
+    }
+
+    /// the levels of the joined tree if `self` is complete and `right` no
+    /// wider, the right tree is raised to the height of the left one and both
+    /// get a common root
+    fn join_complete(&self, right: &Self) -> Vec<Vec<S::Digest>> {
+        let ours = self.cached_levels();
+        let theirs = right.cached_levels();
+        let height = ours.len() - 1;
+        // the right tree is padded up to the width of the left one
+        let padded = right.values.len() < self.values.len();
+        let empties = (padded && self.padding == PaddingStrategy::DefaultValue).then(|| {
+            let empty = self.hasher.hash_padding();
+            EmptyHashes::new(&self.hasher, empty, height)
+        });
+
+        let mut levels: Vec<Vec<S::Digest>> = Vec::with_capacity(height + 2);
+        let mut raised: Vec<S::Digest> = Vec::new();
+        for (level, nodes) in ours.iter().enumerate() {
+            raised = match theirs.get(level) {
+                Some(hashes) => hashes.clone(),
+                // above the right root, which is the first node of its level
+                None => vec![parent(
+                    &self.hasher,
+                    self.padding,
+                    &raised[0],
+                    raised.get(1),
+                )],
+            };
+            if let Some(empties) = &empties {
+                raised.resize(nodes.len(), empties[level]);
+            }
+
+            let mut joined = Vec::with_capacity(nodes.len() + raised.len());
+            joined.extend_from_slice(nodes);
+            joined.extend_from_slice(&raised);
+            levels.push(joined);
+        }
+
+        let top = &levels[height];
+        let root = self.hasher.hash_siblings(&top[0], &top[1]);
+        levels.push(vec![root]);
+        levels
+    }
+}
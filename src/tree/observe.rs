@@ -0,0 +1,56 @@
+//! callbacks told about every new root, e.g. to publish it
+//!
+//! observers run once per mutation, after the values and cached levels are
+//! consistent again. batch operations like `add_all` call them once at the
+//! end. a callback can't reach the tree it observes, it only gets the new
+//! root and length.
+
+use alloc::{boxed::Box, vec::Vec};
+
+use super::MerkleTree;
+use crate::{
+    hash::{LeafHasher, MaybeSync},
+    root::Root,
+};
+
+type Observer<D> = Box<dyn FnMut(Root<D>, usize) + Send + Sync>;
+
+/// the root observers of a tree, clones of a tree start without any
+pub(crate) struct Observers<D>(Vec<Observer<D>>);
+
+impl<D> Default for Observers<D> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<D> Clone for Observers<D> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl<T: Default + MaybeSync, S: LeafHasher<T> + Clone> MerkleTree<T, S> {
+    /// call `callback` with the new root and length after every change of the
+    /// values, observers run in the order they were added
+    ///
+    /// a clone of the tree is not observed
+    pub fn on_root_change(
+        &mut self,
+        callback: impl FnMut(Root<S::Digest>, usize) + Send + Sync + 'static,
+    ) {
+        self.observers.0.push(Box::new(callback));
+    }
+
+    /// tell the observers about the current root, it is only computed if
+    /// there are any
+    pub(super) fn notify(&mut self) {
+        if self.observers.0.is_empty() {
+            return;
+        }
+        let (root, len) = (self.root(), self.values.len());
+        for observer in &mut self.observers.0 {
+            observer(root, len);
+        }
+    }
+}
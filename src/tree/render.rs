@@ -0,0 +1,83 @@
+//! text rendering of the levels of a tree, for debugging small trees
+
+use alloc::{format, string::String};
+use core::fmt::Write;
+
+use super::MerkleTree;
+use crate::{
+    hash::{LeafHasher, MaybeSync},
+    hex,
+};
+
+/// levels [`MerkleTree::render`] prints at most, 16 nodes on the lowest line
+const RENDER_LEVELS: usize = 5;
+
+/// hex digits of a hash shown per node
+const DIGITS: usize = 6;
+/// columns per node of the lowest printed level, room for `[` digits `]`
+/// and a space
+const CELL: usize = DIGITS + 3;
+
+impl<T, S> MerkleTree<T, S>
+where
+    T: Default + MaybeSync,
+    S: LeafHasher<T> + Clone,
+{
+    /// the top 5 levels of the tree, see [`render_top`]
+    ///
+    /// [`render_top`]: MerkleTree::render_top
+    pub fn render(&self) -> String {
+        self.render_top(RENDER_LEVELS)
+    }
+
+    /// one line per level, root first, with the first 6 hex digits of every
+    /// hash centered above the nodes below it. padding leafs are shown in
+    /// brackets. when the tree has more than `levels` levels only the top
+    /// ones are printed, followed by a line summing up the rest
+    /// ```text
+    ///                                 b76059
+    ///               14ede5                              526eb1
+    ///      e5a01f            bffe0b            0a1a54            2dba5d
+    ///  ca9781   3e23e8   2e7d2c   18ac3e   3f79bb  [e3b0c4] [e3b0c4] [e3b0c4]
+    /// ```
+    pub fn render_top(&self, levels: usize) -> String {
+        let all = self.cached_levels();
+        let levels = levels.clamp(1, all.len());
+        let bottom = all.len() - levels;
+        let nodes = all[bottom].len();
+
+        let mut out = String::new();
+        for level in (bottom..all.len()).rev() {
+            let mut line = String::new();
+            // a node covers the nodes of the bottom line below it, the last
+            // one of a level may cover fewer
+            let span = 1 << (level - bottom);
+            for (index, hash) in all[level].iter().enumerate() {
+                let first = index * span;
+                let covered = span.min(nodes - first);
+                let label = if level == 0 && index >= self.values.len() {
+                    format!("[{}]", hex::short(hash, DIGITS))
+                } else {
+                    hex::short(hash, DIGITS)
+                };
+                let column = first * CELL + (covered * CELL - 1 - label.len()) / 2;
+                while line.len() < column {
+                    line.push(' ');
+                }
+                line.push_str(&label);
+            }
+            out.push_str(&line);
+            out.push('\n');
+        }
+
+        if bottom > 0 {
+            let padding = all[0].len() - self.values.len();
+            let _ = write!(out, "… {bottom} more levels, {} leafs", self.values.len());
+            if padding > 0 {
+                let _ = write!(out, " and {padding} padding");
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
@@ -0,0 +1,84 @@
+//! stored values changed in place
+//!
+//! a [`ValueMut`] borrows the tree while a value is changed through it, once
+//! it is dropped the leaf of the value and its path are hashed again like for
+//! [`update`](MerkleTree::update), so no stale root can be read afterwards.
+
+use core::{
+    fmt,
+    ops::{Deref, DerefMut, Index},
+};
+
+use super::MerkleTree;
+use crate::hash::{LeafHasher, MaybeSync, MerkleHasher};
+
+/// a stored value borrowed mutably from a [`MerkleTree`], see
+/// [`get_mut`](MerkleTree::get_mut)
+/// the tree is rehashed and its observers are told about the new root when
+/// the guard is dropped, unless the value was only read
+pub struct ValueMut<'a, T: Default + MaybeSync, S: LeafHasher<T> + Clone> {
+    tree: &'a mut MerkleTree<T, S>,
+    index: usize,
+    changed: bool,
+}
+
+impl<T: Default + MaybeSync, S: LeafHasher<T> + Clone> MerkleTree<T, S> {
+    /// borrow the value at `index` to change it in place, indices at or
+    /// beyond [`len`](MerkleTree::len) give `None`
+    pub fn get_mut(&mut self, index: usize) -> Option<ValueMut<'_, T, S>> {
+        (index < self.values.len()).then_some(ValueMut {
+            tree: self,
+            index,
+            changed: false,
+        })
+    }
+}
+
+impl<T: Default + MaybeSync, S: LeafHasher<T> + Clone> ValueMut<'_, T, S> {
+    /// the index of the value in the tree
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<T: Default + MaybeSync, S: LeafHasher<T> + Clone> Deref for ValueMut<'_, T, S> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.tree.values[self.index]
+    }
+}
+
+impl<T: Default + MaybeSync, S: LeafHasher<T> + Clone> DerefMut for ValueMut<'_, T, S> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.changed = true;
+        &mut self.tree.values[self.index]
+    }
+}
+
+impl<T: Default + MaybeSync, S: LeafHasher<T> + Clone> Drop for ValueMut<'_, T, S> {
+    fn drop(&mut self) {
+        if self.changed {
+            self.tree.rehash_value(self.index);
+            self.tree.notify();
+        }
+    }
+}
+
+impl<T: Default + MaybeSync + fmt::Debug, S: LeafHasher<T> + Clone> fmt::Debug
+    for ValueMut<'_, T, S>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// the stored value at `index`, panics at or beyond [`len`](MerkleTree::len)
+/// like indexing a slice, padding can't be indexed
+impl<T, S: MerkleHasher> Index<usize> for MerkleTree<T, S> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.values[index]
+    }
+}
@@ -0,0 +1,298 @@
+//! verification of proofs from hashes alone
+//!
+//! nothing here needs the tree or the type of its values, light clients that
+//! only hold a root can verify paths handed to them
+
+use alloc::vec::Vec;
+
+use crate::{
+    hash::{Digest, MerkleHasher, SortedPairs},
+    proof::{
+        ConsistencyProof, Direction, MerkleProof, PackedPath, RangeProof, Step, MAX_PACKED_LEN,
+    },
+    root::{LeafHash, Root},
+    tree::PaddingStrategy,
+};
+
+#[cfg(feature = "std")]
+pub use crate::proof::streaming::verify_streaming;
+
+/// check that folding `path` into `leaf` with `hasher` yields `root`
+pub fn verify_path<S: MerkleHasher>(
+    hasher: &S,
+    leaf: &LeafHash<S::Digest>,
+    path: &[Step<S::Digest>],
+    root: &Root<S::Digest>,
+) -> bool {
+    fold_path(hasher, &leaf.0, path).ct_eq(&root.0)
+}
+
+/// the root that folding `path` into `leaf` with `hasher` leads to
+pub fn fold_path<S: MerkleHasher>(
+    hasher: &S,
+    leaf: &S::Digest,
+    path: &[Step<S::Digest>],
+) -> S::Digest {
+    let mut acc = *leaf;
+
+    for step in path {
+        acc = match step.direction {
+            Direction::Left => hasher.hash_siblings(&step.value, &acc),
+            Direction::Right => hasher.hash_siblings(&acc, &step.value),
+        }
+    }
+
+    acc
+}
+
+/// like [`verify_path`] for a path with packed directions
+pub fn verify_packed<S: MerkleHasher>(
+    hasher: &S,
+    leaf: &LeafHash<S::Digest>,
+    path: &PackedPath<S::Digest>,
+    root: &Root<S::Digest>,
+) -> bool {
+    path.len() <= MAX_PACKED_LEN && fold_packed(hasher, &leaf.0, path).ct_eq(&root.0)
+}
+
+/// like [`fold_path`] for a path with packed directions, the direction bits
+/// are read straight from the word
+pub fn fold_packed<S: MerkleHasher>(
+    hasher: &S,
+    leaf: &S::Digest,
+    path: &PackedPath<S::Digest>,
+) -> S::Digest {
+    let mut acc = *leaf;
+    let mut directions = path.directions;
+
+    for sibling in &path.siblings {
+        acc = if directions & 1 == 1 {
+            hasher.hash_siblings(&acc, sibling)
+        } else {
+            hasher.hash_siblings(sibling, &acc)
+        };
+        directions >>= 1;
+    }
+
+    acc
+}
+
+/// check a proof of a tree hashed with [`SortedPairs`], which is nothing but
+/// the sibling hashes from the leaf up
+/// this is what openzeppelin's `MerkleProof.verify` does, the position of the
+/// leaf is not checked
+pub fn verify_sorted_pairs<S: MerkleHasher>(
+    hasher: &SortedPairs<S>,
+    leaf: &LeafHash<S::Digest>,
+    siblings: &[S::Digest],
+    root: &Root<S::Digest>,
+) -> bool {
+    let computed = siblings
+        .iter()
+        .fold(leaf.0, |acc, sibling| hasher.hash_siblings(&acc, sibling));
+    computed.ct_eq(&root.0)
+}
+
+/// check that the directions of `path` are the ones of the leaf at `index`
+///
+/// a node with an odd index has its sibling on the left, one with an even
+/// index on the right. a level may be missing from the path if the node was
+/// promoted to the next level without a sibling, which only happens to the
+/// last node of a level, so the path can't have a right sibling after that.
+pub fn path_matches_index<D>(index: usize, path: &[Step<D>]) -> bool {
+    let mut index = index;
+    let mut promoted = false;
+
+    for step in path {
+        // skip the levels on which the node had no sibling
+        while index & 1 == 0 && (promoted || step.direction == Direction::Left) {
+            if index == 0 {
+                return false;
+            }
+            promoted = true;
+            index >>= 1;
+        }
+
+        let expected = if index & 1 == 1 {
+            Direction::Left
+        } else {
+            Direction::Right
+        };
+        if step.direction != expected {
+            return false;
+        }
+        index >>= 1;
+    }
+
+    index == 0
+}
+
+/// number of steps in the path of the leaf at `index` of a tree with `width`
+/// leafs, promoted levels have no step
+pub fn path_len(index: usize, width: usize, padding: PaddingStrategy) -> usize {
+    directions(index, width, padding).count()
+}
+
+/// directions of the steps in the path of the leaf at `index` of a tree with
+/// `width` leafs, from the leaf up
+pub fn directions(
+    index: usize,
+    width: usize,
+    padding: PaddingStrategy,
+) -> impl Iterator<Item = Direction> {
+    path_levels(index, width, padding).map(|(_, direction)| direction)
+}
+
+/// like [`directions`] together with the height of the level of every step
+pub(crate) fn path_levels(
+    index: usize,
+    width: usize,
+    padding: PaddingStrategy,
+) -> impl Iterator<Item = (usize, Direction)> {
+    let (mut index, mut width, mut height) = (index, width, 0);
+
+    core::iter::from_fn(move || {
+        while width > 1 {
+            let promoted =
+                padding == PaddingStrategy::PromoteOdd && index == width - 1 && index % 2 == 0;
+            let direction = if index % 2 == 1 {
+                Direction::Left
+            } else {
+                Direction::Right
+            };
+            index /= 2;
+            width = width.div_ceil(2);
+            height += 1;
+
+            if !promoted {
+                return Some((height - 1, direction));
+            }
+        }
+        None
+    })
+}
+
+/// check that `subtree_root` is a node of the tree with `root`, with a proof
+/// from [`get_subtree_proof`](crate::MerkleTree::get_subtree_proof)
+pub fn verify_subtree<S: MerkleHasher>(
+    root: &Root<S::Digest>,
+    subtree_root: &Root<S::Digest>,
+    proof: &MerkleProof<S>,
+) -> bool {
+    proof.leaf.0.ct_eq(&subtree_root.0) && proof.verify_against(root)
+}
+
+/// check that the tree of `new_len` leafs with `new_root` extends the tree of
+/// `old_len` leafs with `old_root` by appending, following RFC 9162 section
+/// 2.1.4.2
+pub fn verify_consistency<S: MerkleHasher>(
+    old_root: &Root<S::Digest>,
+    old_len: usize,
+    new_root: &Root<S::Digest>,
+    new_len: usize,
+    proof: &ConsistencyProof<S>,
+) -> bool {
+    if old_len == 0 || old_len > new_len {
+        return false;
+    }
+    if old_len == new_len {
+        return proof.path.is_empty() && old_root.ct_eq(new_root);
+    }
+
+    // the old root is a node of the new tree and not part of the proof
+    let mut path = proof.path.iter();
+    let first = if old_len.is_power_of_two() {
+        &old_root.0
+    } else {
+        match path.next() {
+            Some(first) => first,
+            None => return false,
+        }
+    };
+
+    let (mut old_index, mut new_index) = (old_len - 1, new_len - 1);
+    while old_index & 1 == 1 {
+        old_index >>= 1;
+        new_index >>= 1;
+    }
+
+    let (mut old, mut new) = (*first, *first);
+    for sibling in path {
+        if new_index == 0 {
+            return false;
+        }
+        if old_index & 1 == 1 || old_index == new_index {
+            old = proof.hasher.hash_siblings(sibling, &old);
+            new = proof.hasher.hash_siblings(sibling, &new);
+            while old_index & 1 == 0 && old_index != 0 {
+                old_index >>= 1;
+                new_index >>= 1;
+            }
+        } else {
+            new = proof.hasher.hash_siblings(&new, sibling);
+        }
+        old_index >>= 1;
+        new_index >>= 1;
+    }
+
+    old.ct_eq(&old_root.0) & new.ct_eq(&new_root.0) && new_index == 0
+}
+
+/// check that `leafs` are the leafs of the tree with `root` from index `start`
+/// on, the levels are rebuilt from the leafs and the hashes of the proof
+pub fn verify_range<S: MerkleHasher>(
+    root: &Root<S::Digest>,
+    start: usize,
+    leafs: &[S::Digest],
+    proof: &RangeProof<S>,
+) -> bool {
+    match start.checked_add(leafs.len()) {
+        Some(end) if !leafs.is_empty() && end <= proof.width => {}
+        _ => return false,
+    }
+
+    let mut decommitments = proof.decommitments.iter();
+    let mut nodes = leafs.to_vec();
+    let (mut start, mut width) = (start, proof.width);
+    while width > 1 {
+        let mut level = Vec::with_capacity(nodes.len() + 2);
+        if start % 2 == 1 {
+            match decommitments.next() {
+                Some(left) => level.push(*left),
+                None => return false,
+            }
+            start -= 1;
+        }
+        level.append(&mut nodes);
+        let end = start + level.len();
+        if end % 2 == 1 {
+            if end < width {
+                match decommitments.next() {
+                    Some(right) => level.push(*right),
+                    None => return false,
+                }
+            } else {
+                // the last node of an odd level
+                match proof.padding {
+                    PaddingStrategy::PromoteOdd => {}
+                    PaddingStrategy::DuplicateLast => level.push(level[level.len() - 1]),
+                    // levels padded with default values are never odd
+                    PaddingStrategy::DefaultValue => return false,
+                }
+            }
+        }
+
+        nodes = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => proof.hasher.hash_siblings(left, right),
+                [promoted] => *promoted,
+                _ => unreachable!("chunks of two"),
+            })
+            .collect();
+        start /= 2;
+        width = width.div_ceil(2);
+    }
+
+    decommitments.next().is_none() && nodes.len() == 1 && nodes[0].ct_eq(&root.0)
+}
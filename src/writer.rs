@@ -0,0 +1,132 @@
+//! the root of bytes as they are written, without buffering them
+//!
+//! a [`MerkleWriter`] passes every byte on to the writer it wraps and hashes
+//! the bytes in chunks like [`MerkleTree::from_reader`] reads them, so both
+//! get the same root for the same bytes. only the chunk being filled and the
+//! frontier of an [`IncrementalMerkleTree`] are kept.
+//!
+//! [`MerkleTree::from_reader`]: crate::MerkleTree::from_reader
+
+use std::io::{self, Write};
+
+use alloc::vec::Vec;
+
+use crate::{
+    chunked::{check_chunk_size, ChunkedMerkleTree},
+    hash::{DefaultBuildHasher, LeafHasher, MerkleHasher},
+    incremental::IncrementalMerkleTree,
+    root::Root,
+};
+
+/// the inner writer, the root and the tree if the leafs were kept, see
+/// [`MerkleWriter::finish`]
+pub type Finished<W, S> = (
+    W,
+    Root<<S as MerkleHasher>::Digest>,
+    Option<ChunkedMerkleTree<S>>,
+);
+
+/// a writer that hashes what it writes into a merkle tree over chunks
+pub struct MerkleWriter<W, S: MerkleHasher = DefaultBuildHasher> {
+    inner: W,
+    chunk_size: usize,
+    /// bytes of the last chunk, it is hashed once it is full
+    chunk: Vec<u8>,
+    frontier: IncrementalMerkleTree<Vec<u8>, S>,
+    /// hashes of the full chunks, if they are kept
+    leafs: Option<Vec<S::Digest>>,
+    /// bytes written so far
+    size: u64,
+}
+
+impl<W: Write> MerkleWriter<W> {
+    /// wrap `inner` and hash chunks of `chunk_size` bytes with the default
+    /// hasher
+    pub fn new(inner: W, chunk_size: usize) -> io::Result<Self> {
+        Self::with_hasher(inner, chunk_size, DefaultBuildHasher::default())
+    }
+}
+
+impl<W: Write, S: MerkleHasher + LeafHasher<[u8]>> MerkleWriter<W, S> {
+    /// like [`new`](MerkleWriter::new) with the given hasher
+    /// a chunk size of zero is an [`InvalidInput`](io::ErrorKind::InvalidInput)
+    /// error
+    pub fn with_hasher(inner: W, chunk_size: usize, hasher: S) -> io::Result<Self> {
+        check_chunk_size(chunk_size)?;
+        Ok(Self {
+            inner,
+            chunk_size,
+            chunk: Vec::with_capacity(chunk_size),
+            frontier: IncrementalMerkleTree::with_hasher(hasher),
+            leafs: None,
+            size: 0,
+        })
+    }
+
+    /// also keep the hash of every chunk, so that [`finish`] returns the tree
+    /// over them and proofs can be made. this takes memory for every chunk
+    ///
+    /// [`finish`]: MerkleWriter::finish
+    pub fn keep_leafs(mut self) -> Self {
+        self.leafs = Some(Vec::new());
+        self
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// bytes written so far
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// hash the last chunk and flush the inner writer, returns it with the
+    /// root over all chunks and the tree over them if the leafs were kept
+    pub fn finish(mut self) -> io::Result<Finished<W, S>>
+    where
+        S: Clone,
+    {
+        self.inner.flush()?;
+        if !self.chunk.is_empty() {
+            self.hash_chunk();
+        }
+        let root = self.frontier.root();
+        let hasher = self.frontier.hasher().clone();
+        let tree = self
+            .leafs
+            .map(|leafs| ChunkedMerkleTree::new(leafs, hasher, self.chunk_size, self.size));
+        Ok((self.inner, root, tree))
+    }
+
+    fn hash_chunk(&mut self) {
+        let leaf = self.frontier.hasher().hash_leaf(&self.chunk[..]);
+        self.frontier.add_hash(leaf);
+        if let Some(leafs) = &mut self.leafs {
+            leafs.push(leaf);
+        }
+        self.chunk.clear();
+    }
+}
+
+impl<W: Write, S: MerkleHasher + LeafHasher<[u8]>> Write for MerkleWriter<W, S> {
+    /// only the bytes the inner writer accepted are hashed
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        let mut rest = &buf[..written];
+        while !rest.is_empty() {
+            let take = (self.chunk_size - self.chunk.len()).min(rest.len());
+            self.chunk.extend_from_slice(&rest[..take]);
+            rest = &rest[take..];
+            if self.chunk.len() == self.chunk_size {
+                self.hash_chunk();
+            }
+        }
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,120 @@
+use std::io::ErrorKind;
+
+use merkle::{hash::DefaultBuildHasher, verify_audit, AuditReport, MerkleTree, PaddingStrategy};
+
+const PADDINGS: [PaddingStrategy; 3] = [
+    PaddingStrategy::DefaultValue,
+    PaddingStrategy::DuplicateLast,
+    PaddingStrategy::PromoteOdd,
+];
+
+/// size of the header of an audit file of a tree with u64 digests and fewer
+/// than 128 leafs
+const HEADER: usize = 4 + 1 + 1 + 1 + 1 + 3 * 8 + 8;
+const RECORD: usize = 8 + 8;
+
+fn export(len: u64, padding: PaddingStrategy) -> eyre::Result<Vec<u8>> {
+    let tree = MerkleTree::from((0..len).collect::<Vec<_>>()).with_padding(padding);
+    let mut bytes = Vec::new();
+    tree.export_audit(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[test]
+fn exported_trees_verify() -> eyre::Result<()> {
+    for padding in PADDINGS {
+        for len in [0, 1, 2, 5, 8, 13] {
+            let tree = MerkleTree::from((0..len).collect::<Vec<_>>()).with_padding(padding);
+            let bytes = export(len, padding)?;
+            assert_eq!(bytes.len(), HEADER + len as usize * RECORD);
+
+            let report = verify_audit::<DefaultBuildHasher>(&bytes[..])?;
+            assert!(report.is_valid());
+            assert_eq!(
+                report,
+                AuditReport {
+                    len: len as usize,
+                    padding,
+                    root: tree.root(),
+                    computed: tree.root(),
+                    first_mismatch: None,
+                }
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn hashed_trees_export_the_same_file() -> eyre::Result<()> {
+    for padding in PADDINGS {
+        let tree = MerkleTree::from((0..11u64).collect::<Vec<_>>()).with_padding(padding);
+        let expected = export(11, padding)?;
+
+        let mut bytes = Vec::new();
+        tree.finalize().export_audit(&mut bytes)?;
+        assert_eq!(bytes, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn damaged_leaf_is_pinpointed() -> eyre::Result<()> {
+    let tree = MerkleTree::from((0..10u64).collect::<Vec<_>>());
+    let mut bytes = export(10, PaddingStrategy::DefaultValue)?;
+    for index in [4, 7] {
+        bytes[HEADER + index * RECORD + 3] ^= 0x10;
+    }
+
+    let report = verify_audit::<DefaultBuildHasher>(&bytes[..])?;
+    assert!(!report.is_valid());
+    assert_eq!(report.first_mismatch, Some(4));
+    assert_eq!(report.root, tree.root());
+    assert_ne!(report.computed, tree.root());
+    Ok(())
+}
+
+#[test]
+fn damaged_root_is_rejected() -> eyre::Result<()> {
+    let mut bytes = export(6, PaddingStrategy::DefaultValue)?;
+    bytes[HEADER - 9] ^= 1;
+
+    let err = verify_audit::<DefaultBuildHasher>(&bytes[..]).err();
+    assert_eq!(err.map(|err| err.kind()), Some(ErrorKind::InvalidData));
+    Ok(())
+}
+
+#[test]
+fn malformed_files_are_rejected() -> eyre::Result<()> {
+    let bytes = export(6, PaddingStrategy::DefaultValue)?;
+
+    let mut other_version = bytes.clone();
+    other_version[4] = 2;
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    for bytes in [&other_version[..], &trailing[..], b"MRKL\x01\x00"] {
+        let err = verify_audit::<DefaultBuildHasher>(bytes).err();
+        assert_eq!(err.map(|err| err.kind()), Some(ErrorKind::InvalidData));
+    }
+
+    let err = verify_audit::<DefaultBuildHasher>(&bytes[..bytes.len() - 1]).err();
+    assert_eq!(err.map(|err| err.kind()), Some(ErrorKind::UnexpectedEof));
+    Ok(())
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn other_hash_backends_are_rejected() -> eyre::Result<()> {
+    use merkle::hash::Sha256;
+
+    let tree = MerkleTree::with_hasher(vec![&b"a"[..], b"b", b"c"], Sha256);
+    let mut bytes = Vec::new();
+    tree.export_audit(&mut bytes)?;
+    let report = verify_audit::<Sha256>(&bytes[..])?;
+    assert!(report.is_valid());
+    assert_eq!(report.root, tree.root());
+
+    let err = verify_audit::<DefaultBuildHasher>(&bytes[..]).err();
+    assert_eq!(err.map(|err| err.kind()), Some(ErrorKind::InvalidData));
+    Ok(())
+}
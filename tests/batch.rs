@@ -0,0 +1,81 @@
+use merkle::{MerkleError, MerkleTree, PaddingStrategy};
+
+/// a fixed pseudo random sequence, the same on every run
+fn random(seed: u64) -> impl Iterator<Item = u64> {
+    let mut state = seed;
+    std::iter::repeat_with(move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        state >> 33
+    })
+}
+
+#[test]
+fn batches_match_sequential_updates() -> eyre::Result<()> {
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        let values: Vec<u64> = (0..1000).collect();
+        let mut batched = MerkleTree::from(values.clone()).with_padding(padding);
+        let mut sequential = batched.clone();
+        batched.root();
+        sequential.root();
+
+        let mut random = random(3);
+        for round in 0..20 {
+            let changes: Vec<(usize, u64)> = (0..round * 10)
+                .map(|_| {
+                    (
+                        (random.next().unwrap() % 1000) as usize,
+                        random.next().unwrap(),
+                    )
+                })
+                .collect();
+            batched.update_all(&changes)?;
+            for &(index, value) in &changes {
+                sequential.update(index, value)?;
+            }
+            assert_eq!(batched.root(), sequential.root(), "{padding:?}");
+        }
+
+        let fresh = MerkleTree::from(batched.values().to_vec()).with_padding(padding);
+        assert!(batched.levels().eq(fresh.levels()), "{padding:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn the_last_change_of_an_index_wins() -> eyre::Result<()> {
+    let mut tree = MerkleTree::from((0..10).collect::<Vec<u32>>());
+    tree.root();
+    tree.update_all(&[(3, 30), (4, 40), (3, 31)])?;
+    assert_eq!(tree.values()[3..5], [31, 40]);
+
+    let fresh = MerkleTree::from(vec![0, 1, 2, 31, 40, 5, 6, 7, 8, 9]);
+    assert_eq!(tree.root(), fresh.root());
+
+    // the trees are the same whether the levels were cached or not
+    let mut uncached = MerkleTree::from((0..10).collect::<Vec<u32>>());
+    uncached.update_all(&[(3, 30), (4, 40), (3, 31)])?;
+    assert_eq!(uncached.root(), fresh.root());
+    Ok(())
+}
+
+#[test]
+fn out_of_bounds_batches_change_nothing() -> eyre::Result<()> {
+    let mut tree = MerkleTree::from((0..10).collect::<Vec<u32>>());
+    let root = tree.root();
+    assert_eq!(
+        tree.update_all(&[(0, 7), (10, 7)]),
+        Err(MerkleError::IndexOutOfBounds { index: 10, len: 10 })
+    );
+    assert_eq!(tree.values()[0], 0);
+    assert_eq!(tree.root(), root);
+
+    tree.update_all(&[])?;
+    assert_eq!(tree.root(), root);
+    Ok(())
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "blake3")]
+
+mod common;
+
+use merkle::{
+    hash::{blake3, Blake3},
+    LeafHash, MerkleTree, Root,
+};
+
+use common::hex;
+
+/// the input of the official test vectors, `i % 251` for every byte
+fn vector_input(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+/// xorshift bytes, the tests don't need a real random source
+fn random_bytes(len: usize, mut seed: u64) -> Vec<u8> {
+    (0..len)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed as u8
+        })
+        .collect()
+}
+
+#[test]
+fn known_digests() {
+    let vectors = [
+        (
+            0,
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262",
+        ),
+        (
+            1,
+            "2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213",
+        ),
+        (
+            1023,
+            "10108970eeda3eb932baac1428c7a2163b0e924c9a9e25b35bba72b28f70bd11",
+        ),
+        (
+            1024,
+            "42214739f095a406f3fc83deb889744ac00df831c10daa55189b5d121c855af7",
+        ),
+        (
+            1025,
+            "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444",
+        ),
+        (
+            2048,
+            "e776b6028c7cd22a4d0ba182a8bf62205d2ef576467e838ed6f2529b85fba24a",
+        ),
+        (
+            3073,
+            "7124b49501012f81cc7f11ca069ec9226cecb8a2c850cfe644e327d22d3e1cd3",
+        ),
+        (
+            8193,
+            "bab6c09cb8ce8cf459261398d2e7aef35700bf488116ceb94a36d0f5f1b7bc3b",
+        ),
+        (
+            102400,
+            "bc3e3d41a1146b069abffad3c0d44860cf664390afce4d9661f7902e7943e085",
+        ),
+        // long enough to be split across threads with the parallel feature
+        (
+            (2 << 20) + 1,
+            "52dc212cb4cc61cb94d25bd7b1d47b256e4c3a6d68956df50c235c37a2aeacd7",
+        ),
+    ];
+
+    for (len, expected) in vectors {
+        assert_eq!(
+            hex(&blake3::digest(&vector_input(len))),
+            expected,
+            "{len} bytes"
+        );
+    }
+    assert_eq!(
+        hex(&blake3::digest(b"abc")),
+        "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+    );
+}
+
+#[test]
+fn single_leaf_root_is_the_digest() {
+    let leaf = random_bytes(5000, 1);
+    let tree = MerkleTree::with_hasher(vec![leaf.as_slice()], Blake3);
+    assert_eq!(tree.root(), Root(blake3::digest(&leaf)));
+}
+
+#[test]
+fn siblings_hash_their_concatenation() {
+    let tree = MerkleTree::with_hasher(vec![&b"a"[..], b"b"], Blake3);
+    let concatenated = [blake3::digest(b"a"), blake3::digest(b"b")].concat();
+    assert_eq!(tree.root(), Root(blake3::digest(&concatenated)));
+}
+
+#[test]
+fn large_leafs() -> eyre::Result<()> {
+    let buffers: Vec<_> = (0..5)
+        .map(|i| random_bytes((1 << 20) * (i + 1) + i * 7, i as u64 + 1))
+        .collect();
+    let tree = MerkleTree::with_hasher(buffers.iter().map(Vec::as_slice).collect(), Blake3);
+
+    for (i, buffer) in buffers.iter().enumerate() {
+        let proof = tree.get_proof(i)?;
+        assert_eq!(proof.leaf, LeafHash(blake3::digest(buffer)));
+        assert!(proof.verify_against(&tree.root()));
+    }
+
+    Ok(())
+}
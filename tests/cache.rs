@@ -0,0 +1,287 @@
+mod common;
+
+use merkle::{MerkleTree, PaddingStrategy};
+
+use common::Counting;
+
+#[test]
+fn cached_proofs_match_rebuild() -> eyre::Result<()> {
+    let mut values: Vec<u32> = (0..1000).collect();
+    let mut tree = MerkleTree::from(values.clone());
+    tree.root();
+
+    for value in 1000..1030 {
+        tree.add(value);
+        values.push(value);
+    }
+    tree.root();
+
+    for (index, value) in [(3, 7), (513, 0), (1029, 1)] {
+        tree.update(index, value)?;
+        values[index] = value;
+    }
+
+    let fresh = MerkleTree::from(values);
+    assert_eq!(tree.root(), fresh.root());
+    for index in [0, 3, 512, 513, 1000, 1029] {
+        assert_eq!(
+            tree.get_proof(index)?.to_bytes(),
+            fresh.get_proof(index)?.to_bytes()
+        );
+    }
+    assert_eq!(
+        tree.get_padding_proof(2047)?.to_bytes(),
+        fresh.get_padding_proof(2047)?.to_bytes()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn proofs_reuse_cached_levels() -> eyre::Result<()> {
+    for size in [1 << 10, 1 << 14] {
+        let values: Vec<u32> = (0..size).collect();
+        let hasher = Counting::default();
+        let tree = MerkleTree::with_hasher(values, hasher.clone());
+
+        // every leaf and every inner node is hashed exactly once
+        tree.root();
+        let built = hasher.count();
+        assert_eq!(built, 2 * size as usize - 1);
+
+        for index in (0..size as usize).step_by(97) {
+            let proof = tree.get_proof(index)?;
+            assert!(proof.verify());
+        }
+        tree.root();
+
+        // only verification hashed anything, not proof generation
+        let depth = size.ilog2() as usize;
+        assert_eq!(hasher.count() - built, size.div_ceil(97) as usize * depth);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn update_rehashes_one_path() -> eyre::Result<()> {
+    let values: Vec<u32> = (0..1 << 12).collect();
+    let hasher = Counting::default();
+    let mut tree = MerkleTree::with_hasher(values, hasher.clone());
+    tree.root();
+
+    let before = hasher.count();
+    tree.update(1234, 0)?;
+    assert_eq!(hasher.count() - before, 1 + 12);
+
+    let before = hasher.count();
+    tree.add(0);
+    tree.root();
+    // the new leaf, the padding leaf once, the 13 nodes above the new leaf
+    // and the empty subtrees of the new half up to height 11, above that no
+    // level is padded
+    assert_eq!(hasher.count() - before, 1 + 1 + 13 + 11);
+
+    Ok(())
+}
+
+#[test]
+fn batches_hash_every_node_once() -> eyre::Result<()> {
+    let values: Vec<u32> = (0..1 << 12).collect();
+    let hasher = Counting::default();
+    let mut tree = MerkleTree::with_hasher(values, hasher.clone());
+    tree.root();
+
+    // 64 neighbouring leafs, the 63 nodes of their subtree and the 6 above it
+    let changes: Vec<(usize, u32)> = (1024..1088).map(|index| (index, 0)).collect();
+    let before = hasher.count();
+    tree.update_all(&changes)?;
+    assert_eq!(hasher.count() - before, 64 + 63 + 6);
+
+    // a leaf changed twice is hashed once
+    let before = hasher.count();
+    tree.update_all(&[(7, 1), (7, 2)])?;
+    assert_eq!(hasher.count() - before, 1 + 12);
+
+    Ok(())
+}
+
+#[test]
+fn rewinding_hashes_nothing() -> eyre::Result<()> {
+    let hasher = Counting::default();
+    let mut tree = MerkleTree::with_hasher((0..1000).collect::<Vec<u32>>(), hasher.clone());
+    let root = tree.root();
+    let checkpoint = tree.checkpoint();
+    tree.add_all(1000..6000);
+    tree.root();
+
+    let before = hasher.count();
+    tree.rewind(checkpoint)?;
+    assert_eq!(tree.root(), root);
+    assert_eq!(hasher.count(), before);
+    Ok(())
+}
+
+#[test]
+fn extend_rehashes_once() {
+    let hasher = Counting::default();
+    let mut tree = MerkleTree::with_hasher(vec![0u32; 100], hasher.clone());
+    tree.root();
+
+    let before = hasher.count();
+    tree.extend(100..1024);
+    tree.root();
+    // the new leafs and the nodes above them, subtrees of the first 100
+    // values are kept
+    let kept: usize = (1..=10).map(|height| 100 >> height).sum();
+    assert_eq!(hasher.count() - before, 924 + 1023 - kept);
+}
+
+#[test]
+fn first_proof_hashes_tree_once() -> eyre::Result<()> {
+    let values: Vec<u32> = (0..1000).collect();
+    let hasher = Counting::default();
+    let tree = MerkleTree::with_hasher(values, hasher.clone());
+
+    // the values, the padding leaf once, the parents of the filled nodes and
+    // the empty subtrees of the four padded levels, the root is taken from
+    // the levels the proof is read from
+    let built = 1000 + 1 + (500 + 250 + 125 + 63 + 32 + 16 + 8 + 4 + 2 + 1) + 4;
+    let proof = tree.get_proof(617)?;
+    assert_eq!(hasher.count(), built);
+    assert_eq!(proof.root, tree.root());
+    assert_eq!(hasher.count(), built);
+
+    Ok(())
+}
+
+#[test]
+fn add_all_matches_loop_of_add() {
+    let mut looped = MerkleTree::from((0..37u32).collect::<Vec<_>>());
+    let mut batched = MerkleTree::from((0..37u32).collect::<Vec<_>>());
+    looped.root();
+    batched.root();
+
+    (37..100).for_each(|value| looped.add(value));
+    batched.add_all(37..100);
+    assert_eq!(batched.len(), 100);
+    assert_eq!(batched.root(), looped.root());
+
+    // without cached levels the tree is simply built on the next root
+    let mut uncached = MerkleTree::from((0..37u32).collect::<Vec<_>>());
+    uncached.add_all(37..100);
+    assert_eq!(uncached.root(), looped.root());
+}
+
+#[test]
+fn add_all_hashes_once() {
+    let hasher = Counting::default();
+    let mut tree = MerkleTree::with_hasher((0..1024u32).collect(), hasher.clone());
+    tree.root();
+
+    let before = hasher.count();
+    tree.add_all(1024..2048);
+    tree.root();
+    // 1024 new leafs, the nodes above them and the new root, the subtree of
+    // the first 1024 leafs is kept
+    assert_eq!(hasher.count() - before, 1024 + 1023 + 1);
+}
+
+#[test]
+fn append_hashes_one_path() -> eyre::Result<()> {
+    let hasher = Counting::default();
+    let mut tree = MerkleTree::with_hasher((0..1 << 16).collect(), hasher.clone());
+    tree.root();
+
+    // the last free leaf, its path and nothing else
+    tree.truncate((1 << 16) - 1);
+    let before = hasher.count();
+    tree.add(0);
+    tree.root();
+    assert_eq!(hasher.count() - before, 1 + 16);
+
+    // outgrowing the width adds a level and the empty subtrees of the new
+    // half, the padding leaf was hashed when the tree was truncated
+    let before = hasher.count();
+    tree.add(1);
+    tree.root();
+    assert_eq!(hasher.count() - before, 1 + 17 + 15);
+
+    // the empty subtrees are kept for later appends
+    let before = hasher.count();
+    tree.add(2);
+    let proof = tree.get_proof(1 << 16)?;
+    assert_eq!(hasher.count() - before, 1 + 17);
+
+    let mut values: Vec<u32> = (0..(1 << 16) - 1).collect();
+    values.extend([0, 1, 2]);
+    let fresh = MerkleTree::from(values);
+    assert_eq!(tree.root(), fresh.root());
+    assert_eq!(proof.to_bytes(), fresh.get_proof(1 << 16)?.to_bytes());
+    Ok(())
+}
+
+#[test]
+fn cached_levels_follow_every_change() -> eyre::Result<()> {
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        let mut values: Vec<u32> = Vec::new();
+        let mut tree = MerkleTree::from(values.clone()).with_padding(padding);
+        let mut hashed = tree.clone().finalize();
+        // grow across several powers of two, shrink back and grow again
+        for step in 0..400usize {
+            let next = 10 * step as u32;
+            let growing = !(150..=250).contains(&step);
+            // the hashed tree follows appends, after other changes it is copied
+            let mut appended = false;
+            match step % 7 {
+                0 | 1 | 3 if growing => {
+                    tree.add(next);
+                    hashed.add_hash(tree.leafs()[values.len()]);
+                    values.push(next);
+                    appended = true;
+                }
+                2 if growing => {
+                    tree.add_all(next..next + 5);
+                    values.extend(next..next + 5);
+                }
+                4 if !values.is_empty() => {
+                    let index = step % values.len();
+                    tree.update(index, next)?;
+                    values[index] = next;
+                }
+                5 if !values.is_empty() => {
+                    let index = step % values.len();
+                    tree.remove(index)?;
+                    values.remove(index);
+                }
+                6 => {
+                    tree.truncate(values.len().saturating_sub(3));
+                    values.truncate(values.len().saturating_sub(3));
+                }
+                _ => {
+                    tree.pop();
+                    values.pop();
+                }
+            }
+
+            let fresh = MerkleTree::from(values.clone()).with_padding(padding);
+            assert_eq!(tree.root(), fresh.root(), "{padding:?} {step}");
+            assert_eq!(tree.leafs(), fresh.leafs());
+            if let Some(last) = values.len().checked_sub(1) {
+                for index in [0, last / 2, last] {
+                    assert_eq!(tree.get_proof(index)?, fresh.get_proof(index)?);
+                }
+            }
+            if appended {
+                assert_eq!(hashed.root(), fresh.root(), "{padding:?} {step}");
+            } else {
+                hashed = tree.clone().finalize();
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use merkle::MerkleTree;
+
+#[test]
+fn presized_tree_does_not_grow() {
+    let mut tree = MerkleTree::with_capacity(1000);
+    let capacity = tree.capacity();
+    assert!(capacity >= 1000);
+    assert!(tree.is_empty());
+
+    (0..1000u32).for_each(|value| tree.add(value));
+    assert_eq!(tree.capacity(), capacity);
+    assert_eq!(
+        tree.root(),
+        MerkleTree::from((0..1000u32).collect::<Vec<_>>()).root()
+    );
+}
+
+#[test]
+fn reserve_before_bulk_load() {
+    let mut tree = MerkleTree::from((0..10u32).collect::<Vec<_>>());
+    tree.root();
+    tree.reserve(5000);
+    let capacity = tree.capacity();
+    assert!(capacity >= 5010);
+
+    tree.add_all(10..5010);
+    assert_eq!(tree.capacity(), capacity);
+    assert_eq!(
+        tree.root(),
+        MerkleTree::from((0..5010u32).collect::<Vec<_>>()).root()
+    );
+}
+
+#[test]
+fn collected_tree_is_allocated_once() {
+    let tree: MerkleTree<u32> = (0..777).collect();
+    assert_eq!(tree.capacity(), 777);
+    assert_eq!(
+        tree.root(),
+        MerkleTree::from((0..777u32).collect::<Vec<_>>()).root()
+    );
+}
@@ -0,0 +1,88 @@
+use std::sync::{Arc, Mutex};
+
+use merkle::{hash::LeafHasher, CasError, MerkleTree, PaddingStrategy};
+
+#[test]
+fn swaps_happen_only_from_the_expected_value() -> eyre::Result<()> {
+    let mut tree = MerkleTree::from((0..10).collect::<Vec<u32>>());
+    tree.compare_and_update(3, &3, 30)?;
+    assert_eq!(
+        tree.root(),
+        MerkleTree::from(vec![0, 1, 2, 30, 4, 5, 6, 7, 8, 9]).root()
+    );
+
+    // someone else changed the value first, the error tells what it is now
+    let root = tree.root();
+    let err = tree.compare_and_update(3, &3, 31).unwrap_err();
+    let actual = tree.hasher().hash_leaf(&30);
+    assert_eq!(err, CasError::Mismatch { index: 3, actual });
+    assert_eq!((tree[3], tree.root()), (30, root));
+
+    // a retry against the value in the tree goes through
+    tree.compare_and_update(3, &30, 31)?;
+    assert_eq!(tree[3], 31);
+
+    assert_eq!(
+        tree.compare_and_update(10, &0, 1),
+        Err(CasError::IndexOutOfBounds { index: 10, len: 10 })
+    );
+    Ok(())
+}
+
+#[test]
+fn failed_swaps_change_nothing() -> eyre::Result<()> {
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        // the mismatch is the same whether the levels are cached or not
+        let mut uncached = MerkleTree::from((0..7).collect::<Vec<u32>>()).with_padding(padding);
+        let mut cached = uncached.clone();
+        let root = cached.root();
+        assert_eq!(
+            uncached.compare_and_update(6, &5, 0),
+            cached.compare_and_update(6, &5, 0)
+        );
+
+        let roots = Arc::new(Mutex::new(Vec::new()));
+        let seen = roots.clone();
+        cached.on_root_change(move |root, _| seen.lock().unwrap().push(root));
+        assert!(cached.compare_and_update(0, &1, 0).is_err());
+        assert!(roots.lock().unwrap().is_empty());
+        assert_eq!(cached.root(), root);
+        assert!(cached.levels().eq(uncached.levels()), "{padding:?}");
+
+        cached.compare_and_update(0, &0, 9)?;
+        assert_eq!(roots.lock().unwrap()[..], [cached.root()]);
+    }
+    Ok(())
+}
+
+#[test]
+fn hashed_trees_swap_leaf_hashes() -> eyre::Result<()> {
+    let tree = MerkleTree::from((0..10).collect::<Vec<u32>>());
+    tree.root();
+    let hasher = tree.hasher().clone();
+    let mut hashed = tree.finalize();
+    let (three, thirty) = (hasher.hash_leaf(&3), hasher.hash_leaf(&30));
+
+    hashed.compare_and_update_hash(3, &three, thirty)?;
+    let updated = MerkleTree::from(vec![0, 1, 2, 30, 4, 5, 6, 7, 8, 9]);
+    assert_eq!(hashed.root(), updated.root());
+    assert_eq!(hashed.get_proof(3)?, updated.get_proof(3)?);
+
+    assert_eq!(
+        hashed.compare_and_update_hash(3, &three, 0),
+        Err(CasError::Mismatch {
+            index: 3,
+            actual: thirty
+        })
+    );
+    assert_eq!(hashed.root(), updated.root());
+    assert!(matches!(
+        hashed.compare_and_update_hash(10, &three, 0),
+        Err(CasError::IndexOutOfBounds { index: 10, len: 10 })
+    ));
+    Ok(())
+}
@@ -0,0 +1,140 @@
+#![cfg(feature = "cbor")]
+
+use merkle::{
+    hash::DefaultBuildHasher, proof::MAX_PATH_LEN, Direction, LeafHash, MerkleError, MerkleProof,
+    MerkleTree, PaddingStrategy, Root, Step,
+};
+
+type Proof = MerkleProof<DefaultBuildHasher>;
+
+fn proof_with_steps(directions: &[Direction]) -> Proof {
+    MerkleProof {
+        path: directions
+            .iter()
+            .enumerate()
+            .map(|(i, &direction)| Step {
+                direction,
+                value: 0x1111_1111_1111_1111u64.wrapping_mul(i as u64 + 1),
+            })
+            .collect(),
+        root: Root(0x0102_0304_0506_0708),
+        leaf: LeafHash(0xa0a1_a2a3_a4a5_a6a7),
+        index: 1,
+        width: 8,
+        padding: PaddingStrategy::DefaultValue,
+        hasher: DefaultBuildHasher::default(),
+    }
+}
+
+#[test]
+fn golden_cbor() -> eyre::Result<()> {
+    let proof = proof_with_steps(&[Direction::Left, Direction::Right, Direction::Right]);
+    let expected = include_bytes!("fixtures/proof.cbor");
+
+    assert_eq!(proof.to_cbor(), expected);
+    assert_eq!(Proof::from_cbor(expected)?, proof);
+    Ok(())
+}
+
+#[test]
+fn round_trip() -> eyre::Result<()> {
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        let tree = MerkleTree::from((0..300u32).collect::<Vec<_>>()).with_padding(padding);
+        for index in [0, 1, 150, 299] {
+            let proof = tree.get_proof(index)?;
+            let parsed = Proof::from_cbor(&proof.to_cbor())?;
+
+            assert!(parsed.verify());
+            assert_eq!(parsed, proof);
+        }
+    }
+
+    let empty = proof_with_steps(&[]);
+    assert_eq!(Proof::from_cbor(&empty.to_cbor())?, empty);
+    Ok(())
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn wide_digests() -> eyre::Result<()> {
+    use merkle::hash::Sha256;
+
+    let tree = MerkleTree::with_hasher(vec!["a", "b", "c"], Sha256);
+    let proof = tree.get_proof(2)?;
+    let parsed = MerkleProof::<Sha256>::from_cbor(&proof.to_cbor())?;
+    assert!(parsed.verify());
+    assert_eq!(parsed, proof);
+
+    // a 32 byte digest is not a 8 byte one
+    assert!(Proof::from_cbor(&proof.to_cbor()).is_err());
+    Ok(())
+}
+
+#[test]
+fn truncated_input_is_rejected() {
+    let cbor = proof_with_steps(&[Direction::Left, Direction::Right]).to_cbor();
+    for len in 0..cbor.len() {
+        assert!(Proof::from_cbor(&cbor[..len]).is_err());
+    }
+
+    let mut trailing = cbor.clone();
+    trailing.push(0);
+    assert_eq!(
+        Proof::from_cbor(&trailing).err(),
+        Some(MerkleError::TrailingBytes(1))
+    );
+}
+
+#[test]
+fn damaged_input_never_panics() {
+    let cbor = proof_with_steps(&[Direction::Left, Direction::Right, Direction::Left]).to_cbor();
+    for i in 0..cbor.len() {
+        for byte in [0x00, 0x17, 0x18, 0x1b, 0x1f, 0x5f, 0x9f, 0xff] {
+            let mut damaged = cbor.clone();
+            damaged[i] = byte;
+            let _ = Proof::from_cbor(&damaged);
+        }
+    }
+}
+
+#[test]
+fn long_paths_are_rejected_before_allocating() {
+    let mut cbor = proof_with_steps(&[]).to_cbor();
+    // the empty path array after the leaf, made to claim 2^24 steps
+    let at = 1 + 5 + 9 + 5;
+    assert_eq!(cbor[at], 0x80);
+    cbor.splice(at..=at, [0x9a, 0x00, 0x00, 0x00, 0x00]);
+    cbor[at + 1] = 1;
+    assert_eq!(
+        Proof::from_cbor(&cbor).err(),
+        Some(MerkleError::InvalidEncoding("proof path is too long"))
+    );
+
+    let longest = proof_with_steps(&[Direction::Left; MAX_PATH_LEN]);
+    assert!(Proof::from_cbor(&longest.to_cbor()).is_ok());
+    let longer = proof_with_steps(&[Direction::Left; MAX_PATH_LEN + 1]);
+    assert_eq!(
+        Proof::from_cbor(&longer.to_cbor()).err(),
+        Some(MerkleError::InvalidEncoding("proof path is too long"))
+    );
+}
+
+#[test]
+fn only_shortest_forms_are_accepted() {
+    let cbor = proof_with_steps(&[]).to_cbor();
+    // the width 8 written with a one byte argument
+    let at = cbor.len() - 10;
+    assert_eq!(cbor[at], 0x08);
+    let mut long = cbor.clone();
+    long.splice(at..=at, [0x18, 0x08]);
+    assert_eq!(
+        Proof::from_cbor(&long).err(),
+        Some(MerkleError::InvalidEncoding(
+            "integer is not in its shortest form"
+        ))
+    );
+}
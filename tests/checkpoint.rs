@@ -0,0 +1,126 @@
+use merkle::{MerkleError, MerkleTree, PaddingStrategy};
+
+#[test]
+fn rewinding_restores_the_tree_at_the_checkpoint() -> eyre::Result<()> {
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        let mut tree = MerkleTree::from((0..1000).collect::<Vec<u32>>()).with_padding(padding);
+        let root = tree.root();
+        let checkpoint = tree.checkpoint();
+        assert_eq!(checkpoint.len(), 1000);
+
+        tree.add_all(1000..6000);
+        assert_ne!(tree.root(), root);
+        tree.rewind(checkpoint)?;
+
+        let fresh = MerkleTree::from((0..1000).collect::<Vec<u32>>()).with_padding(padding);
+        assert_eq!(
+            (tree.len(), tree.root()),
+            (1000, fresh.root()),
+            "{padding:?}"
+        );
+        for index in [0, 511, 999] {
+            assert_eq!(tree.get_proof(index)?, fresh.get_proof(index)?);
+        }
+        assert!(tree.levels().eq(fresh.levels()), "{padding:?}");
+
+        // at the sizes where the shape of the tree changes
+        for len in [0, 1, 2, 1023, 1024] {
+            let values: Vec<u32> = (0..len).collect();
+            let mut tree = MerkleTree::from(values.clone()).with_padding(padding);
+            let root = tree.root();
+            let checkpoint = tree.checkpoint();
+            tree.add_all(0..1025);
+            tree.root();
+            tree.rewind(checkpoint)?;
+            assert_eq!(tree.root(), root, "{len} {padding:?}");
+            let fresh = MerkleTree::from(values).with_padding(padding);
+            assert!(tree.levels().eq(fresh.levels()), "{len} {padding:?}");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn checkpoints_can_be_rewound_to_again() -> eyre::Result<()> {
+    let mut tree = MerkleTree::from((0..1000).collect::<Vec<u32>>());
+    let first = (tree.root(), tree.checkpoint());
+    tree.add_all(1000..3000);
+    let second = (tree.root(), tree.checkpoint());
+    tree.add_all(3000..4000);
+
+    tree.rewind(second.1)?;
+    assert_eq!(tree.root(), second.0);
+    tree.add_all(0..10);
+    tree.rewind(second.1)?;
+    assert_eq!(tree.root(), second.0);
+
+    tree.rewind(first.1)?;
+    assert_eq!(tree.root(), first.0);
+    tree.rewind(first.1)?;
+    assert_eq!((tree.len(), tree.root()), (1000, first.0));
+
+    // rewinding past the second checkpoint forgot it
+    tree.add_all(1000..3000);
+    assert_eq!(
+        tree.rewind(second.1),
+        Err(MerkleError::StaleCheckpoint { len: 3000 })
+    );
+    assert_eq!(tree.len(), 3000);
+    Ok(())
+}
+
+#[test]
+fn changes_below_a_checkpoint_forget_it() -> eyre::Result<()> {
+    let mut tree = MerkleTree::from((0..100).collect::<Vec<u32>>());
+    tree.root();
+
+    // changes after the checkpoint are rewound with the appends
+    let checkpoint = tree.checkpoint();
+    tree.add(100);
+    tree.update(100, 0)?;
+    tree.rewind(checkpoint)?;
+    assert_eq!(
+        tree.root(),
+        MerkleTree::from((0..100).collect::<Vec<u32>>()).root()
+    );
+
+    tree.update(99, 0)?;
+    assert!(tree.rewind(checkpoint).is_err());
+
+    let checkpoint = tree.checkpoint();
+    tree.truncate(50);
+    tree.add_all(50..100);
+    assert!(tree.rewind(checkpoint).is_err());
+
+    let checkpoint = tree.checkpoint();
+    tree.remove(0)?;
+    assert!(tree.rewind(checkpoint).is_err());
+
+    let checkpoint = tree.checkpoint();
+    let mut tree = tree.with_padding(PaddingStrategy::PromoteOdd);
+    assert!(tree.rewind(checkpoint).is_err());
+    Ok(())
+}
+
+#[test]
+fn checkpoints_before_caching_rewind_too() -> eyre::Result<()> {
+    let mut tree = MerkleTree::from((0..1000).collect::<Vec<u32>>());
+    let checkpoint = tree.checkpoint();
+    tree.add_all(1000..1100);
+    tree.root();
+    tree.rewind(checkpoint)?;
+    let fresh = MerkleTree::from((0..1000).collect::<Vec<u32>>());
+    assert!(tree.levels().eq(fresh.levels()));
+
+    // nothing was ever cached
+    let mut tree = MerkleTree::from((0..1000).collect::<Vec<u32>>());
+    let checkpoint = tree.checkpoint();
+    tree.add_all(1000..1100);
+    tree.rewind(checkpoint)?;
+    assert_eq!(tree.root(), fresh.root());
+    Ok(())
+}
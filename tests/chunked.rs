@@ -0,0 +1,114 @@
+use std::io::Read;
+
+use merkle::{
+    chunked::DEFAULT_CHUNK_SIZE,
+    hash::{DefaultBuildHasher, LeafHasher, MerkleHasher},
+    ChunkedMerkleTree, MerkleError, MerkleTree,
+};
+
+const CHUNK: usize = 1000;
+
+fn file(size: usize) -> Vec<u8> {
+    (0..size).map(|i| (i * 7 % 251) as u8).collect()
+}
+
+/// hands out a few bytes per read, like a pipe
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = buf.len().min(self.0.len()).min(7);
+        buf[..n].copy_from_slice(&self.0[..n]);
+        self.0 = &self.0[n..];
+        Ok(n)
+    }
+}
+
+fn check(bytes: &[u8], chunks: usize) -> eyre::Result<()> {
+    let tree = MerkleTree::from_reader(bytes, CHUNK)?;
+    assert_eq!(tree.len(), chunks);
+    assert_eq!(tree.size(), bytes.len() as u64);
+
+    let hasher = DefaultBuildHasher::default();
+    let leafs = bytes
+        .chunks(CHUNK)
+        .map(|chunk| hasher.hash_leaf(chunk))
+        .collect();
+    let expected = MerkleTree::<Vec<u8>>::from_leaf_hashes_with_hasher(leafs, hasher);
+    assert_eq!(tree.root(), expected.root());
+    assert_eq!(
+        tree.root(),
+        MerkleTree::from_reader(Trickle(bytes), CHUNK)?.root()
+    );
+
+    for index in [0, chunks / 2, chunks - 1] {
+        let range = tree.chunk_range(index).unwrap();
+        let chunk = &bytes[range.start as usize..range.end as usize];
+        let proof = tree.get_proof(index)?;
+        let root = tree.root();
+        assert!(ChunkedMerkleTree::verify_chunk_against(
+            &proof, &root, chunk
+        ));
+        assert!(!ChunkedMerkleTree::verify_chunk_against(
+            &proof,
+            &root,
+            &chunk[1..]
+        ));
+        // a proof with a forged root still has to lead to the trusted one
+        let mut forged = proof.clone();
+        forged.root.0 ^= 1;
+        assert!(ChunkedMerkleTree::verify_chunk_against(
+            &forged, &root, chunk
+        ));
+        assert!(!ChunkedMerkleTree::verify_chunk_against(
+            &forged,
+            &forged.root,
+            chunk
+        ));
+
+        for offset in [range.start, range.end - 1] {
+            assert_eq!(tree.chunk_of(offset), Some(index));
+            assert_eq!(tree.proof_for_offset(offset)?.index, index);
+        }
+    }
+
+    let size = bytes.len() as u64;
+    assert_eq!(tree.chunk_of(size), None);
+    assert_eq!(tree.chunk_range(chunks), None);
+    assert_eq!(
+        tree.proof_for_offset(size).err(),
+        Some(MerkleError::OffsetOutOfBounds { offset: size, size })
+    );
+    Ok(())
+}
+
+#[test]
+fn multiple_of_the_chunk_size() -> eyre::Result<()> {
+    check(&file(5 * CHUNK), 5)
+}
+
+#[test]
+fn one_byte_over() -> eyre::Result<()> {
+    let bytes = file(5 * CHUNK + 1);
+    check(&bytes, 6)?;
+    let tree = MerkleTree::from_reader(&bytes[..], CHUNK)?;
+    assert_eq!(tree.chunk_range(5), Some(5000..5001));
+    Ok(())
+}
+
+#[test]
+fn smaller_than_a_chunk() -> eyre::Result<()> {
+    check(&file(CHUNK / 3), 1)
+}
+
+#[test]
+fn empty_and_invalid() -> eyre::Result<()> {
+    let tree = MerkleTree::from_reader(&[][..], DEFAULT_CHUNK_SIZE)?;
+    assert!(tree.is_empty());
+    assert_eq!(tree.root().0, DefaultBuildHasher::default().hash_padding());
+    assert!(tree.proof_for_offset(0).is_err());
+
+    let err = MerkleTree::from_reader(&[1u8][..], 0).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    Ok(())
+}
@@ -0,0 +1,124 @@
+#![cfg(feature = "sha256")]
+
+use std::process::{Command, Output};
+
+use merkle::{hash::Sha256, MerkleTree};
+
+const LINES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/lines.txt");
+const EMPTY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/empty.txt");
+
+fn merkle(args: &[&str]) -> eyre::Result<Output> {
+    Ok(Command::new(env!("CARGO_BIN_EXE_merkle"))
+        .args(args)
+        .output()?)
+}
+
+fn stdout(output: &Output) -> eyre::Result<String> {
+    Ok(String::from_utf8(output.stdout.clone())?.trim().to_owned())
+}
+
+#[test]
+fn root_of_lines() -> eyre::Result<()> {
+    let output = merkle(&["root", LINES])?;
+    assert!(output.status.success());
+
+    let lines = ["alpha", "beta", "gamma", "delta", "epsilon"];
+    let tree = MerkleTree::with_hasher(lines.map(str::as_bytes).to_vec(), Sha256);
+    assert_eq!(stdout(&output)?, tree.root().to_string());
+
+    Ok(())
+}
+
+#[test]
+fn root_of_chunks() -> eyre::Result<()> {
+    let output = merkle(&["root", LINES, "--chunk-size", "8"])?;
+    assert!(output.status.success());
+
+    let contents = std::fs::read(LINES)?;
+    let tree = MerkleTree::with_hasher(contents.chunks(8).collect(), Sha256);
+    assert_eq!(stdout(&output)?, tree.root().to_string());
+
+    Ok(())
+}
+
+#[test]
+fn proof_round_trip() -> eyre::Result<()> {
+    let root = stdout(&merkle(&["root", LINES])?)?;
+    let proof = merkle(&["proof", LINES, "--index", "3"])?;
+    assert!(proof.status.success());
+    let json = stdout(&proof)?;
+    assert!(json.starts_with(r#"{"leaf":"0x"#));
+    assert!(json.contains(r#""index":3"#));
+
+    let dir = std::env::temp_dir().join(format!("merkle-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("proof.json");
+    std::fs::write(&path, &json)?;
+    let path = path.to_str().unwrap();
+
+    let verify = merkle(&["verify", "--root", &root, "--proof", path])?;
+    assert!(verify.status.success());
+    let verify = merkle(&["verify", "--root", &format!("0x{root}"), "--proof", path])?;
+    assert!(verify.status.success());
+
+    let other = stdout(&merkle(&["root", LINES, "--chunk-size", "4"])?)?;
+    let verify = merkle(&["verify", "--root", &other, "--proof", path])?;
+    assert!(!verify.status.success());
+    assert!(String::from_utf8(verify.stderr)?.contains("does not verify"));
+
+    std::fs::write(path, json.replace("\"left\"", "\"up\""))?;
+    let verify = merkle(&["verify", "--root", &root, "--proof", path])?;
+    assert!(!verify.status.success());
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn clear_errors() -> eyre::Result<()> {
+    let output = merkle(&["root", EMPTY])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("is empty"));
+
+    let output = merkle(&["proof", LINES, "--index", "5"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("index 5 is out of range for 5 leafs"));
+
+    let output = merkle(&["root", "/does/not/exist"])?;
+    assert!(!output.status.success());
+
+    let output = merkle(&["root", LINES, "--chunk-size", "0"])?;
+    assert!(!output.status.success());
+
+    // an option without its value, at the end or before the next option
+    for args in [
+        &["root", LINES, "--chunk-size"][..],
+        &["root", "--chunk-size", "--index", "3", LINES],
+        &["proof", LINES, "--index"],
+    ] {
+        let output = merkle(args)?;
+        assert!(!output.status.success());
+        assert!(String::from_utf8(output.stderr)?.contains("needs a value"));
+    }
+
+    // arguments left over after the input file
+    for (args, unexpected) in [
+        (&["root", LINES, "extra"][..], "\"extra\""),
+        (&["root", "-v", LINES], "\"-v\""),
+        (&["root", LINES, "--bogus"], "\"--bogus\""),
+    ] {
+        let output = merkle(args)?;
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr)?;
+        assert!(
+            stderr.contains(&format!("unexpected argument {unexpected}")),
+            "{stderr}"
+        );
+    }
+
+    let output = merkle(&["frobnicate"])?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("usage"));
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+//! helpers shared by the integration tests, every test crate only uses some
+//! of them
+#![allow(dead_code)]
+
+use std::{
+    hash::{BuildHasher, DefaultHasher},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+/// lowercase hex of `bytes`, to compare digests with published vectors
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// hasher factory counting how many hashes are computed
+/// clones share the count
+#[derive(Clone, Default)]
+pub struct Counting(Arc<AtomicUsize>);
+
+impl Counting {
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl BuildHasher for Counting {
+    type Hasher = DefaultHasher;
+
+    fn build_hasher(&self) -> DefaultHasher {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        DefaultHasher::new()
+    }
+}
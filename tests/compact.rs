@@ -0,0 +1,89 @@
+use merkle::{CompactStep, EmptyHashes, MerkleError, MerkleTree, PaddingStrategy};
+
+#[test]
+fn compact_and_full_proofs_agree() -> eyre::Result<()> {
+    for len in 1..=40u32 {
+        for padding in [
+            PaddingStrategy::DefaultValue,
+            PaddingStrategy::DuplicateLast,
+            PaddingStrategy::PromoteOdd,
+        ] {
+            let tree = MerkleTree::from((1..=len).collect::<Vec<_>>()).with_padding(padding);
+            let empty = tree.empty_hashes();
+            for index in 0..tree.padded_len() {
+                let full = if index < tree.len() {
+                    tree.get_proof(index)?
+                } else {
+                    tree.get_padding_proof(index)?
+                };
+                let bytes = full.to_bytes();
+                let compact = full.compact(&empty);
+                assert!(
+                    compact.verify_against(&tree.root(), &empty),
+                    "{len} {padding:?} {index}"
+                );
+                assert_eq!(compact.expand(&empty)?.to_bytes(), bytes);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn nearly_empty_trees_shrink() -> eyre::Result<()> {
+    // 1025 values pad to 2048 leafs, the last value sees padding up to the top
+    let tree = MerkleTree::from((1..=1025u32).collect::<Vec<_>>());
+    let root = tree.root();
+    let empty = tree.empty_hashes();
+    let proof = tree.get_proof(1024)?;
+    assert_eq!(proof.path.len(), 11);
+    let compact = proof.compact(&empty);
+    assert_eq!(compact.siblings(), 1);
+    assert!(compact.verify_against(&root, &empty));
+
+    // a hashed tree has the same table
+    let hashed = MerkleTree::from((1..=1025u32).collect::<Vec<_>>()).finalize();
+    let compact = hashed.get_proof(1024)?.compact(&hashed.empty_hashes());
+    assert_eq!(compact.siblings(), 1);
+    assert!(compact.verify_against(&root, &empty));
+    Ok(())
+}
+
+#[test]
+fn wrong_markers_fail() -> eyre::Result<()> {
+    let tree = MerkleTree::from((1..=5u32).collect::<Vec<_>>());
+    let root = tree.root();
+    let empty = tree.empty_hashes();
+
+    let mut compact = tree.get_proof(4)?.compact(&empty);
+    assert_eq!(compact.siblings(), 1);
+    compact.path.swap(0, 1);
+    assert!(!compact.verify_against(&root, &empty));
+    assert_eq!(
+        compact.expand(&empty).err(),
+        Some(MerkleError::InvalidEncoding(
+            "empty subtree at the wrong height"
+        ))
+    );
+
+    // a marker in place of a real sibling leads to another root
+    let mut compact = tree.get_proof(0)?.compact(&empty);
+    assert_eq!(compact.siblings(), 3);
+    compact.path[2] = CompactStep::Empty { height: 2 };
+    assert!(!compact.verify_against(&root, &empty));
+
+    // a forged root does not help, the path still leads to the trusted one
+    let mut forged = tree.get_proof(1)?.compact(&empty);
+    forged.root.0 ^= 1;
+    assert!(forged.verify_against(&root, &empty));
+    assert!(!forged.verify_against(&forged.root, &empty));
+
+    // the table of another padding leaf does not verify
+    let other = EmptyHashes::new(tree.hasher(), 0, 3);
+    let compact = tree.get_proof(4)?.compact(&empty);
+    assert!(!compact.verify_against(&root, &other));
+    // nor does a table that is too short
+    let short = EmptyHashes::new(tree.hasher(), *empty.get(0).unwrap(), 0);
+    assert!(!compact.verify_against(&root, &short));
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use merkle::{ConcurrentMerkleTree, MerkleTree, PaddingStrategy};
+
+const PRODUCERS: u64 = 8;
+const VALUES: u64 = 500;
+
+#[test]
+fn proofs_verify_against_their_snapshot() -> eyre::Result<()> {
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        let tree = ConcurrentMerkleTree::new().with_padding(padding);
+        let done = AtomicBool::new(false);
+
+        let placed = std::thread::scope(|scope| -> eyre::Result<Vec<(usize, u64)>> {
+            let producers: Vec<_> = (0..PRODUCERS)
+                .map(|producer| {
+                    let tree = &tree;
+                    scope.spawn(move || {
+                        (0..VALUES)
+                            .map(|i| {
+                                let value = producer * VALUES + i;
+                                (tree.add(value), value)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            let verifier = scope.spawn(|| -> eyre::Result<usize> {
+                let mut roots = HashMap::new();
+                let mut checked = 0;
+                while !done.load(Ordering::Relaxed) || checked == 0 {
+                    let (len, root) = tree.root();
+                    roots.insert(len, root);
+                    if len == 0 {
+                        continue;
+                    }
+                    for index in [0, len / 2, len - 1] {
+                        let proof = tree.get_proof(index)?;
+                        assert!(proof.len >= len);
+                        assert!(proof.proof.verify());
+                        if let Some(root) = roots.get(&proof.len) {
+                            assert!(proof.verify_against(root));
+                            checked += 1;
+                        }
+                    }
+
+                    let snapshot = tree.snapshot();
+                    assert!(snapshot.len() >= len);
+                    let proof = snapshot.get_proof(len - 1)?;
+                    assert!(proof.verify_against(&snapshot.root()));
+                }
+                Ok(checked)
+            });
+
+            let mut placed = Vec::new();
+            for producer in producers {
+                placed.extend(producer.join().unwrap());
+            }
+            done.store(true, Ordering::Relaxed);
+            assert!(verifier.join().unwrap()? > 0);
+            Ok(placed)
+        })?;
+
+        let mut placed = placed;
+        placed.sort();
+        assert_eq!(placed.len(), (PRODUCERS * VALUES) as usize);
+        let values: Vec<u64> = placed.into_iter().map(|(_, value)| value).collect();
+        let expected = MerkleTree::from(values).with_padding(padding);
+        assert_eq!(tree.root(), (tree.len(), expected.root()));
+
+        let proof = tree.get_proof(17)?;
+        assert_eq!(proof.len, tree.len());
+        assert!(proof.verify_against(&expected.root()));
+        // a proof for another length does not pass for this root
+        let shorter = ConcurrentMerkleTree::<u64>::new().with_padding(padding);
+        shorter.add_hash(proof.proof.leaf.0);
+        assert!(!shorter.get_proof(0)?.verify_against(&expected.root()));
+    }
+    Ok(())
+}
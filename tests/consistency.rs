@@ -0,0 +1,95 @@
+use merkle::{verify::verify_consistency, MerkleError, MerkleTree, PaddingStrategy, Root};
+
+fn tree(len: u32) -> MerkleTree<u32> {
+    (0..len)
+        .collect::<MerkleTree<u32>>()
+        .with_padding(PaddingStrategy::PromoteOdd)
+}
+
+#[test]
+fn every_pair_of_sizes_is_consistent() -> eyre::Result<()> {
+    let mut tree = tree(0);
+    let mut roots = Vec::new();
+    for value in 0..64 {
+        tree.add(value);
+        roots.push(tree.root());
+    }
+
+    for new_len in 1..=64 {
+        let tree = self::tree(new_len as u32);
+        for old_len in 1..=new_len {
+            let proof = tree.consistency_proof(old_len)?;
+            let (old_root, new_root) = (roots[old_len - 1], roots[new_len - 1]);
+            assert!(verify_consistency(
+                &old_root, old_len, &new_root, new_len, &proof
+            ));
+
+            // the proof is bound to both sizes and roots
+            assert!(!verify_consistency(
+                &Root(old_root.0 ^ 1),
+                old_len,
+                &new_root,
+                new_len,
+                &proof
+            ));
+            assert!(!verify_consistency(
+                &old_root,
+                old_len,
+                &Root(new_root.0 ^ 1),
+                new_len,
+                &proof
+            ));
+            if old_len < new_len {
+                assert!(!verify_consistency(
+                    &old_root,
+                    old_len + 1,
+                    &new_root,
+                    new_len,
+                    &proof
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn changed_leafs_break_consistency() -> eyre::Result<()> {
+    let old = tree(13);
+    for index in 0..13 {
+        let mut new = tree(40);
+        new.update(index, 1000)?;
+
+        let proof = new.consistency_proof(13)?;
+        assert!(!verify_consistency(
+            &old.root(),
+            13,
+            &new.root(),
+            40,
+            &proof
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn consistency_needs_rfc6962_shape() {
+    let tree = MerkleTree::from(vec![1u32, 2, 3]);
+    assert_eq!(
+        tree.consistency_proof(2).unwrap_err(),
+        MerkleError::UnsupportedPadding(PaddingStrategy::DefaultValue)
+    );
+
+    let tree = self::tree(3);
+    for old_len in [0, 4] {
+        assert_eq!(
+            tree.consistency_proof(old_len).unwrap_err(),
+            MerkleError::ConsistencySize {
+                old_len,
+                new_len: 3
+            }
+        );
+    }
+}
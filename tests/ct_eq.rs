@@ -0,0 +1,47 @@
+use merkle::{
+    hash::{ct_eq, Digest},
+    MerkleTree, Root,
+};
+
+#[test]
+fn equal_and_unequal_digests() {
+    assert!(7u64.ct_eq(&7));
+    assert!(!7u64.ct_eq(&8));
+    assert!(!0u64.ct_eq(&(1 << 63)));
+
+    let digest = [0xab; 32];
+    assert!(digest.ct_eq(&[0xab; 32]));
+    for byte in [0, 15, 31] {
+        let mut other = digest;
+        other[byte] ^= 1;
+        assert!(!digest.ct_eq(&other));
+    }
+}
+
+#[test]
+fn equal_and_unequal_roots() {
+    assert!(Root(3u64).ct_eq(&Root(3)));
+    assert!(!Root(3u64).ct_eq(&Root(4)));
+    assert!(Root([1u8; 32]).ct_eq(&Root([1; 32])));
+    assert!(!Root([1u8; 32]).ct_eq(&Root([2; 32])));
+}
+
+#[test]
+fn byte_strings_of_other_lengths_differ() {
+    assert!(ct_eq(b"", b""));
+    assert!(ct_eq(b"abc", b"abc"));
+    assert!(!ct_eq(b"abc", b"abd"));
+    assert!(!ct_eq(b"abc", b"ab"));
+}
+
+#[test]
+fn verification_still_decides() -> eyre::Result<()> {
+    let tree = MerkleTree::from((0..9u32).collect::<Vec<_>>());
+    for index in 0..tree.len() {
+        let mut proof = tree.get_proof(index)?;
+        assert!(proof.verify());
+        proof.root.0 ^= 1;
+        assert!(!proof.verify());
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use merkle::{MerkleError, MerkleTree, PaddingStrategy};
+
+#[test]
+fn finds_changed_leafs() -> eyre::Result<()> {
+    let values: Vec<u64> = (0..100_000).collect();
+    // indices of a fixed xorshift sequence, sorted
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    let mut changed: Vec<usize> = (0..3)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 100_000) as usize
+        })
+        .collect();
+    changed.sort_unstable();
+
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        let old = MerkleTree::from(values.clone()).with_padding(padding);
+        let mut mutated = values.clone();
+        for &index in &changed {
+            mutated[index] += 1_000_000;
+        }
+        let new = MerkleTree::from(mutated).with_padding(padding);
+
+        assert_eq!(old.diff(&new)?, changed, "{padding:?}");
+        assert_eq!(new.diff(&old)?, changed);
+        assert_eq!(old.diff(&old)?, Vec::<usize>::new());
+        assert_eq!(old.finalize().diff(&new.finalize())?, changed);
+    }
+    Ok(())
+}
+
+#[test]
+fn edges_of_the_tree() -> eyre::Result<()> {
+    for len in 1..=17u32 {
+        let old = MerkleTree::from((0..len).collect::<Vec<_>>());
+        for index in [0, len as usize / 2, len as usize - 1] {
+            let mut values: Vec<u32> = (0..len).collect();
+            values[index] = 100;
+            assert_eq!(old.diff(&MerkleTree::from(values))?, [index]);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn different_shapes() {
+    let old = MerkleTree::from(vec![1u32, 2, 3]);
+    let longer = MerkleTree::from(vec![1u32, 2, 3, 4]);
+    assert_eq!(
+        old.diff(&longer).err(),
+        Some(MerkleError::LengthMismatch { len: 3, other: 4 })
+    );
+
+    let promoted = MerkleTree::from(vec![1u32, 2, 3]).with_padding(PaddingStrategy::PromoteOdd);
+    assert_eq!(
+        old.diff(&promoted).err(),
+        Some(MerkleError::PaddingMismatch {
+            padding: PaddingStrategy::DefaultValue,
+            other: PaddingStrategy::PromoteOdd,
+        })
+    );
+}
@@ -0,0 +1,177 @@
+#![cfg(feature = "disk")]
+
+use std::{io::ErrorKind, path::PathBuf};
+
+use merkle::{
+    hash::{DefaultBuildHasher, LeafHasher, MerkleHasher},
+    DiskMerkleTree, HashedMerkleTree, MerkleError, MerkleTree, PaddingStrategy,
+};
+
+const PADDINGS: [PaddingStrategy; 3] = [
+    PaddingStrategy::DefaultValue,
+    PaddingStrategy::DuplicateLast,
+    PaddingStrategy::PromoteOdd,
+];
+
+fn temp(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("merkle-disk-{}-{name}", std::process::id()))
+}
+
+fn leafs(len: usize) -> Vec<u64> {
+    (0..len as u64)
+        .map(|i| DefaultBuildHasher::default().hash_leaf(&i))
+        .collect()
+}
+
+fn empty() -> u64 {
+    DefaultBuildHasher::default().hash_padding()
+}
+
+fn on_disk(name: &str, leafs: &[u64], padding: PaddingStrategy) -> eyre::Result<DiskMerkleTree> {
+    let mut tree = DiskMerkleTree::create(temp(name), DefaultBuildHasher::default(), empty())?
+        .with_padding(padding);
+    for leaf in leafs {
+        tree.add_leaf_hash(*leaf)?;
+    }
+    Ok(tree)
+}
+
+fn in_memory(leafs: &[u64], padding: PaddingStrategy) -> HashedMerkleTree {
+    MerkleTree::<u64>::from_leaf_hashes(leafs.to_vec()).with_padding(padding)
+}
+
+#[test]
+fn matches_in_memory() -> eyre::Result<()> {
+    for len in [0, 1, 2, 3, 65535, 65536, 65537, 200_001] {
+        let leafs = leafs(len);
+        for padding in PADDINGS {
+            let name = format!("{len}-{padding:?}");
+            let disk = on_disk(&name, &leafs, padding)?;
+            let memory = in_memory(&leafs, padding);
+            assert_eq!(disk.len(), len);
+            assert_eq!(disk.root()?, memory.root(), "{len} {padding:?}");
+
+            for index in [0, 1, len / 2, 65535, 65536, len.saturating_sub(1)] {
+                if index >= len {
+                    continue;
+                }
+                let proof = disk.get_proof(index)?;
+                assert!(proof.verify());
+                assert_eq!(
+                    proof.to_bytes(),
+                    memory.get_proof(index)?.to_bytes(),
+                    "{len} {padding:?} {index}"
+                );
+            }
+            let last = memory.get_proof(0).map_or(1, |proof| proof.width) - 1;
+            if last >= len {
+                let proof = disk.get_padding_proof(last)?;
+                assert!(proof.verify());
+                assert_eq!(
+                    proof.to_bytes(),
+                    memory.get_padding_proof(last)?.to_bytes(),
+                    "{len} {padding:?} {last}"
+                );
+            }
+            std::fs::remove_file(temp(&name))?;
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn millions_of_leafs() -> eyre::Result<()> {
+    let leafs = leafs(3_000_000);
+    let mut disk = on_disk("millions", &leafs, PaddingStrategy::DefaultValue)?;
+    disk.flush()?;
+    assert_eq!(std::fs::metadata(temp("millions"))?.len(), 3_000_000 * 8);
+
+    let memory = in_memory(&leafs, PaddingStrategy::DefaultValue);
+    assert_eq!(disk.root()?, memory.root());
+    for index in [0, 1_234_567, 2_999_999] {
+        assert_eq!(
+            disk.get_proof(index)?.to_bytes(),
+            memory.get_proof(index)?.to_bytes()
+        );
+    }
+    assert_eq!(
+        disk.get_padding_proof(4_194_303)?.to_bytes(),
+        memory.get_padding_proof(4_194_303)?.to_bytes()
+    );
+
+    // the prefix of a tree is a tree of its own
+    let prefix = in_memory(&leafs[..1_000_000], PaddingStrategy::DefaultValue);
+    let disk = on_disk("prefix", &leafs[..1_000_000], PaddingStrategy::DefaultValue)?;
+    assert_eq!(disk.root()?, prefix.root());
+    assert!(disk.get_proof(999_999)?.verify());
+
+    std::fs::remove_file(temp("millions"))?;
+    std::fs::remove_file(temp("prefix"))?;
+    Ok(())
+}
+
+#[test]
+fn reopen() -> eyre::Result<()> {
+    let leafs = leafs(150_000);
+    let path = temp("reopen");
+    drop(on_disk(
+        "reopen",
+        &leafs[..100_000],
+        PaddingStrategy::PromoteOdd,
+    )?);
+
+    let mut tree = DiskMerkleTree::open(&path, DefaultBuildHasher::default(), empty())?
+        .with_padding(PaddingStrategy::PromoteOdd);
+    assert_eq!(tree.len(), 100_000);
+    assert_eq!(
+        tree.root()?,
+        in_memory(&leafs[..100_000], PaddingStrategy::PromoteOdd).root()
+    );
+
+    for leaf in &leafs[100_000..] {
+        tree.add_leaf_hash(*leaf)?;
+    }
+    let memory = in_memory(&leafs, PaddingStrategy::PromoteOdd);
+    assert_eq!(tree.root()?, memory.root());
+    assert_eq!(
+        tree.get_proof(99_999)?.to_bytes(),
+        memory.get_proof(99_999)?.to_bytes()
+    );
+    drop(tree);
+    assert_eq!(std::fs::metadata(&path)?.len(), 150_000 * 8);
+
+    std::fs::write(&path, [0; 12])?;
+    let err = DiskMerkleTree::open(&path, DefaultBuildHasher::default(), empty())
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn index_out_of_bounds() -> eyre::Result<()> {
+    let tree = on_disk("bounds", &leafs(5), PaddingStrategy::DefaultValue)?;
+    let merkle_error = |err: std::io::Error| {
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        err.get_ref()
+            .and_then(|err| err.downcast_ref::<MerkleError>())
+            .cloned()
+    };
+    assert_eq!(
+        merkle_error(tree.get_proof(5).err().unwrap()),
+        Some(MerkleError::IndexOutOfBounds { index: 5, len: 5 })
+    );
+    assert!(tree.get_padding_proof(5)?.verify());
+    assert_eq!(
+        merkle_error(tree.get_padding_proof(4).err().unwrap()),
+        Some(MerkleError::NotPadding { index: 4, len: 5 })
+    );
+    assert_eq!(
+        merkle_error(tree.get_padding_proof(8).err().unwrap()),
+        Some(MerkleError::IndexOutOfBounds { index: 8, len: 8 })
+    );
+    drop(tree);
+    std::fs::remove_file(temp("bounds"))?;
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use merkle::{
+    hash::{DefaultBuildHasher, Domain},
+    verify::verify_path,
+    MerkleTree,
+};
+
+fn values() -> Vec<u32> {
+    (0..9).collect()
+}
+
+#[test]
+fn domains_change_roots() -> eyre::Result<()> {
+    let plain = MerkleTree::from(values());
+    let first = MerkleTree::with_domain(values(), b"my-app-v1");
+    let second = MerkleTree::with_domain(values(), b"my-app-v2");
+
+    assert_ne!(first.root(), second.root());
+    assert_ne!(first.root(), plain.root());
+    assert_eq!(
+        first.root(),
+        MerkleTree::with_domain(values(), b"my-app-v1").root()
+    );
+    Ok(())
+}
+
+#[test]
+fn proofs_do_not_replay_across_domains() -> eyre::Result<()> {
+    let tree = MerkleTree::with_domain(values(), b"my-app-v1");
+    let other = Domain::new(DefaultBuildHasher::default(), b"my-app-v2");
+
+    for index in 0..tree.len() {
+        let mut proof = tree.get_proof(index)?;
+        assert!(proof.verify());
+        assert!(!verify_path(&other, &proof.leaf, &proof.path, &proof.root));
+
+        proof.hasher = other.clone();
+        assert!(!proof.verify());
+    }
+    Ok(())
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn wraps_any_backend() -> eyre::Result<()> {
+    use merkle::hash::{LeafHasher, MerkleHasher, Sha256};
+
+    let domain = Domain::new(Sha256, b"tag");
+    let key = Sha256.hash_leaf(b"tag");
+    let leaf = Sha256.hash_children(&[key, Sha256.hash_leaf(b"a")]);
+    assert_eq!(domain.hash_leaf(b"a"), leaf);
+    assert_eq!(
+        domain.hash_siblings(&leaf, &leaf),
+        Sha256.hash_children(&[key, leaf, leaf])
+    );
+
+    let values = ["a", "b", "c"].map(|s| s.as_bytes().to_vec()).to_vec();
+    let tree = MerkleTree::with_hasher(values.clone(), Domain::new(Sha256, b"tag"));
+    assert_ne!(tree.root(), MerkleTree::with_hasher(values, Sha256).root());
+    assert!(tree.get_proof(2)?.verify_against(&tree.root()));
+    Ok(())
+}
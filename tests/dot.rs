@@ -0,0 +1,65 @@
+use merkle::{MerkleTree, PaddingStrategy};
+
+fn nodes(dot: &str) -> usize {
+    dot.lines().filter(|line| line.contains("[label=")).count()
+}
+
+#[test]
+fn one_node_per_hash() -> eyre::Result<()> {
+    for len in [1, 2, 3, 5, 8] {
+        let tree = MerkleTree::from((0..len).collect::<Vec<u32>>());
+        let dot = tree.to_dot();
+        assert!(dot.starts_with("digraph merkle {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert_eq!(nodes(&dot), 2 * tree.padded_len() - 1);
+        // every node but the root has exactly one parent
+        assert_eq!(dot.matches(" -> ").count(), 2 * tree.padded_len() - 2);
+
+        let root = tree.root().to_string();
+        let top = tree.height();
+        let node = format!("n{top}_0 [");
+        let line = dot.lines().find(|line| line.contains(&node)).unwrap();
+        assert!(line.contains(&root[..8]));
+    }
+    Ok(())
+}
+
+#[test]
+fn leafs_are_annotated() -> eyre::Result<()> {
+    let tree = MerkleTree::from(vec![1u32, 2, 3]);
+    let dot = tree.to_dot();
+    assert!(dot.contains("n0_0 [label=\"#0\\n"));
+    assert!(dot.contains("n0_2 [label=\"#2\\n"));
+    let padding = dot.lines().find(|line| line.contains("n0_3 [")).unwrap();
+    assert!(padding.contains("#3 padding"));
+    assert!(padding.contains("style=\"dashed\""));
+    assert_eq!(dot.matches("padding").count(), 1);
+    Ok(())
+}
+
+#[test]
+fn proof_path_is_highlighted() -> eyre::Result<()> {
+    for padding in [PaddingStrategy::DefaultValue, PaddingStrategy::PromoteOdd] {
+        let tree = MerkleTree::from((0..6).collect::<Vec<u32>>()).with_padding(padding);
+        for index in 0..tree.len() {
+            let proof = tree.get_proof(index)?;
+            let dot = tree.to_dot_with_proof(&proof);
+            assert_eq!(nodes(&dot), nodes(&tree.to_dot()));
+            assert_eq!(
+                dot.matches("fillcolor=orange").count(),
+                proof.path.len(),
+                "{padding:?} {index}"
+            );
+            // the leaf, one node per level above it and the root
+            assert_eq!(
+                dot.matches("fillcolor=lightblue").count(),
+                tree.height() + 1
+            );
+            assert!(dot.contains(&format!("n0_{index} [label=\"#{index}\\n")));
+        }
+    }
+    assert!(!MerkleTree::from(vec![1u32, 2])
+        .to_dot()
+        .contains("fillcolor"));
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use merkle::{
+    hash::{DefaultBuildHasher, LeafHasher, MerkleHasher},
+    EmptyHashes, MerkleError, MerkleTree, Root,
+};
+
+/// the root of `leafs` padded with `empty`, hashing every node
+fn naive_root(mut nodes: Vec<u64>, empty: u64) -> u64 {
+    let hasher = DefaultBuildHasher::default();
+    nodes.resize(nodes.len().next_power_of_two().max(1), empty);
+    while nodes.len() > 1 {
+        nodes = nodes
+            .chunks(2)
+            .map(|pair| hasher.hash_siblings(&pair[0], &pair[1]))
+            .collect();
+    }
+    nodes[0]
+}
+
+#[test]
+fn table() {
+    let hasher = DefaultBuildHasher::default();
+    let table = MerkleTree::<u32>::empty_subtree_hashes(3);
+    assert_eq!(table.height(), 3);
+    assert_eq!(table[0], hasher.hash_padding());
+    assert_eq!(
+        table.get(1),
+        Some(&hasher.hash_siblings(&table[0], &table[0]))
+    );
+    assert_eq!(table[3], naive_root(vec![table[0]; 8], table[0]));
+    assert_eq!(table.get(4), None);
+
+    let mut grown = MerkleTree::<u32>::empty_subtree_hashes(0);
+    grown.extend_to(&hasher, 3);
+    assert_eq!(grown, table);
+    grown.extend_to(&hasher, 1);
+    assert_eq!(grown, table);
+}
+
+#[test]
+fn padded_roots_match_naive_construction() -> eyre::Result<()> {
+    let hasher = DefaultBuildHasher::default();
+    let empty = hasher.hash_padding();
+    for len in (0..=70).chain([524_289]) {
+        let values: Vec<u64> = (1..=len).collect();
+        let leafs: Vec<u64> = values.iter().map(|value| hasher.hash_leaf(value)).collect();
+        let expected = Root(naive_root(leafs.clone(), empty));
+
+        let tree = MerkleTree::from(values);
+        assert_eq!(tree.root(), expected, "{len}");
+        assert_eq!(MerkleTree::<u64>::from_leaf_hashes(leafs).root(), expected);
+        if len > 0 {
+            assert!(tree
+                .get_proof(len as usize - 1)?
+                .verify_against(&tree.root()));
+            if tree.padded_len() > tree.len() {
+                assert!(tree.get_padding_proof(tree.padded_len() - 1)?.verify());
+            }
+            assert_eq!(
+                tree.root_at(len as usize - 1)?.0,
+                naive_root(tree.leaf_hashes().take(len as usize - 1).collect(), empty)
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn bytes_round_trip() -> eyre::Result<()> {
+    let table = MerkleTree::<u32>::empty_subtree_hashes(20);
+    let bytes = table.to_bytes();
+    assert_eq!(bytes.len(), 2 + 21 * 8);
+    assert_eq!(EmptyHashes::<u64>::from_bytes(&bytes)?, table);
+
+    assert_eq!(
+        EmptyHashes::<u64>::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(MerkleError::Truncated {
+            needed: 168,
+            available: 167
+        })
+    );
+    let mut longer = bytes.clone();
+    longer.push(0);
+    assert_eq!(
+        EmptyHashes::<u64>::from_bytes(&longer),
+        Err(MerkleError::TrailingBytes(1))
+    );
+    assert_eq!(
+        EmptyHashes::<[u8; 32]>::from_bytes(&bytes),
+        Err(MerkleError::InvalidEncoding(
+            "table was written with another hash backend"
+        ))
+    );
+    Ok(())
+}
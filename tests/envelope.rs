@@ -0,0 +1,100 @@
+use merkle::{
+    hash::DefaultBuildHasher, proof::PROOF_FORMAT_VERSION, MerkleError, MerkleProof, MerkleTree,
+};
+
+type Proof = MerkleProof<DefaultBuildHasher>;
+
+#[test]
+fn encoded_proofs_round_trip() -> eyre::Result<()> {
+    let tree = MerkleTree::from((0..13u32).collect::<Vec<_>>());
+    for index in [0, 6, 12] {
+        let proof = tree.get_proof(index)?;
+        let encoded = proof.encode();
+        assert!(encoded.starts_with(b"MRKP\x01"));
+        assert!(encoded.ends_with(&proof.to_bytes()));
+
+        let decoded = Proof::decode(&encoded)?;
+        assert!(decoded.verify());
+        assert_eq!(decoded, proof);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn version_1_fixture() -> eyre::Result<()> {
+    use merkle::{hash::Sha256, Direction, LeafHash, PaddingStrategy, Root, Step};
+
+    let fixture = include_bytes!("fixtures/proof.v1.bin");
+    let proof = MerkleProof::<Sha256>::decode(fixture)?;
+    let expected = MerkleProof {
+        path: vec![Step {
+            direction: Direction::Left,
+            value: [0x11; 32],
+        }],
+        root: Root([0x01; 32]),
+        leaf: LeafHash([0xaa; 32]),
+        index: 1,
+        width: 2,
+        padding: PaddingStrategy::DefaultValue,
+        hasher: Sha256,
+    };
+    assert_eq!(proof, expected);
+    assert_eq!(expected.encode(), fixture);
+    Ok(())
+}
+
+#[test]
+fn future_versions_are_rejected() -> eyre::Result<()> {
+    let tree = MerkleTree::from((0..4u32).collect::<Vec<_>>());
+    let mut encoded = tree.get_proof(1)?.encode();
+    encoded[4] = PROOF_FORMAT_VERSION + 1;
+
+    assert_eq!(
+        Proof::decode(&encoded).err(),
+        Some(MerkleError::UnsupportedVersion {
+            version: PROOF_FORMAT_VERSION + 1,
+            latest: PROOF_FORMAT_VERSION
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn other_backends_are_rejected() -> eyre::Result<()> {
+    // same digest length, other hashes
+    let tree = MerkleTree::with_domain((0..4u32).collect(), b"other");
+    let encoded = tree.get_proof(1)?.encode();
+    assert_eq!(
+        Proof::decode(&encoded).err(),
+        Some(MerkleError::WrongBackend)
+    );
+
+    #[cfg(feature = "sha256")]
+    {
+        let tree = MerkleTree::with_hasher(vec!["a", "b"], merkle::hash::Sha256);
+        let encoded = tree.get_proof(1)?.encode();
+        assert_eq!(
+            Proof::decode(&encoded).err(),
+            Some(MerkleError::WrongBackend)
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn raw_proofs_are_not_encoded_proofs() -> eyre::Result<()> {
+    let tree = MerkleTree::from((0..4u32).collect::<Vec<_>>());
+    let proof = tree.get_proof(1)?;
+    assert_eq!(
+        Proof::decode(&proof.to_bytes()).err(),
+        Some(MerkleError::InvalidEncoding("not an encoded proof"))
+    );
+    assert_eq!(Proof::from_bytes(&proof.to_bytes())?, proof);
+
+    let encoded = proof.encode();
+    for len in 0..encoded.len() {
+        assert!(Proof::decode(&encoded[..len]).is_err());
+    }
+    Ok(())
+}
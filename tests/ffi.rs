@@ -0,0 +1,102 @@
+#![cfg(feature = "ffi")]
+
+use std::ptr;
+
+use merkle::{
+    ffi::*,
+    hash::{DefaultBuildHasher, Sha256},
+    MerkleProof, MerkleTree, Root,
+};
+
+#[test]
+fn build_prove_and_verify() {
+    unsafe {
+        let tree = merkle_tree_new();
+        assert!(!tree.is_null());
+        for value in ["a", "b", "c", "d", "e"] {
+            assert_eq!(
+                merkle_tree_add_bytes(tree, value.as_ptr(), value.len()),
+                MERKLE_OK
+            );
+        }
+
+        let mut root = [0u8; 32];
+        assert_eq!(merkle_tree_root(tree, root.as_mut_ptr()), MERKLE_OK);
+        let expected = MerkleTree::with_hasher(
+            ["a", "b", "c", "d", "e"]
+                .map(|value| value.as_bytes().to_vec())
+                .to_vec(),
+            Sha256,
+        );
+        assert_eq!(Root(root), expected.root());
+
+        let (mut data, mut len) = (ptr::null_mut(), 0);
+        assert_eq!(merkle_tree_proof(tree, 3, &mut data, &mut len), MERKLE_OK);
+        let bytes = std::slice::from_raw_parts(data, len).to_vec();
+        assert_eq!(
+            MerkleProof::<Sha256>::from_bytes(&bytes).unwrap().root,
+            Root(root)
+        );
+        assert_eq!(merkle_proof_verify(data, len), MERKLE_OK);
+        merkle_bytes_free(data, len);
+
+        let mut tampered = bytes.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert_eq!(
+            merkle_proof_verify(tampered.as_ptr(), tampered.len()),
+            MERKLE_INVALID
+        );
+        assert_eq!(merkle_proof_verify(bytes.as_ptr(), 10), MERKLE_MALFORMED);
+
+        merkle_tree_free(tree);
+    }
+}
+
+#[test]
+fn error_codes() {
+    unsafe {
+        let tree = merkle_tree_new();
+        assert_eq!(
+            merkle_tree_add_bytes(ptr::null_mut(), ptr::null(), 0),
+            MERKLE_NULL_POINTER
+        );
+        assert_eq!(
+            merkle_tree_add_bytes(tree, ptr::null(), 3),
+            MERKLE_NULL_POINTER
+        );
+        assert_eq!(merkle_tree_add_bytes(tree, ptr::null(), 0), MERKLE_OK);
+        assert_eq!(merkle_tree_root(tree, ptr::null_mut()), MERKLE_NULL_POINTER);
+        assert_eq!(
+            merkle_tree_root(ptr::null(), [0u8; 32].as_mut_ptr()),
+            MERKLE_NULL_POINTER
+        );
+
+        let (mut data, mut len) = (ptr::null_mut(), 0);
+        assert_eq!(
+            merkle_tree_proof(tree, 1, &mut data, &mut len),
+            MERKLE_OUT_OF_BOUNDS
+        );
+        assert_eq!(
+            merkle_tree_proof(tree, 0, ptr::null_mut(), &mut len),
+            MERKLE_NULL_POINTER
+        );
+        assert!(data.is_null());
+        assert_eq!(merkle_proof_verify(ptr::null(), 5), MERKLE_NULL_POINTER);
+
+        // proofs of other backends don't decode as sha-256 ones
+        let other = MerkleTree::from(vec![1u32, 2])
+            .get_proof(0)
+            .unwrap()
+            .to_bytes();
+        assert_eq!(
+            merkle_proof_verify(other.as_ptr(), other.len()),
+            MERKLE_MALFORMED
+        );
+        let _ = MerkleProof::<DefaultBuildHasher>::from_bytes(&other).unwrap();
+
+        merkle_bytes_free(ptr::null_mut(), 0);
+        merkle_tree_free(ptr::null_mut());
+        merkle_tree_free(tree);
+    }
+}
@@ -0,0 +1,240 @@
+use std::{hash::Hasher, io::ErrorKind, path::PathBuf};
+
+use merkle::{
+    hash::{DefaultBuildHasher, FnvHasher, LeafHasher, MerkleHasher},
+    HashedMerkleTree, MerkleError, MerkleTree, PaddingStrategy,
+};
+
+fn temp(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("merkle-{}-{name}.tree", std::process::id()))
+}
+
+fn merkle_error(err: &std::io::Error) -> Option<&MerkleError> {
+    err.get_ref()?.downcast_ref()
+}
+
+#[test]
+fn round_trip() -> eyre::Result<()> {
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        let tree = MerkleTree::from((0..1000).collect::<Vec<u32>>()).with_padding(padding);
+        let path = temp(&format!("{padding:?}"));
+        tree.save(&path)?;
+        let mut loaded = MerkleTree::<u32>::load(&path)?;
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(loaded.len(), 1000);
+        assert_eq!(loaded.padding(), padding);
+        assert_eq!(loaded.root(), tree.root());
+        let proof = loaded.get_proof(321)?;
+        assert!(proof.verify_against(&tree.root()));
+
+        // the loaded tree keeps growing like the original
+        let grown = MerkleTree::from((0..1001).collect::<Vec<u32>>()).with_padding(padding);
+        loaded.add_hash(grown.leafs()[1000]);
+        assert_eq!(loaded.root(), grown.root());
+    }
+    Ok(())
+}
+
+#[test]
+fn round_trip_with_levels() -> eyre::Result<()> {
+    let tree = MerkleTree::from((0..37).collect::<Vec<u32>>()).finalize();
+    let bytes = tree.to_bytes_with_levels();
+    assert!(bytes.len() > tree.to_bytes().len());
+
+    let loaded: HashedMerkleTree = HashedMerkleTree::from_bytes(&bytes)?;
+    assert_eq!(loaded.root(), tree.root());
+    assert_eq!(loaded.len(), 37);
+
+    let path = temp("levels");
+    tree.save_with_levels(&path)?;
+    let loaded: HashedMerkleTree = HashedMerkleTree::load(&path)?;
+    std::fs::remove_file(&path)?;
+    assert_eq!(loaded.root(), tree.root());
+    assert!(loaded.get_proof(36)?.verify_against(&loaded.root()));
+    Ok(())
+}
+
+#[test]
+fn empty_tree() -> eyre::Result<()> {
+    let tree = MerkleTree::<u32>::from(vec![]).finalize();
+    let loaded: HashedMerkleTree = HashedMerkleTree::from_bytes(&tree.to_bytes())?;
+    assert!(loaded.is_empty());
+    assert_eq!(loaded.root(), tree.root());
+    let loaded: HashedMerkleTree = HashedMerkleTree::from_bytes(&tree.to_bytes_with_levels())?;
+    assert_eq!(loaded.root(), tree.root());
+    Ok(())
+}
+
+#[test]
+fn corruption_is_detected() -> eyre::Result<()> {
+    let tree = MerkleTree::from((0..10).collect::<Vec<u32>>());
+    let path = temp("corrupt");
+    tree.save(&path)?;
+    let mut bytes = std::fs::read(&path)?;
+    bytes[40] ^= 1;
+    std::fs::write(&path, &bytes)?;
+
+    let err = MerkleTree::<u32>::load(&path).err().unwrap();
+    std::fs::remove_file(&path)?;
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        merkle_error(&err),
+        Some(&MerkleError::InvalidEncoding("checksum mismatch"))
+    );
+    Ok(())
+}
+
+#[test]
+fn incompatible_files() -> eyre::Result<()> {
+    let bytes = MerkleTree::from(vec![1u32, 2, 3]).finalize().to_bytes();
+    let decode =
+        |bytes: &[u8]| HashedMerkleTree::<merkle::hash::DefaultBuildHasher>::from_bytes(bytes);
+
+    assert_eq!(
+        decode(b"not a tree").err(),
+        Some(MerkleError::InvalidEncoding("not a merkle tree file"))
+    );
+    let mut newer = bytes.clone();
+    newer[4] = 2;
+    assert_eq!(
+        decode(&newer).err(),
+        Some(MerkleError::InvalidEncoding("unsupported file version"))
+    );
+    assert_eq!(
+        decode(&bytes[..bytes.len() - 1]).err(),
+        Some(MerkleError::InvalidEncoding("checksum mismatch"))
+    );
+
+    // same digest size, another backend
+    let fnv =
+        HashedMerkleTree::<std::hash::BuildHasherDefault<merkle::hash::FnvHasher>>::from_bytes(
+            &bytes,
+        );
+    assert_eq!(
+        fnv.err(),
+        Some(MerkleError::InvalidEncoding(
+            "file was written with another hash backend"
+        ))
+    );
+
+    // same backend, padded with the hash of a default value like before
+    // padding leafs were told apart from values
+    let hasher = DefaultBuildHasher::default();
+    let empty = hasher.hash_leaf(&0u32);
+    let mut bytes = MerkleTree::from(vec![1u32, 2, 3]).finalize().to_bytes();
+    bytes.truncate(bytes.len() - 8);
+    bytes[9..17].copy_from_slice(&empty.to_be_bytes());
+    bytes[17..25].copy_from_slice(&hasher.hash_siblings(&empty, &empty).to_be_bytes());
+    let mut checksum = FnvHasher::default();
+    checksum.write(&bytes);
+    bytes.extend_from_slice(&checksum.finish().to_be_bytes());
+
+    let path = temp("padding");
+    std::fs::write(&path, &bytes)?;
+    let err = MerkleTree::<u32>::load(&path).err().unwrap();
+    std::fs::remove_file(&path)?;
+    assert_eq!(
+        merkle_error(&err),
+        Some(&MerkleError::InvalidEncoding(
+            "file was written with another padding leaf"
+        ))
+    );
+    assert_eq!(
+        HashedMerkleTree::<DefaultBuildHasher>::from_bytes(&bytes)?.len(),
+        3
+    );
+    assert_eq!(
+        MerkleTree::<u32>::load(temp("missing"))
+            .err()
+            .unwrap()
+            .kind(),
+        ErrorKind::NotFound
+    );
+    Ok(())
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn digest_size_is_checked() -> eyre::Result<()> {
+    use merkle::hash::Sha256;
+
+    let values = vec![b"a".to_vec(), b"b".to_vec()];
+    let bytes = MerkleTree::with_hasher(values, Sha256)
+        .finalize()
+        .to_bytes();
+    assert!(HashedMerkleTree::<Sha256>::from_bytes(&bytes).is_ok());
+    assert_eq!(
+        HashedMerkleTree::<merkle::hash::DefaultBuildHasher>::from_bytes(&bytes).err(),
+        Some(MerkleError::InvalidEncoding(
+            "file was written with another hash backend"
+        ))
+    );
+    Ok(())
+}
+
+/// `bytes` of a file claiming to hold `len` leafs, with a fixed checksum
+fn claiming(bytes: &[u8], len: u64) -> Vec<u8> {
+    // the leaf count follows the magic, version, padding and digest size
+    let mut out = bytes[..7].to_vec();
+    let mut rest = len;
+    while rest >= 0x80 {
+        out.push(rest as u8 | 0x80);
+        rest >>= 7;
+    }
+    out.push(rest as u8);
+    out.extend_from_slice(&bytes[8..bytes.len() - 8]);
+    let mut checksum = FnvHasher::default();
+    checksum.write(&out);
+    out.extend_from_slice(&checksum.finish().to_be_bytes());
+    out
+}
+
+#[test]
+fn absurd_sizes_are_rejected() -> eyre::Result<()> {
+    use merkle::tree::MAX_LEAFS;
+
+    let tree = MerkleTree::from(vec![1u32, 2, 3]).finalize();
+    let decode = HashedMerkleTree::<DefaultBuildHasher>::from_bytes_with_max;
+    assert_eq!(decode(&claiming(&tree.to_bytes(), 3), 4)?.len(), 3);
+
+    // padding to the next power of two would overflow
+    for bytes in [tree.to_bytes(), tree.to_bytes_with_levels()] {
+        for len in [MAX_LEAFS as u64 + 1, u64::MAX] {
+            assert_eq!(
+                HashedMerkleTree::<DefaultBuildHasher>::from_bytes(&claiming(&bytes, len)).err(),
+                Some(MerkleError::TooLarge {
+                    len: len as usize,
+                    max: MAX_LEAFS
+                })
+            );
+        }
+    }
+
+    // the limit of a 32 bit target counts the padding too
+    let max = 1 << 31;
+    let bytes = tree.to_bytes_with_levels();
+    for len in [max + 1, u32::MAX as usize] {
+        assert_eq!(
+            decode(&claiming(&bytes, len as u64), max).err(),
+            Some(MerkleError::TooLarge { len, max })
+        );
+    }
+    assert!(matches!(
+        decode(&claiming(&bytes, max as u64 - 1), max),
+        Err(MerkleError::Truncated { .. })
+    ));
+    assert_eq!(
+        decode(&bytes, 3).err(),
+        Some(MerkleError::TooLarge { len: 3, max: 3 })
+    );
+    let unpadded = MerkleTree::from(vec![1u32, 2, 3])
+        .with_padding(PaddingStrategy::PromoteOdd)
+        .finalize();
+    assert_eq!(decode(&unpadded.to_bytes_with_levels(), 3)?.len(), 3);
+    Ok(())
+}
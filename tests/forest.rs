@@ -0,0 +1,77 @@
+use merkle::{ChainedProof, MerkleError, MerkleForest, MerkleTree, ProofError, Root};
+
+/// a week of daily trees of different sizes
+fn week() -> Vec<MerkleTree<u32>> {
+    (0..7u32)
+        .map(|day| (day * 100..day * 100 + 3 + day * 2).collect())
+        .collect()
+}
+
+#[test]
+fn chained_proofs_verify() -> eyre::Result<()> {
+    let days = week();
+    let forest = MerkleForest::from_trees(&days);
+    assert_eq!(forest.len(), 7);
+    let weekly = forest.root();
+    assert_eq!(
+        weekly,
+        MerkleForest::new(days.iter().map(MerkleTree::root).collect()).root()
+    );
+
+    for (day, tree) in days.iter().enumerate() {
+        for index in 0..tree.len() {
+            let proof = forest.chain(day, tree.get_proof(index)?)?;
+            assert_eq!(proof.tree_index(), day);
+            assert!(proof.verify(&weekly));
+            assert!(!proof.verify(&Root(weekly.0 ^ 1)));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn swapped_proofs_fail() -> eyre::Result<()> {
+    let days = week();
+    let forest = MerkleForest::from_trees(&days);
+    let weekly = forest.root();
+
+    let monday = forest.chain(0, days[0].get_proof(1)?)?;
+    let tuesday = forest.chain(1, days[1].get_proof(1)?)?;
+
+    let swapped = ChainedProof {
+        inner: tuesday.inner.clone(),
+        outer: monday.outer.clone(),
+    };
+    assert!(!swapped.verify(&weekly));
+    assert_eq!(
+        swapped.verify_detailed(&weekly),
+        Err(ProofError::ChainMismatch {
+            root: days[1].root().0,
+            leaf: days[0].root().0,
+        })
+    );
+
+    assert_eq!(
+        forest.chain(0, days[1].get_proof(1)?).unwrap_err(),
+        MerkleError::NotInForest { index: 0 }
+    );
+    assert_eq!(
+        forest.chain(7, days[1].get_proof(1)?).unwrap_err(),
+        MerkleError::IndexOutOfBounds { index: 7, len: 7 }
+    );
+    Ok(())
+}
+
+#[test]
+fn tampered_inner_proof_fails() -> eyre::Result<()> {
+    let days = week();
+    let forest = MerkleForest::from_trees(&days);
+
+    let mut proof = forest.chain(3, days[3].get_proof(4)?)?;
+    proof.inner.path[0].value ^= 1;
+    assert!(matches!(
+        proof.verify_detailed(&forest.root()),
+        Err(ProofError::RootMismatch { .. })
+    ));
+    Ok(())
+}
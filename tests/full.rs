@@ -0,0 +1,81 @@
+use merkle::{FullMerkleTree, MerkleError, MerkleTree};
+
+/// a fixed pseudo random sequence, the same on every run
+fn random(seed: u64) -> impl Iterator<Item = u64> {
+    let mut state = seed;
+    std::iter::repeat_with(move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        state >> 11
+    })
+}
+
+fn assert_same(full: &FullMerkleTree<u64>, tree: &MerkleTree<u64>) -> eyre::Result<()> {
+    assert_eq!(full.len(), tree.len());
+    assert_eq!(full.padded_len(), tree.padded_len());
+    assert_eq!(full.root(), tree.root(), "{} values", tree.len());
+    for index in 0..tree.len() {
+        assert_eq!(full.get_proof(index)?, tree.get_proof(index)?);
+    }
+    Ok(())
+}
+
+#[test]
+fn small_trees_match() -> eyre::Result<()> {
+    for len in 0..=64 {
+        let values: Vec<u64> = (0..len).collect();
+        let full = FullMerkleTree::from(values.clone());
+        assert_same(&full, &MerkleTree::from(values))?;
+        assert_eq!(full.nodes().len(), 2 * full.padded_len() - 1);
+    }
+    Ok(())
+}
+
+#[test]
+fn large_random_tree_matches() -> eyre::Result<()> {
+    let values: Vec<u64> = random(7).take(100_003).collect();
+    let mut full = FullMerkleTree::from(values.clone());
+    let mut tree = MerkleTree::from(values);
+    assert_eq!(full.root(), tree.root());
+
+    let len = tree.len();
+    let mut indices = random(8).map(|i| i as usize % len);
+    for (index, value) in indices.by_ref().zip(random(9)).take(500) {
+        full.update(index, value)?;
+        tree.update(index, value)?;
+        assert_eq!(full.root(), tree.root());
+    }
+    for index in indices.take(200) {
+        let proof = full.get_proof(index)?;
+        assert!(proof.verify());
+        assert_eq!(proof, tree.get_proof(index)?);
+    }
+    Ok(())
+}
+
+#[test]
+fn appends_match() -> eyre::Result<()> {
+    let mut full = FullMerkleTree::from(Vec::new());
+    let mut tree = MerkleTree::from(Vec::new());
+    for value in random(3).take(70) {
+        full.add(value);
+        tree.add(value);
+        assert_same(&full, &tree)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn out_of_bounds() {
+    let mut full = FullMerkleTree::from(vec![1u64, 2, 3]);
+    assert_eq!(
+        full.get_proof(3).unwrap_err(),
+        MerkleError::IndexOutOfBounds { index: 3, len: 3 }
+    );
+    assert_eq!(
+        full.update(3, 4).unwrap_err(),
+        MerkleError::IndexOutOfBounds { index: 3, len: 3 }
+    );
+    assert_eq!(full.values(), [1, 2, 3]);
+}
@@ -0,0 +1,130 @@
+//! backends that hash whole levels at once against the same backends hashing
+//! pair by pair
+
+use merkle::{
+    hash::{DefaultBuildHasher, LeafHasher, MerkleHasher},
+    MerkleTree, PaddingStrategy,
+};
+
+/// a backend hashing every pair on its own, through the default `hash_pairs`
+#[derive(Debug, Clone, Copy, Default)]
+struct Scalar<S>(S);
+
+impl<S: MerkleHasher> MerkleHasher for Scalar<S> {
+    type Digest = S::Digest;
+
+    fn hash_siblings(&self, left: &S::Digest, right: &S::Digest) -> S::Digest {
+        self.0.hash_siblings(left, right)
+    }
+
+    fn hash_padding(&self) -> S::Digest {
+        self.0.hash_padding()
+    }
+}
+
+impl<T: ?Sized, S: LeafHasher<T>> LeafHasher<T> for Scalar<S> {
+    fn hash_leaf(&self, leaf: &T) -> S::Digest {
+        self.0.hash_leaf(leaf)
+    }
+}
+
+const PADDINGS: [PaddingStrategy; 3] = [
+    PaddingStrategy::DefaultValue,
+    PaddingStrategy::DuplicateLast,
+    PaddingStrategy::PromoteOdd,
+];
+
+fn values(len: u32) -> Vec<Vec<u8>> {
+    (0..len).map(|i| i.to_be_bytes().to_vec()).collect()
+}
+
+fn check<S>(hasher: S) -> eyre::Result<()>
+where
+    S: LeafHasher<Vec<u8>> + Clone,
+{
+    let nodes: Vec<S::Digest> = values(64).iter().map(|v| hasher.hash_leaf(v)).collect();
+    let pairs: Vec<S::Digest> = nodes
+        .chunks(2)
+        .map(|pair| hasher.hash_siblings(&pair[0], &pair[1]))
+        .collect();
+    assert_eq!(hasher.hash_pairs(&nodes), pairs);
+    assert!(hasher.hash_pairs(&[]).is_empty());
+
+    for padding in PADDINGS {
+        for len in (0..=70).chain([1000, 1025]) {
+            let batched =
+                MerkleTree::with_hasher(values(len), hasher.clone()).with_padding(padding);
+            let scalar =
+                MerkleTree::with_hasher(values(len), Scalar(hasher.clone())).with_padding(padding);
+            assert_eq!(batched.root(), scalar.root(), "{len} {padding:?}");
+            if len > 0 {
+                let index = len as usize / 2;
+                assert_eq!(
+                    batched.get_proof(index)?.path,
+                    scalar.get_proof(index)?.path
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn default_backend() -> eyre::Result<()> {
+    check(DefaultBuildHasher::default())
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn sha256() -> eyre::Result<()> {
+    check(merkle::hash::Sha256)
+}
+
+#[cfg(feature = "blake3")]
+#[test]
+fn blake3() -> eyre::Result<()> {
+    check(merkle::hash::Blake3)
+}
+
+/// the time to build the levels of a tree of 2^22 leafs with whole levels
+/// hashed at once and pair by pair, sha256 was 1.1x to 1.3x faster on a single
+/// core where it was measured. run with
+/// `cargo test --release --features sha256 --test hash_pairs -- --ignored --nocapture`
+#[cfg(feature = "sha256")]
+#[test]
+#[ignore = "throughput measurement"]
+fn throughput() {
+    use std::time::Instant;
+
+    use merkle::hash::Sha256;
+
+    fn time<S: LeafHasher<[u8; 32]> + Clone>(leafs: &[[u8; 32]], hasher: S) -> f64 {
+        // leaf hashes are the same on both paths, only the levels are timed
+        let hashes: Vec<_> = leafs.iter().map(|leaf| hasher.hash_leaf(leaf)).collect();
+        (0..3)
+            .map(|_| {
+                let hashes = hashes.clone();
+                let start = Instant::now();
+                let tree =
+                    MerkleTree::<[u8; 32], S>::from_leaf_hashes_with_hasher(hashes, hasher.clone());
+                std::hint::black_box(tree.root());
+                start.elapsed().as_secs_f64()
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    let leafs: Vec<[u8; 32]> = (0..1u32 << 22)
+        .map(|i| {
+            let mut leaf = [0; 32];
+            leaf[..4].copy_from_slice(&i.to_be_bytes());
+            leaf
+        })
+        .collect();
+
+    let batched = time(&leafs, Sha256);
+    let scalar = time(&leafs, Scalar(Sha256));
+    println!(
+        "sha256: {batched:.3}s batched, {scalar:.3}s scalar, {:.2}x",
+        scalar / batched
+    );
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use merkle::{MerkleTree, PaddingStrategy};
+
+#[test]
+fn proofs_survive_finalize() -> eyre::Result<()> {
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        let values: Vec<u32> = (0..13).collect();
+        let tree = MerkleTree::from(values).with_padding(padding);
+        let root = tree.root();
+        let before: Vec<_> = (0..13)
+            .map(|i| tree.get_proof(i))
+            .collect::<Result<_, _>>()?;
+
+        let hashed = tree.finalize();
+        assert_eq!(hashed.len(), 13);
+        assert_eq!(hashed.root(), root);
+        for (index, proof) in before.iter().enumerate() {
+            let after = hashed.get_proof(index)?;
+            assert_eq!(after.leaf, proof.leaf);
+            assert_eq!(after.root, proof.root);
+            assert_eq!(after.path.len(), proof.path.len());
+            for (a, b) in after.path.iter().zip(&proof.path) {
+                assert_eq!(a.direction, b.direction);
+                assert_eq!(a.value, b.value);
+            }
+            assert!(after.verify());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn appended_hashes_match_added_values() -> eyre::Result<()> {
+    let mut tree = MerkleTree::from((0..5u32).collect::<Vec<_>>());
+    // finalize before anything was cached
+    let mut hashed = MerkleTree::from((0..5u32).collect::<Vec<_>>()).finalize();
+
+    for value in 5..40 {
+        tree.add(value);
+        let leaf = *tree.leafs().get(value as usize).unwrap();
+        hashed.add_hash(leaf);
+
+        assert_eq!(hashed.root(), tree.root());
+        let proof = hashed.get_proof(value as usize)?;
+        assert!(proof.verify());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn finalize_drops_values() {
+    let value = Arc::new(7u64);
+    let values = vec![value.clone(); 100];
+    let tree = MerkleTree::from(values);
+    let root = tree.root();
+    assert_eq!(Arc::strong_count(&value), 101);
+
+    let hashed = tree.finalize();
+    assert_eq!(Arc::strong_count(&value), 1);
+    assert_eq!(hashed.root(), root);
+}
+
+#[test]
+fn from_leaf_hashes_reproduces_the_tree() -> eyre::Result<()> {
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        let tree = MerkleTree::from((0..13u32).collect::<Vec<_>>()).with_padding(padding);
+
+        // the padded leaf layer and the bare leaf hashes both give the tree back
+        let padded =
+            MerkleTree::<u32>::from_leaf_hashes(tree.leafs().to_vec()).with_padding(padding);
+        let bare =
+            MerkleTree::<u32>::from_leaf_hashes(tree.leaf_hashes().collect()).with_padding(padding);
+        assert_eq!(padded.root(), tree.root());
+        assert_eq!(bare.root(), tree.root());
+        assert_eq!(bare.len(), 13);
+
+        for index in 0..13 {
+            let proof = bare.get_proof(index)?;
+            assert!(proof.verify());
+            assert_eq!(proof.to_bytes(), tree.get_proof(index)?.to_bytes());
+        }
+    }
+
+    let mut tree = MerkleTree::from(vec![1u32, 2, 3]);
+    let mut hashed = MerkleTree::<u32>::from_leaf_hashes(tree.leaf_hashes().collect());
+    tree.add(4);
+    hashed.add_hash(tree.leafs()[3]);
+    assert_eq!(hashed.root(), tree.root());
+
+    Ok(())
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn from_digests_of_another_backend() -> eyre::Result<()> {
+    use merkle::hash::{sha256, Sha256};
+
+    let values = ["a", "b", "c"];
+    let tree = MerkleTree::with_hasher(values.to_vec(), Sha256);
+    let digests = values
+        .map(|value| sha256::digest(value.as_bytes()))
+        .to_vec();
+    let hashed = MerkleTree::<&str, _>::from_leaf_hashes_with_hasher(digests, Sha256);
+
+    assert_eq!(hashed.root(), tree.root());
+    assert!(hashed.get_proof(2)?.verify_against(&hashed.root()));
+
+    Ok(())
+}
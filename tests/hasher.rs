@@ -0,0 +1,68 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasherDefault, Hasher},
+};
+
+use merkle::{hash::FnvHasher, MerkleProof, MerkleTree};
+
+type FnvBuildHasher = BuildHasherDefault<FnvHasher>;
+
+#[test]
+fn roots_differ_between_hashers() {
+    let values: Vec<u32> = (0..1000).collect();
+
+    let default = MerkleTree::from(values.clone());
+    let fnv = MerkleTree::with_hasher(values.clone(), FnvBuildHasher::default());
+    let seeded = MerkleTree::with_hasher(values, RandomState::new());
+
+    assert_ne!(default.root(), fnv.root());
+    assert_ne!(default.root(), seeded.root());
+    assert_ne!(fnv.root(), seeded.root());
+}
+
+#[test]
+fn proofs_verify_within_hasher() -> eyre::Result<()> {
+    let values: Vec<u32> = (0..1000).collect();
+
+    let fnv = MerkleTree::with_hasher(values.clone(), FnvBuildHasher::default());
+    let proof = fnv.get_proof(123)?;
+    assert!(proof.verify_against(&fnv.root()));
+
+    let seeded = MerkleTree::with_hasher(values, RandomState::new());
+    let proof = seeded.get_proof(321)?;
+    assert!(proof.verify_against(&seeded.root()));
+
+    Ok(())
+}
+
+#[test]
+fn mixed_hashers_fail_verification() -> eyre::Result<()> {
+    let values: Vec<u32> = (0..1000).collect();
+    let a = MerkleTree::with_hasher(values.clone(), RandomState::new());
+    let b = MerkleTree::with_hasher(values, RandomState::new());
+
+    let proof = a.get_proof(7)?;
+    assert_ne!(proof.root, b.root());
+
+    // the same path folded with the other tree's hasher does not reach the root
+    let mixed = MerkleProof {
+        hasher: b.hasher().clone(),
+        ..proof
+    };
+    assert!(!mixed.verify());
+
+    Ok(())
+}
+
+#[test]
+fn fnv_known_answers() {
+    for (input, expected) in [
+        (&b""[..], 0xcbf2_9ce4_8422_2325),
+        (b"a", 0xaf63_dc4c_8601_ec8c),
+        (b"foobar", 0x8594_4171_f739_67e8),
+    ] {
+        let mut hasher = FnvHasher::default();
+        hasher.write(input);
+        assert_eq!(hasher.finish(), expected);
+    }
+}
@@ -0,0 +1,182 @@
+//! shapes and proofs that used to reach asserts or overflows, every one of
+//! them has to be an error or a failed verification
+
+use merkle::{
+    hash::{DefaultBuildHasher, EmptyHashes},
+    verify::{path_len, verify_consistency, verify_range},
+    ConsistencyProof, Direction, LeafHash, MerkleMultiProof, MerkleProof, MerkleTree, Mmr,
+    MmrProof, PaddingStrategy, ProofError, RangeProof, Root, Step,
+};
+
+const PADDINGS: [PaddingStrategy; 3] = [
+    PaddingStrategy::DefaultValue,
+    PaddingStrategy::DuplicateLast,
+    PaddingStrategy::PromoteOdd,
+];
+
+fn proof(index: usize, width: usize, padding: PaddingStrategy, steps: usize) -> MerkleProof {
+    MerkleProof {
+        path: (0..steps)
+            .map(|i| Step {
+                direction: Direction::Left,
+                value: i as u64,
+            })
+            .collect(),
+        root: Root(1),
+        leaf: LeafHash(2),
+        index,
+        width,
+        padding,
+        hasher: DefaultBuildHasher::default(),
+    }
+}
+
+#[test]
+fn odd_hash_levels_hash() -> eyre::Result<()> {
+    for padding in PADDINGS {
+        for len in [0, 1, 3, 5, 7, 9] {
+            let hashes: Vec<u64> = (0..len).map(|i| i * 0x9e37_79b9).collect();
+            let tree = MerkleTree::<u32>::from_leaf_hashes(hashes).with_padding(padding);
+            let root = tree.root();
+
+            for index in 0..len as usize {
+                assert!(tree.get_proof(index)?.verify_against(&root));
+            }
+            let width = tree.get_proof(0).map_or(1, |proof| proof.width);
+            assert!(tree.get_proof(width).is_err());
+            assert!(tree.get_proof(usize::MAX).is_err());
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn extreme_indices_and_widths_fail() {
+    for padding in PADDINGS {
+        for (index, width) in [
+            (0, 0),
+            (usize::MAX, usize::MAX),
+            (usize::MAX - 1, usize::MAX),
+            (usize::MAX, 0),
+            (1 << 63, 1 << 63),
+        ] {
+            let _ = path_len(index, width, padding);
+            for steps in [0, 1, 64, 65] {
+                assert!(!proof(index, width, padding, steps).verify());
+            }
+        }
+    }
+
+    assert_eq!(
+        proof(usize::MAX, usize::MAX, PaddingStrategy::PromoteOdd, 0).verify_detailed(),
+        Err(ProofError::IndexOutOfBounds {
+            index: usize::MAX,
+            width: usize::MAX
+        })
+    );
+}
+
+/// on the widest trees the top level of a promoted path is 63, shifting the
+/// last index past it used to overflow
+#[test]
+fn promoted_paths_of_the_widest_trees() {
+    let padding = PaddingStrategy::PromoteOdd;
+    for index in [0, 1, 1 << 62, usize::MAX - 2, usize::MAX - 1] {
+        for steps in [path_len(index, usize::MAX, padding), 0, 63, 64, 65] {
+            let proof = proof(index, usize::MAX, padding, steps);
+            let _ = proof.implied_index();
+            assert!(!proof.verify());
+        }
+    }
+}
+
+#[test]
+fn compact_proofs_of_extreme_shapes_fail() {
+    let empty = EmptyHashes::new(&DefaultBuildHasher::default(), 0, 8);
+    for padding in PADDINGS {
+        let compact = proof(usize::MAX, usize::MAX, padding, 3).compact(&empty);
+        assert!(!compact.verify_against(&Root(1), &empty));
+    }
+}
+
+#[test]
+fn multi_proofs_of_extreme_shapes_fail() {
+    for padding in PADDINGS {
+        let proof = MerkleMultiProof {
+            leafs: vec![(usize::MAX - 2, 1), (usize::MAX - 1, 2)],
+            decommitments: vec![3; 70],
+            width: usize::MAX,
+            padding,
+            root: Root(4),
+            hasher: DefaultBuildHasher::default(),
+        };
+        assert!(!proof.verify_against(&Root(4)));
+    }
+}
+
+#[test]
+fn range_and_consistency_proofs_of_extreme_shapes_fail() {
+    for padding in PADDINGS {
+        let proof = RangeProof {
+            decommitments: vec![3; 70],
+            width: usize::MAX,
+            padding,
+            hasher: DefaultBuildHasher::default(),
+        };
+        assert!(!verify_range(&Root(1), usize::MAX - 1, &[1, 2], &proof));
+        assert!(!verify_range(&Root(1), usize::MAX - 2, &[1, 2], &proof));
+    }
+
+    let proof = ConsistencyProof {
+        path: vec![3; 70],
+        hasher: DefaultBuildHasher::default(),
+    };
+    assert!(!verify_consistency(
+        &Root(1),
+        usize::MAX - 1,
+        &Root(2),
+        usize::MAX,
+        &proof
+    ));
+    assert!(!verify_consistency(
+        &Root(1),
+        1,
+        &Root(2),
+        usize::MAX,
+        &proof
+    ));
+}
+
+#[test]
+fn mmr_proofs_with_long_paths_fail() -> eyre::Result<()> {
+    let mut mmr = Mmr::<u32>::new();
+    for value in 0..3 {
+        mmr.append(&value);
+    }
+    let mut proof = mmr.get_proof(0)?;
+    assert!(proof.verify_against(&proof.root));
+    proof.path.resize(100, 5);
+    assert!(!proof.verify_against(&proof.root));
+    Ok(())
+}
+
+#[test]
+fn mmr_proofs_of_extreme_shapes_fail() {
+    for (position, size, steps) in [
+        (usize::MAX - 1, usize::MAX, 70),
+        (0, usize::MAX, 70),
+        (0, 1 << 63, 64),
+        (0, 3, 1000),
+    ] {
+        let proof = MmrProof {
+            position,
+            size,
+            leaf: LeafHash(1),
+            path: vec![2; steps],
+            peaks: vec![3; 64],
+            root: Root(4),
+            hasher: DefaultBuildHasher::default(),
+        };
+        assert!(!proof.verify_against(&Root(4)));
+    }
+}
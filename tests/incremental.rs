@@ -0,0 +1,27 @@
+use merkle::{IncrementalMerkleTree, MerkleTree};
+
+#[test]
+fn roots_match_batch_construction() {
+    let mut incremental = IncrementalMerkleTree::new();
+    assert_eq!(incremental.root(), MerkleTree::<u64>::from(vec![]).root());
+
+    let mut values = Vec::new();
+    for value in 0..300u64 {
+        incremental.add(value);
+        values.push(value);
+
+        assert_eq!(incremental.len(), values.len());
+        assert_eq!(incremental.root(), MerkleTree::from(values.clone()).root());
+    }
+}
+
+#[test]
+fn million_appends() {
+    let mut incremental = IncrementalMerkleTree::new();
+    for value in 0..1_000_000u64 {
+        incremental.add(value);
+    }
+
+    let values: Vec<u64> = (0..1_000_000).collect();
+    assert_eq!(incremental.root(), MerkleTree::from(values).root());
+}
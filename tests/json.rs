@@ -0,0 +1,163 @@
+use merkle::{
+    hash::DefaultBuildHasher, Direction, LeafHash, MerkleError, MerkleProof, MerkleTree,
+    PaddingStrategy, Root, Step,
+};
+
+type Proof = MerkleProof<DefaultBuildHasher>;
+
+fn proof_with_steps(directions: &[Direction]) -> Proof {
+    MerkleProof {
+        path: directions
+            .iter()
+            .enumerate()
+            .map(|(i, &direction)| Step {
+                direction,
+                value: 0x1111_1111_1111_1111u64.wrapping_mul(i as u64 + 1),
+            })
+            .collect(),
+        root: Root(0x0102_0304_0506_0708),
+        leaf: LeafHash(0xa0a1_a2a3_a4a5_a6a7),
+        index: 1,
+        width: 8,
+        padding: PaddingStrategy::DefaultValue,
+        hasher: DefaultBuildHasher::default(),
+    }
+}
+
+fn json_error(json: &str) -> String {
+    match Proof::from_json(json) {
+        Ok(_) => panic!("{json} was accepted"),
+        Err(err) => err.to_string(),
+    }
+}
+
+#[test]
+fn golden_json() -> eyre::Result<()> {
+    let proof = proof_with_steps(&[Direction::Left, Direction::Right, Direction::Right]);
+    let expected = include_str!("fixtures/proof.json").trim_end();
+
+    assert_eq!(proof.to_json(), expected);
+    assert_eq!(Proof::from_json(expected)?.to_json(), expected);
+
+    Ok(())
+}
+
+#[test]
+fn round_trip() -> eyre::Result<()> {
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        let tree = MerkleTree::from((0..13u32).collect::<Vec<_>>()).with_padding(padding);
+        for index in 0..13 {
+            let proof = tree.get_proof(index)?;
+            let parsed = Proof::from_json(&proof.to_json())?;
+
+            assert!(parsed.verify());
+            assert_eq!(parsed.to_bytes(), proof.to_bytes());
+        }
+    }
+
+    let empty = proof_with_steps(&[]);
+    assert!(empty.to_json().ends_with(r#""path":[]}"#));
+    assert_eq!(
+        Proof::from_json(&empty.to_json())?.to_json(),
+        empty.to_json()
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn wide_digests() -> eyre::Result<()> {
+    use merkle::hash::Sha256;
+
+    let tree = MerkleTree::with_hasher(vec!["a", "b", "c"], Sha256);
+    let proof = tree.get_proof(2)?;
+    let json = proof.to_json();
+    assert!(json.starts_with(
+        r#"{"leaf":"0x2e7d2c03a9507ae265ecf5b5356885a53393a2029d241394997265a1a25aefc6""#
+    ));
+
+    let parsed = MerkleProof::<Sha256>::from_json(&json)?;
+    assert!(parsed.verify_against(&tree.root()));
+
+    // a 32 byte digest is not a 8 byte one
+    assert!(Proof::from_json(&json).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn whitespace_and_uppercase_are_accepted() -> eyre::Result<()> {
+    let proof = proof_with_steps(&[Direction::Right]);
+    let json = r#"
+        {
+            "leaf": "0xA0A1A2A3A4A5A6A7",
+            "root": "0x0102030405060708",
+            "index": 1,
+            "width": 8,
+            "padding": "default_value",
+            "path": [ { "dir": "right", "hash": "0x1111111111111111" } ]
+        }
+    "#;
+
+    assert_eq!(Proof::from_json(json)?.to_json(), proof.to_json());
+    Ok(())
+}
+
+#[test]
+fn malformed_json_is_rejected() {
+    let json = proof_with_steps(&[Direction::Left, Direction::Right]).to_json();
+
+    for len in 0..json.len() {
+        assert!(Proof::from_json(&json[..len]).is_err());
+    }
+
+    assert_eq!(
+        json_error(&json.replace("0xa0a1a2a3a4a5a6a7", "0xa0a1a2a3a4a5a6zz")),
+        r#"invalid json: "leaf" contains the non hex digit 'z'"#
+    );
+    assert_eq!(
+        json_error(&json.replace("0xa0a1a2a3a4a5a6a7", "0xa0a1a2a3a4a5a6")),
+        r#"invalid json: "leaf" has 14 hex digits, a digest has 16"#
+    );
+    assert_eq!(
+        json_error(&json.replace("0x0102030405060708", "0102030405060708")),
+        r#"invalid json: "root" is missing the 0x prefix"#
+    );
+    assert_eq!(
+        json_error(&json.replace("0x2222222222222222", "0x22222222222222222")),
+        r#"invalid json: step 1: "hash" has 17 hex digits, a digest has 16"#
+    );
+    assert_eq!(
+        Proof::from_json(&json.replace("\"right\"", "\"up\"")).unwrap_err(),
+        MerkleError::InvalidDirection("\"up\"".into())
+    );
+    assert_eq!(
+        json_error(&json.replace("default_value", "zero")),
+        r#"invalid json: unknown padding strategy "zero""#
+    );
+    assert_eq!(
+        json_error(&json.replace(r#""index":1,"#, "")),
+        r#"invalid json: missing field "index""#
+    );
+    assert_eq!(
+        json_error(&json.replace(r#""index":1"#, r#""index":"1""#)),
+        r#"invalid json: "index" has to be a number"#
+    );
+    assert_eq!(
+        json_error(&json.replace(r#""index":1"#, r#""index":1,"extra":2"#)),
+        r#"invalid json: unknown field "extra""#
+    );
+    assert_eq!(
+        json_error(&json.replace(r#""width":8"#, r#""index":8"#)),
+        r#"invalid json: duplicate field "index""#
+    );
+    assert_eq!(
+        json_error(&format!("{json}}}")),
+        "invalid json: trailing characters after the proof"
+    );
+}
@@ -0,0 +1,141 @@
+use merkle::{
+    hash::{DefaultBuildHasher, LeafHasher, MerkleHasher},
+    proof::Direction,
+    KaryMerkleTree, MerkleError, MerkleTree, Root,
+};
+
+#[test]
+fn arity_four_with_odd_sizes() -> eyre::Result<()> {
+    for len in [1u32, 3, 5, 15, 17, 63, 65, 99] {
+        let tree = KaryMerkleTree::with_arity((0..len).collect(), 4)?;
+        let root = tree.root();
+        assert!(tree.leafs().len().is_power_of_two());
+        assert_eq!(tree.leafs().len().trailing_zeros() % 2, 0);
+
+        for index in 0..len as usize {
+            let proof = tree.get_proof(index)?;
+            assert_eq!(
+                proof.path.len(),
+                tree.leafs().len().trailing_zeros() as usize / 2
+            );
+            assert!(proof.path.iter().all(|step| step.siblings.len() == 3));
+            assert!(proof.verify_against(&root));
+
+            let mut moved = tree.get_proof(index)?;
+            moved.index ^= 1;
+            assert!(!moved.verify_against(&root));
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn tampered_proofs_fail() -> eyre::Result<()> {
+    let tree = KaryMerkleTree::with_arity((0..50u32).collect(), 8)?;
+    let root = tree.root();
+    let proof = tree.get_proof(42)?;
+    assert!(proof.verify_against(&root));
+
+    let mut sibling = tree.get_proof(42)?;
+    sibling.path[1].siblings[3] ^= 1;
+    assert!(!sibling.verify_against(&root));
+
+    let mut position = tree.get_proof(42)?;
+    position.path[0].position = 1;
+    assert!(!position.verify_against(&root));
+
+    // a leaf claiming to be the root of a tree of any size
+    let mut forged = tree.get_proof(42)?;
+    forged.path.clear();
+    forged.root = Root(forged.leaf.0);
+    assert!(!forged.verify_against(&forged.root));
+    assert!(!forged.verify_against(&root));
+    forged.len = 0;
+    assert!(!forged.verify_against(&forged.root));
+
+    let mut shorter = tree.get_proof(42)?;
+    shorter.path.pop();
+    assert!(!shorter.verify_against(&root));
+
+    assert!(proof.verify_with_len(&root, 50));
+    assert!(!proof.verify_with_len(&root, 64));
+
+    // a proof from another tree only leads to the root of that tree
+    let other = KaryMerkleTree::with_arity((1..51u32).collect(), 8)?;
+    let foreign = other.get_proof(42)?;
+    assert!(foreign.verify_against(&other.root()));
+    assert!(!foreign.verify_against(&root));
+    Ok(())
+}
+
+#[test]
+fn padding_and_bad_arities_are_rejected() -> eyre::Result<()> {
+    let tree = KaryMerkleTree::with_arity((0..5u32).collect(), 4)?;
+    assert_eq!(tree.leafs().len(), 16);
+    assert_eq!(
+        tree.get_proof(5).err(),
+        Some(MerkleError::IndexOutOfBounds { index: 5, len: 5 })
+    );
+    assert!(KaryMerkleTree::<u32>::with_arity(vec![], 0).is_err());
+    assert_eq!(
+        KaryMerkleTree::<u32>::with_arity(vec![1], 1).err(),
+        Some(MerkleError::InvalidArity(1))
+    );
+    Ok(())
+}
+
+#[test]
+fn arity_two_matches_binary_tree() -> eyre::Result<()> {
+    let hasher = DefaultBuildHasher::default();
+    let (left, right) = (hasher.hash_leaf(&1u32), hasher.hash_leaf(&2u32));
+    assert_eq!(
+        hasher.hash_children(&[left, right]),
+        hasher.hash_siblings(&left, &right)
+    );
+
+    for len in 0..40u32 {
+        let values: Vec<u32> = (0..len).collect();
+        let binary = MerkleTree::from(values.clone());
+        let kary = KaryMerkleTree::with_arity(values, 2)?;
+        assert_eq!(kary.root(), binary.root());
+        assert_eq!(kary.leafs(), binary.leafs());
+
+        // the same siblings in the same order, the position is the direction
+        for index in 0..len as usize {
+            let (kary, binary) = (kary.get_proof(index)?, binary.get_proof(index)?);
+            assert_eq!((kary.leaf, kary.root), (binary.leaf, binary.root));
+            assert_eq!(kary.path.len(), binary.path.len());
+            for (kary, binary) in kary.path.iter().zip(&binary.path) {
+                let direction = match kary.position {
+                    0 => Direction::Right,
+                    _ => Direction::Left,
+                };
+                assert_eq!(
+                    (&kary.siblings[..], direction),
+                    (&[binary.value][..], binary.direction)
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(all(feature = "sha256", feature = "keccak", feature = "rfc6962"))]
+#[test]
+fn backends_hash_two_children_like_siblings() {
+    use merkle::hash::{Keccak256, Rfc6962, Sha256};
+
+    fn check<S: LeafHasher<[u8]>>(hasher: S) {
+        let (left, right) = (hasher.hash_leaf(&b"a"[..]), hasher.hash_leaf(&b"b"[..]));
+        assert_eq!(
+            hasher.hash_children(&[left, right]),
+            hasher.hash_siblings(&left, &right)
+        );
+    }
+
+    check(Sha256);
+    check(Keccak256);
+    check(Rfc6962);
+}
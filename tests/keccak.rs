@@ -0,0 +1,60 @@
+#![cfg(feature = "keccak")]
+
+mod common;
+
+use merkle::{
+    hash::{keccak, Keccak256},
+    MerkleTree,
+};
+
+use common::hex;
+
+#[test]
+fn known_answers() {
+    assert_eq!(
+        hex(&keccak::digest(b"")),
+        "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+    );
+    assert_eq!(
+        hex(&keccak::digest(b"abc")),
+        "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+    );
+    // longer than one block of 136 bytes
+    assert_eq!(
+        hex(&keccak::digest(&[b'x'; 200])),
+        "3c3800defb6a25a70a2737e0716eeb5d270559ad3cad8f6abddac58802d7158e"
+    );
+}
+
+#[test]
+fn four_leaf_tree() -> eyre::Result<()> {
+    let values: Vec<&str> = vec!["a", "b", "c", "d"];
+    let tree = MerkleTree::with_hasher(values, Keccak256);
+
+    // the root merkletreejs produces for ['a', 'b', 'c', 'd'] with keccak256
+    // leafs and unsorted pairs
+    assert_eq!(
+        tree.root().to_string(),
+        "68203f90e9d07dc5859259d7536e87a6ba9d345f2552b5b9de2999ddce9ce1bf"
+    );
+
+    let proof = tree.get_proof(2)?;
+    assert!(proof.verify());
+    assert_eq!(
+        proof.leaf.to_string(),
+        "0b42b6393c1f53060fe3ddbfcd7aadcca894465a5a438f69c87d790b2299b9b2"
+    );
+
+    let path: Vec<String> = proof.path.iter().map(|step| hex(&step.value)).collect();
+    assert_eq!(
+        path,
+        [
+            // keccak256("d")
+            "f1918e8562236eb17adc8502332f4c9c82bc14e19bfc0aa10ab674ff75b3d2f3",
+            // keccak256(keccak256("a") || keccak256("b"))
+            "805b21d846b189efaeb0377d6bb0d201b3872a363e607c25088f025b0c6ae1f8",
+        ]
+    );
+
+    Ok(())
+}
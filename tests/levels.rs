@@ -0,0 +1,67 @@
+use merkle::{hash::MerkleHasher, MerkleTree, PaddingStrategy};
+
+fn tree(len: u32, padding: PaddingStrategy) -> MerkleTree<u32> {
+    MerkleTree::from((0..len).collect::<Vec<_>>()).with_padding(padding)
+}
+
+#[test]
+fn widths_follow_the_padding() {
+    use PaddingStrategy::*;
+
+    for (len, padding, widths) in [
+        (0, DefaultValue, &[1][..]),
+        (1, DefaultValue, &[1]),
+        (1, DuplicateLast, &[1]),
+        (1, PromoteOdd, &[1]),
+        (3, DefaultValue, &[4, 2, 1]),
+        (3, DuplicateLast, &[3, 2, 1]),
+        (3, PromoteOdd, &[3, 2, 1]),
+        (8, DefaultValue, &[8, 4, 2, 1]),
+        (8, DuplicateLast, &[8, 4, 2, 1]),
+        (8, PromoteOdd, &[8, 4, 2, 1]),
+    ] {
+        let tree = tree(len, padding);
+        let levels: Vec<_> = tree.levels().map(<[u64]>::len).collect();
+        assert_eq!(levels, widths, "{len} {padding:?}");
+        assert_eq!(tree.levels().len(), tree.height() + 1);
+        assert_eq!(tree.levels().next(), Some(tree.leafs()));
+        assert_eq!(tree.levels().last(), Some(&[tree.root().0][..]));
+    }
+}
+
+#[test]
+fn nodes_are_the_hashes_of_their_children() {
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        let tree = tree(3, padding);
+        let hasher = tree.hasher();
+        let (leafs, above) = (tree.leafs(), tree.levels().nth(1).unwrap());
+        assert_eq!(
+            tree.node(1, 0),
+            Some(hasher.hash_siblings(&leafs[0], &leafs[1]))
+        );
+        let odd = match padding {
+            PaddingStrategy::DefaultValue => hasher.hash_siblings(&leafs[2], &leafs[3]),
+            PaddingStrategy::DuplicateLast => hasher.hash_siblings(&leafs[2], &leafs[2]),
+            PaddingStrategy::PromoteOdd => leafs[2],
+        };
+        assert_eq!(above, [tree.node(1, 0).unwrap(), odd]);
+        assert_eq!(tree.node(2, 0), Some(tree.root().0));
+        assert_eq!(tree.node(2, 1), None);
+        assert_eq!(tree.node(3, 0), None);
+    }
+}
+
+#[test]
+fn levels_follow_changes() -> eyre::Result<()> {
+    let mut tree = tree(8, PaddingStrategy::DefaultValue);
+    tree.add(8);
+    assert_eq!(tree.levels().len(), 5);
+    tree.update(0, 42)?;
+    let fresh = MerkleTree::from(vec![42, 1, 2, 3, 4, 5, 6, 7, 8]);
+    assert!(tree.levels().eq(fresh.levels()));
+    Ok(())
+}
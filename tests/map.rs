@@ -0,0 +1,100 @@
+use merkle::{MerkleError, MerkleMap};
+
+/// a fixed pseudo random sequence, the same on every run
+fn random(seed: u64) -> impl Iterator<Item = u64> {
+    let mut state = seed;
+    std::iter::repeat_with(move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        state >> 33
+    })
+}
+
+#[test]
+fn insertion_order_does_not_matter() {
+    // a history of inserts and removes, replayed in several orders of the
+    // final entries
+    let mut history = MerkleMap::new();
+    let mut expected = std::collections::BTreeMap::new();
+    for value in random(7).take(500) {
+        let key = format!("account {}", value % 60);
+        if value % 5 == 0 {
+            assert_eq!(history.remove(&key), expected.remove(&key));
+        } else {
+            assert_eq!(
+                history.insert(key.clone(), value),
+                expected.insert(key, value)
+            );
+        }
+    }
+    assert_eq!(history.len(), expected.len());
+
+    let entries: Vec<(String, u64)> = expected.into_iter().collect();
+    for seed in 0..5 {
+        let mut order: Vec<(u64, (String, u64))> = random(seed).zip(entries.clone()).collect();
+        order.sort();
+
+        let mut map = MerkleMap::new();
+        for (_, (key, value)) in order {
+            map.insert(key.clone(), value + 1);
+            map.insert(key, value);
+        }
+        map.insert("transient".to_string(), 0);
+        map.remove(&"transient".to_string());
+        assert_eq!(map.root(), history.root(), "{seed}");
+    }
+
+    let reversed: MerkleMap<String, u64> = {
+        let mut map = MerkleMap::new();
+        for (key, value) in entries.into_iter().rev() {
+            map.insert(key, value);
+        }
+        map
+    };
+    assert_eq!(reversed.root(), history.root());
+}
+
+#[test]
+fn proofs_by_key() -> eyre::Result<()> {
+    let mut map = MerkleMap::new();
+    for account in 0..37u32 {
+        map.insert(account, u64::from(account) * 10);
+    }
+    let root = map.root().unwrap();
+
+    for account in 0..37u32 {
+        let balance = *map.get(&account).unwrap();
+        let proof = map.prove(&account)?;
+        assert!(proof.verify(&root, &account, &balance));
+        assert!(!proof.verify(&root, &account, &(balance + 1)));
+        assert!(!proof.verify(&root, &(account + 1), &balance));
+    }
+
+    let (account, before, after) = (3u32, 30u64, 31u64);
+    let stale = map.prove(&account)?;
+    assert!(stale.verify(&root, &account, &before));
+    assert_eq!(map.insert(account, after), Some(before));
+    let root = map.root().unwrap();
+    assert!(!stale.verify(&root, &account, &before));
+    assert!(!stale.verify(&root, &account, &after));
+    assert!(map.prove(&account)?.verify(&root, &account, &after));
+
+    assert_eq!(map.remove(&3), Some(31));
+    assert_eq!(map.get(&3), None);
+    assert_eq!(map.prove(&3).unwrap_err(), MerkleError::KeyNotFound);
+    Ok(())
+}
+
+#[test]
+fn empty_map() {
+    let mut map: MerkleMap<u32, u32> = MerkleMap::new();
+    assert!(map.is_empty());
+    assert_eq!(map.root(), None);
+    assert_eq!(map.prove(&1).unwrap_err(), MerkleError::KeyNotFound);
+
+    map.insert(1, 2);
+    assert!(map.root().is_some());
+    map.remove(&1);
+    assert_eq!(map.root(), None);
+}
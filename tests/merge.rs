@@ -0,0 +1,116 @@
+mod common;
+
+use merkle::{MerkleError, MerkleTree, PaddingStrategy};
+
+use common::Counting;
+
+const PADDINGS: [PaddingStrategy; 3] = [
+    PaddingStrategy::DefaultValue,
+    PaddingStrategy::DuplicateLast,
+    PaddingStrategy::PromoteOdd,
+];
+
+fn check(left: u32, right: u32, padding: PaddingStrategy) -> eyre::Result<()> {
+    let shard = |values: std::ops::Range<u32>| {
+        let tree = MerkleTree::from(values.collect::<Vec<_>>()).with_padding(padding);
+        tree.root();
+        tree
+    };
+    let merged = MerkleTree::merge(shard(0..left), shard(left..left + right))?;
+    let expected = shard(0..left + right);
+
+    let name = format!("{left} + {right} {padding:?}");
+    assert_eq!(merged.len(), expected.len(), "{name}");
+    assert_eq!(merged.root(), expected.root(), "{name}");
+    for index in 0..expected.len() {
+        assert_eq!(
+            merged.get_proof(index)?.to_bytes(),
+            expected.get_proof(index)?.to_bytes(),
+            "{name} {index}"
+        );
+    }
+    for index in expected.len()..expected.padded_len() {
+        assert_eq!(
+            merged.get_padding_proof(index)?.to_bytes(),
+            expected.get_padding_proof(index)?.to_bytes(),
+            "{name} {index}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn merged_trees_equal_trees_from_scratch() -> eyre::Result<()> {
+    for padding in PADDINGS {
+        for left in 0..=17 {
+            for right in 0..=17 {
+                check(left, right, padding)?;
+            }
+        }
+        check(1024, 1024, padding)?;
+        check(1024, 1, padding)?;
+        check(1000, 24, padding)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn complete_shards_are_not_rehashed() -> eyre::Result<()> {
+    for padding in PADDINGS {
+        let hasher = Counting::default();
+        let shards: Vec<_> = (0..8u32)
+            .map(|shard| {
+                let values = (shard * 1024..(shard + 1) * 1024).collect();
+                let tree = MerkleTree::with_hasher(values, hasher.clone()).with_padding(padding);
+                tree.root();
+                tree
+            })
+            .collect();
+
+        let before = hasher.count();
+        let merged = MerkleTree::merge_all(shards)?;
+        // the roots of the pairs, of the quadruples and of all shards
+        assert_eq!(hasher.count() - before, 4 + 2 + 1);
+
+        let expected =
+            MerkleTree::with_hasher((0..8192).collect(), Counting::default()).with_padding(padding);
+        assert_eq!(merged.root(), expected.root());
+    }
+    Ok(())
+}
+
+#[test]
+fn ragged_shards() -> eyre::Result<()> {
+    for padding in PADDINGS {
+        let sizes = [7u32, 300, 1, 64, 0, 129];
+        let mut start = 0;
+        let mut shards = Vec::new();
+        for size in sizes {
+            shards.push(
+                MerkleTree::from((start..start + size).collect::<Vec<_>>()).with_padding(padding),
+            );
+            start += size;
+        }
+        let merged = MerkleTree::merge_all(shards)?;
+        let expected = MerkleTree::from((0..start).collect::<Vec<_>>()).with_padding(padding);
+        assert_eq!(merged.root(), expected.root());
+        assert!(merged.get_proof(300)?.verify_against(&merged.root()));
+    }
+
+    let empty = MerkleTree::<u32>::merge_all(Vec::new())?;
+    assert!(empty.is_empty());
+    Ok(())
+}
+
+#[test]
+fn paddings_have_to_agree() {
+    let left = MerkleTree::from(vec![1u32, 2]);
+    let right = MerkleTree::from(vec![3u32]).with_padding(PaddingStrategy::PromoteOdd);
+    assert_eq!(
+        MerkleTree::merge(left, right).err(),
+        Some(MerkleError::PaddingMismatch {
+            padding: PaddingStrategy::DefaultValue,
+            other: PaddingStrategy::PromoteOdd,
+        })
+    );
+}
@@ -0,0 +1,86 @@
+use merkle::{MerkleError, Mmr};
+
+#[test]
+fn positions_and_sizes() {
+    let mut mmr = Mmr::<u32>::new();
+    assert!(mmr.root().is_none());
+
+    // leafs sit at the positions of a post order numbering
+    let positions: Vec<usize> = (0..8).map(|value| mmr.append(&value)).collect();
+    assert_eq!(positions, [0, 1, 3, 4, 7, 8, 10, 11]);
+    assert_eq!(mmr.size(), 15);
+}
+
+#[test]
+fn every_proof_verifies() -> eyre::Result<()> {
+    let mut mmr = Mmr::<u32>::new();
+    let positions: Vec<usize> = (0..10_000).map(|value| mmr.append(&value)).collect();
+    let root = mmr.root().ok_or_else(|| eyre::eyre!("no root"))?;
+
+    for &position in &positions {
+        let proof = mmr.get_proof(position)?;
+        assert_eq!(proof.root, root);
+        assert!(proof.verify_against(&root));
+    }
+
+    let mut proof = mmr.get_proof(positions[1234])?;
+    proof.path[2] ^= 1;
+    assert!(!proof.verify_against(&root));
+
+    // a proof from another range only leads to the root of that range
+    let mut other = Mmr::<u32>::new();
+    for value in 1..=10_000 {
+        other.append(&value);
+    }
+    let foreign = other.get_proof(positions[1234])?;
+    assert!(foreign.verify_against(&foreign.root));
+    assert!(!foreign.verify_against(&root));
+
+    // inner nodes are not leafs
+    assert_eq!(
+        mmr.get_proof(2).unwrap_err(),
+        MerkleError::IndexOutOfBounds {
+            index: 2,
+            len: mmr.size()
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn early_proofs_stay_valid_for_their_root() -> eyre::Result<()> {
+    let mut mmr = Mmr::<u32>::new();
+    let mut early = Vec::new();
+    for value in 0..300 {
+        let position = mmr.append(&value);
+        early.push(mmr.get_proof(position)?);
+    }
+
+    let root = mmr.root().ok_or_else(|| eyre::eyre!("no root"))?;
+    for old in &early {
+        assert!(old.verify_against(&old.root));
+        let new = mmr.get_proof(old.position)?;
+        assert!(new.verify_against(&root));
+        assert_eq!(new.path[..old.path.len()], old.path[..]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn sizes_that_are_no_range_fail() -> eyre::Result<()> {
+    let mut mmr = Mmr::<u32>::new();
+    mmr.append(&1);
+    let proof = mmr.get_proof(0)?;
+    assert!(proof.verify_against(&proof.root));
+
+    // a single leaf is the only peak of a size of 2, the node left over
+    // after it belongs to no tree
+    for size in [2, 5, 6, 9, usize::MAX] {
+        let mut bad = mmr.get_proof(0)?;
+        bad.size = size;
+        assert!(!bad.verify_against(&proof.root), "{size}");
+    }
+    Ok(())
+}
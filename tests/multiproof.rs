@@ -0,0 +1,135 @@
+use merkle::{MerkleError, MerkleTree, PaddingStrategy};
+
+#[test]
+fn multiproof_verifies() -> eyre::Result<()> {
+    let values: Vec<u32> = (0..1000).collect();
+    let tree = MerkleTree::from(values);
+
+    let proof = tree.get_multi_proof(&[3, 500, 501, 999, 17])?;
+    assert!(proof.verify());
+    assert_eq!(proof.root, tree.root());
+
+    let indices: Vec<usize> = proof.leafs.iter().map(|(index, _)| *index).collect();
+    assert_eq!(indices, [3, 17, 500, 501, 999]);
+
+    Ok(())
+}
+
+#[test]
+fn multiproof_against_trusted_root() -> eyre::Result<()> {
+    let tree = MerkleTree::from((0..100).collect::<Vec<u32>>());
+    let trusted = tree.root();
+
+    let mut proof = tree.get_multi_proof(&[1, 2, 50])?;
+    assert!(proof.verify_against(&trusted));
+
+    // a forged root does not help, the leafs still lead to the trusted one
+    proof.root.0 ^= 1;
+    assert!(proof.verify_against(&trusted));
+    assert!(!proof.verify());
+    assert!(!proof.verify_against(&proof.root));
+
+    Ok(())
+}
+
+#[test]
+fn unsorted_and_duplicate_indices() -> eyre::Result<()> {
+    let values: Vec<u32> = (0..100).collect();
+    let tree = MerkleTree::from(values);
+
+    let sorted = tree.get_multi_proof(&[1, 2, 50])?;
+    let shuffled = tree.get_multi_proof(&[50, 2, 1, 2, 50])?;
+
+    assert_eq!(sorted.leafs, shuffled.leafs);
+    assert_eq!(sorted.decommitments, shuffled.decommitments);
+    assert!(shuffled.verify());
+
+    Ok(())
+}
+
+#[test]
+fn out_of_bounds() {
+    let tree: MerkleTree<u32> = MerkleTree::from(vec![1, 2, 3]);
+
+    assert_eq!(
+        tree.get_multi_proof(&[0, 4]).unwrap_err(),
+        MerkleError::IndexOutOfBounds { index: 4, len: 3 }
+    );
+    // padding is not a member of the tree
+    assert_eq!(
+        tree.get_multi_proof(&[0, 3]).unwrap_err(),
+        MerkleError::IndexOutOfBounds { index: 3, len: 3 }
+    );
+    assert_eq!(
+        tree.get_multi_proof(&[]).unwrap_err(),
+        MerkleError::NoIndices
+    );
+}
+
+#[test]
+fn adjacent_leafs_share_siblings() -> eyre::Result<()> {
+    let values: Vec<u32> = (0..1 << 12).collect();
+    let tree = MerkleTree::from(values);
+
+    let indices: Vec<usize> = (1024..1074).collect();
+    let multi = tree.get_multi_proof(&indices)?;
+    assert!(multi.verify());
+
+    let mut individual = 0;
+    for &index in &indices {
+        individual += tree.get_proof(index)?.path.len();
+    }
+
+    assert_eq!(individual, 50 * 12);
+    assert!(multi.decommitments.len() * 20 < individual);
+
+    Ok(())
+}
+
+#[test]
+fn tampering_breaks_verification() -> eyre::Result<()> {
+    let values: Vec<u32> = (0..100).collect();
+    let tree = MerkleTree::from(values);
+    let proof = tree.get_multi_proof(&[4, 5, 6, 42, 77])?;
+
+    for i in 0..proof.leafs.len() {
+        let mut tampered = tree.get_multi_proof(&[4, 5, 6, 42, 77])?;
+        tampered.leafs[i].1 ^= 1;
+        assert!(!tampered.verify());
+    }
+
+    // claiming another position for a leaf
+    let mut moved = tree.get_multi_proof(&[4, 5, 6, 42, 77])?;
+    moved.leafs[3].0 = 43;
+    assert!(!moved.verify());
+
+    let mut missing = tree.get_multi_proof(&[4, 5, 6, 42, 77])?;
+    missing.decommitments.pop();
+    assert!(!missing.verify());
+
+    let mut extra = tree.get_multi_proof(&[4, 5, 6, 42, 77])?;
+    extra.decommitments.push(proof.root.0);
+    assert!(!extra.verify());
+
+    Ok(())
+}
+
+#[test]
+fn promoted_odd_nodes() -> eyre::Result<()> {
+    for size in 1..=12 {
+        let values: Vec<u32> = (0..size).collect();
+        let tree = MerkleTree::from(values).with_padding(PaddingStrategy::PromoteOdd);
+
+        // every non empty subset of the leafs
+        for mask in 1u32..(1 << size) {
+            let indices: Vec<usize> = (0..size as usize)
+                .filter(|i| mask & (1 << i) != 0)
+                .collect();
+            let proof = tree.get_multi_proof(&indices)?;
+            assert!(proof.verify(), "{size} {indices:?}");
+            assert_eq!(proof.root, tree.root());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use std::sync::{Arc, Mutex};
+
+use merkle::{MerkleTree, Root};
+
+type Events = Arc<Mutex<Vec<(Root, usize)>>>;
+
+/// a tree that records every root it was told about
+fn recorded(values: Vec<u32>) -> (MerkleTree<u32>, Events) {
+    let events = Events::default();
+    let mut tree = MerkleTree::from(values);
+    let sink = events.clone();
+    tree.on_root_change(move |root, len| sink.lock().unwrap().push((root, len)));
+    (tree, events)
+}
+
+/// the root and length of a fresh tree over `values`
+fn expected(values: impl IntoIterator<Item = u32>) -> (Root, usize) {
+    let tree: MerkleTree<u32> = values.into_iter().collect();
+    (tree.root(), tree.len())
+}
+
+#[test]
+fn mutations_report_their_roots() -> eyre::Result<()> {
+    let (mut tree, events) = recorded(vec![0, 1, 2]);
+    assert!(events.lock().unwrap().is_empty());
+
+    tree.add(3);
+    tree.add_all(4..10);
+    tree.update(0, 42)?;
+    assert!(tree.update(10, 1).is_err());
+    tree.truncate(20);
+    tree.truncate(8);
+    tree.pop();
+    tree.remove(1)?;
+    tree.extend(100..103);
+
+    let after_update = [42, 1, 2, 3, 4, 5, 6, 7];
+    assert_eq!(
+        *events.lock().unwrap(),
+        [
+            expected(0..4),
+            expected(0..10),
+            expected([42, 1, 2, 3, 4, 5, 6, 7, 8, 9]),
+            expected(after_update),
+            expected(after_update[..7].iter().copied()),
+            expected([42, 2, 3, 4, 5, 6]),
+            expected([42, 2, 3, 4, 5, 6, 100, 101, 102]),
+        ]
+    );
+    assert_eq!(events.lock().unwrap().last(), Some(&(tree.root(), 9)));
+    Ok(())
+}
+
+#[test]
+fn observers_run_in_order() {
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let mut tree = MerkleTree::from(vec![1u32]);
+    for observer in 0..3 {
+        let order = order.clone();
+        tree.on_root_change(move |_, len| order.lock().unwrap().push((observer, len)));
+    }
+    tree.add(2);
+    assert_eq!(*order.lock().unwrap(), [(0, 2), (1, 2), (2, 2)]);
+}
+
+#[test]
+fn merges_report_once() -> eyre::Result<()> {
+    let (left, events) = recorded(vec![0, 1, 2]);
+    let (right, ignored) = recorded(vec![3, 4]);
+    let merged = MerkleTree::merge(left, right)?;
+    assert_eq!(*events.lock().unwrap(), [expected(0..5)]);
+    assert!(ignored.lock().unwrap().is_empty());
+
+    let (empty, events) = recorded(Vec::new());
+    let merged = MerkleTree::merge(empty, merged)?;
+    assert_eq!(*events.lock().unwrap(), [expected(0..5)]);
+    assert_eq!(merged.root(), expected(0..5).0);
+    Ok(())
+}
+
+#[test]
+fn clones_are_not_observed() {
+    let (tree, events) = recorded(vec![0, 1]);
+    let mut clone = tree.clone();
+    clone.add(2);
+    assert!(events.lock().unwrap().is_empty());
+}
@@ -0,0 +1,116 @@
+use merkle::{
+    hash::DefaultBuildHasher,
+    proof::MAX_PACKED_LEN,
+    verify::{fold_path, verify_packed, verify_path},
+    Direction, LeafHash, MerkleProof, MerkleTree, PackedPath, PaddingStrategy, Root, Step,
+};
+
+/// a fixed pseudo random sequence, the same on every run
+fn random(seed: u64) -> impl Iterator<Item = u64> {
+    let mut state = seed;
+    std::iter::repeat_with(move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        state >> 11
+    })
+}
+
+/// a path of `depth` random steps
+fn random_path(depth: usize, seed: u64) -> Vec<Step> {
+    random(seed)
+        .take(depth)
+        .map(|value| Step {
+            direction: if value & 1 == 1 {
+                Direction::Right
+            } else {
+                Direction::Left
+            },
+            value,
+        })
+        .collect()
+}
+
+#[test]
+fn random_paths_round_trip_and_verify() {
+    let hasher = DefaultBuildHasher::default();
+    for depth in 0..=40 {
+        for seed in 0..8 {
+            let path = random_path(depth, depth as u64 * 8 + seed);
+            let packed = PackedPath::pack(&path).unwrap();
+            assert_eq!(packed.len(), depth);
+            assert_eq!(packed.directions >> depth, 0);
+            assert_eq!(packed.unpack(), path);
+            assert_eq!(PackedPath::pack(&packed.unpack()).unwrap(), packed);
+
+            let leaf = LeafHash(seed);
+            let root = Root(fold_path(&hasher, &leaf.0, &path));
+            assert!(verify_path(&hasher, &leaf, &path, &root));
+            assert!(verify_packed(&hasher, &leaf, &packed, &root));
+            if depth > 0 {
+                let mut flipped = packed.clone();
+                flipped.directions ^= 1 << (seed as usize % depth);
+                assert!(!verify_packed(&hasher, &leaf, &flipped, &root));
+                assert!(!verify_path(&hasher, &leaf, &flipped.unpack(), &root));
+            }
+        }
+    }
+}
+
+#[test]
+fn tree_proofs_verify_packed() -> eyre::Result<()> {
+    let hasher = DefaultBuildHasher::default();
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        let tree = MerkleTree::from((0..37u32).collect::<Vec<_>>()).with_padding(padding);
+        for index in 0..tree.len() {
+            let proof = tree.get_proof(index)?;
+            let packed = proof.packed_path().unwrap();
+            assert_eq!(Some(packed.directions), proof.packed_directions());
+            assert!(verify_packed(&hasher, &proof.leaf, &packed, &tree.root()));
+
+            let rebuilt = MerkleProof {
+                path: packed.unpack(),
+                ..proof.clone()
+            };
+            assert_eq!(rebuilt, proof);
+            assert_eq!(MerkleProof::from_bytes(&proof.to_bytes())?, proof);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn deep_proofs_encode_their_directions() -> eyre::Result<()> {
+    for depth in [0, 1, 7, 8, 9, 40, 63, 64] {
+        let proof = MerkleProof {
+            path: random_path(depth, depth as u64),
+            root: Root(1),
+            leaf: LeafHash(2),
+            index: 0,
+            width: 1,
+            padding: PaddingStrategy::PromoteOdd,
+            hasher: DefaultBuildHasher::default(),
+        };
+        let bytes = proof.to_bytes();
+        assert_eq!(MerkleProof::from_bytes(&bytes)?, proof, "{depth}");
+    }
+    Ok(())
+}
+
+#[test]
+fn overlong_paths_do_not_pack() {
+    let path = random_path(MAX_PACKED_LEN + 1, 3);
+    assert!(PackedPath::pack(&path).is_none());
+
+    let packed = PackedPath {
+        directions: u64::MAX,
+        siblings: vec![1; MAX_PACKED_LEN + 1],
+    };
+    assert_eq!(packed.direction(MAX_PACKED_LEN), Direction::Left);
+    let hasher = DefaultBuildHasher::default();
+    assert!(!verify_packed(&hasher, &LeafHash(0), &packed, &Root(0)));
+}
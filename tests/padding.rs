@@ -0,0 +1,195 @@
+use merkle::{
+    hash::{DefaultBuildHasher, LeafHasher, MerkleHasher},
+    LeafHash, MerkleError, MerkleTree, PaddingStrategy, Root,
+};
+
+const STRATEGIES: [PaddingStrategy; 3] = [
+    PaddingStrategy::DefaultValue,
+    PaddingStrategy::DuplicateLast,
+    PaddingStrategy::PromoteOdd,
+];
+
+#[test]
+fn odd_sizes_verify() -> eyre::Result<()> {
+    for padding in STRATEGIES {
+        for size in [1, 3, 5, 7] {
+            let values: Vec<u32> = (0..size).collect();
+            let tree = MerkleTree::from(values).with_padding(padding);
+
+            let width = match padding {
+                PaddingStrategy::DefaultValue => size.next_power_of_two(),
+                _ => size,
+            };
+            assert_eq!(tree.leafs().len(), width as usize);
+
+            for index in 0..width as usize {
+                let proof = if index < size as usize {
+                    tree.get_proof(index)?
+                } else {
+                    tree.get_padding_proof(index)?
+                };
+                assert!(proof.verify(), "{padding:?} {size}");
+                assert_eq!(proof.root, tree.root());
+            }
+
+            let indices: Vec<usize> = (0..size as usize).step_by(2).collect();
+            let multi = tree.get_multi_proof(&indices)?;
+            assert!(multi.verify());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn strategies_give_different_roots() {
+    let roots: Vec<Root> = STRATEGIES
+        .iter()
+        .map(|&padding| {
+            MerkleTree::from(vec![1u32, 2, 3])
+                .with_padding(padding)
+                .root()
+        })
+        .collect();
+
+    assert_ne!(roots[0], roots[1]);
+    assert_ne!(roots[0], roots[2]);
+    assert_ne!(roots[1], roots[2]);
+}
+
+#[test]
+fn tree_shapes() {
+    let hasher = DefaultBuildHasher::default();
+    let [a, b, c] = [1u32, 2, 3].map(|value| hasher.hash_leaf(&value));
+    let ab = hasher.hash_siblings(&a, &b);
+    let values = vec![1u32, 2, 3];
+
+    let duplicated = MerkleTree::from(values.clone()).with_padding(PaddingStrategy::DuplicateLast);
+    assert_eq!(
+        duplicated.root(),
+        Root(hasher.hash_siblings(&ab, &hasher.hash_siblings(&c, &c)))
+    );
+
+    let promoted = MerkleTree::from(values.clone()).with_padding(PaddingStrategy::PromoteOdd);
+    assert_eq!(promoted.root(), Root(hasher.hash_siblings(&ab, &c)));
+
+    let padded = MerkleTree::from(values);
+    let padding = hasher.hash_padding();
+    assert_eq!(
+        padded.root(),
+        Root(hasher.hash_siblings(&ab, &hasher.hash_siblings(&c, &padding)))
+    );
+}
+
+#[test]
+fn multiproof_records_padding() -> eyre::Result<()> {
+    let values: Vec<u32> = (0..5).collect();
+    let tree = MerkleTree::from(values).with_padding(PaddingStrategy::DuplicateLast);
+
+    let mut proof = tree.get_multi_proof(&[4])?;
+    assert!(proof.verify());
+
+    // the verifier has to complete odd nodes the same way as the tree
+    proof.padding = PaddingStrategy::PromoteOdd;
+    assert!(!proof.verify());
+
+    Ok(())
+}
+
+#[test]
+fn updates_respect_padding() -> eyre::Result<()> {
+    for padding in STRATEGIES {
+        let mut values: Vec<u32> = (0..7).collect();
+        let mut tree = MerkleTree::from(values.clone()).with_padding(padding);
+        tree.root();
+
+        tree.update(6, 60)?;
+        values[6] = 60;
+        assert_eq!(
+            tree.root(),
+            MerkleTree::from(values).with_padding(padding).root()
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn proofs_stop_at_the_last_value() -> eyre::Result<()> {
+    for padding in STRATEGIES {
+        for len in [1, 4, 5, 8] {
+            let tree = MerkleTree::from((0..len as u32).collect::<Vec<_>>()).with_padding(padding);
+            assert!(tree.get_proof(len - 1)?.verify_against(&tree.root()));
+            assert_eq!(
+                tree.get_proof(len).unwrap_err(),
+                MerkleError::IndexOutOfBounds { index: len, len }
+            );
+            assert_eq!(
+                tree.proofs_for(&[0, len]).unwrap_err(),
+                MerkleError::IndexOutOfBounds { index: len, len }
+            );
+            assert_eq!(
+                tree.get_padding_proof(len - 1).unwrap_err(),
+                MerkleError::NotPadding {
+                    index: len - 1,
+                    len
+                }
+            );
+
+            // a padding leaf exists exactly where the tree is wider than len
+            let padded = tree.padded_len();
+            assert_eq!(tree.get_padding_proof(len).is_ok(), padded > len);
+            assert!(tree.get_padding_proof(padded).is_err());
+            for index in len..padded {
+                let proof = tree.get_padding_proof(index)?;
+                assert!(proof.verify_against(&tree.root()), "{padding:?} {len}");
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn hashed_trees_stop_at_the_last_leaf() -> eyre::Result<()> {
+    let hashes: Vec<u64> = (0..5).collect();
+    let tree = MerkleTree::<u32>::from_leaf_hashes(hashes);
+    assert!(tree.get_proof(4)?.verify_against(&tree.root()));
+    assert_eq!(
+        tree.get_proof(5).unwrap_err(),
+        MerkleError::IndexOutOfBounds { index: 5, len: 5 }
+    );
+    assert_eq!(
+        tree.get_padding_proof(4).unwrap_err(),
+        MerkleError::NotPadding { index: 4, len: 5 }
+    );
+    assert!(tree.get_padding_proof(5)?.verify_against(&tree.root()));
+    assert!(tree.get_padding_proof(8).is_err());
+    Ok(())
+}
+
+#[test]
+fn stored_default_values_are_not_padding() -> eyre::Result<()> {
+    let hasher = DefaultBuildHasher::default();
+    let tree = MerkleTree::from(vec![5u32, 0, 7]);
+    assert_ne!(hasher.hash_padding(), hasher.hash_leaf(&0u32));
+    assert!(tree.get_proof(1)?.verify_against(&tree.root()));
+
+    // claim the stored 0 sits in the padding slot at index 3
+    let mut forged = tree.get_padding_proof(3)?;
+    assert!(forged.verify_against(&tree.root()));
+    forged.leaf = LeafHash(hasher.hash_leaf(&0u32));
+    assert!(!forged.verify_against(&tree.root()));
+    Ok(())
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn stored_empty_bytes_are_not_padding() -> eyre::Result<()> {
+    use merkle::hash::Sha256;
+
+    let tree = MerkleTree::with_hasher(vec![&b"a"[..], b"", b"c"], Sha256);
+    let mut forged = tree.get_padding_proof(3)?;
+    forged.leaf = LeafHash(Sha256.hash_leaf(b""));
+    assert!(!forged.verify_against(&tree.root()));
+    Ok(())
+}
@@ -0,0 +1,83 @@
+//! run both with and without the `parallel` feature, the roots are pinned so
+//! both builds have to agree on them
+
+use merkle::{MerkleTree, PaddingStrategy};
+
+/// sizes of the trees in [`ROOTS`], over the little endian bytes of
+/// `0..size` hashed with sha-256
+#[cfg(feature = "sha256")]
+const SIZES: [u32; 5] = [1, 5, 4097, 65539, 262144];
+
+/// roots of the trees of [`SIZES`] for every padding
+#[cfg(feature = "sha256")]
+const ROOTS: [(PaddingStrategy, [&str; 5]); 3] = [
+    (
+        PaddingStrategy::DefaultValue,
+        [
+            "df3f619804a92fdb4057192dc43dd748ea778adc52bc498ce80524c014b81119",
+            "8637aed82a928882a00d84c06a541169c3b3eb1d4361552b94874620e70fea2c",
+            "00db0cf5c6b7862227d5c2bb3e784af4bbda740486a74e9c0d170c5899bbbcc1",
+            "d542e5e60769547ce741755ca72db3732fa79a4e6ccec94852240b5d6888b4ef",
+            "f26709490be0dc3b6380c503726028ed4860be8cb315d26306e10d8ac9ee6f6d",
+        ],
+    ),
+    (
+        PaddingStrategy::DuplicateLast,
+        [
+            "df3f619804a92fdb4057192dc43dd748ea778adc52bc498ce80524c014b81119",
+            "a0557eb74fabab24a18ec8a5e06bbaa9c4fe14eeffd5c97cb1c799beea27516c",
+            "78098db40d210ecdb2a08c39ea16339bf0da6f8beed4d5a1d069d71369c11379",
+            "09d388e913d54445fce546f272e83e731519afb6e249aece8158d6d155832f83",
+            "f26709490be0dc3b6380c503726028ed4860be8cb315d26306e10d8ac9ee6f6d",
+        ],
+    ),
+    (
+        PaddingStrategy::PromoteOdd,
+        [
+            "df3f619804a92fdb4057192dc43dd748ea778adc52bc498ce80524c014b81119",
+            "c00df688b34bf36a867921188959b0f8fd1ab693845ebaf0d9e0bf71d555a155",
+            "0cb5db5430ecb2a7ec81d83b7dba748a2af26191581f40cc533c13d827896fde",
+            "b9d5890d6b6fbacd8b1b1cff7569d7a9122e00ac14cf69c107fc217aa2e34731",
+            "f26709490be0dc3b6380c503726028ed4860be8cb315d26306e10d8ac9ee6f6d",
+        ],
+    ),
+];
+
+#[cfg(feature = "sha256")]
+#[test]
+fn roots_are_the_same_in_every_build() {
+    use merkle::hash::Sha256;
+
+    for (padding, roots) in ROOTS {
+        for (len, root) in SIZES.into_iter().zip(roots) {
+            let values: Vec<[u8; 4]> = (0..len).map(u32::to_le_bytes).collect();
+            let tree = MerkleTree::with_hasher(values, Sha256).with_padding(padding);
+            assert_eq!(tree.root().to_string(), root, "{len} {padding:?}");
+        }
+    }
+}
+
+/// the cached levels are built in parallel, the incremental updates after
+/// them are not, both have to give the same nodes
+#[test]
+fn built_levels_match_incremental_ones() -> eyre::Result<()> {
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        for size in [1 << 16, (1 << 16) + 3] {
+            let mut grown = MerkleTree::from(Vec::new()).with_padding(padding);
+            grown.root();
+            grown.add_all(0..size);
+            let built = MerkleTree::from((0..size).collect::<Vec<u32>>()).with_padding(padding);
+
+            assert_eq!(built.root(), grown.root(), "{size} {padding:?}");
+            assert_eq!(built.leafs(), grown.leafs());
+            for index in [0, 1, 4242, size as usize - 1] {
+                assert_eq!(built.get_proof(index)?, grown.get_proof(index)?);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "poseidon")]
+
+use merkle::{
+    hash::{poseidon, Digest, Fr, Poseidon},
+    MerkleProof, MerkleTree, Root,
+};
+
+fn fr(hex: &str) -> eyre::Result<Fr> {
+    Ok(hex.parse::<Root<Fr>>()?.0)
+}
+
+#[test]
+fn matches_circomlib() -> eyre::Result<()> {
+    // poseidon([1, 2]) and poseidon([0, 0]) of circomlib
+    assert_eq!(
+        poseidon::hash(Fr::from(1), Fr::from(2)),
+        fr("0x115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a")?
+    );
+    assert_eq!(
+        poseidon::hash(Fr::ZERO, Fr::ZERO),
+        fr("0x2098f5fb9e239eab3ceac3f27b81e481dc3124d55ffed523a839ee8446b64864")?
+    );
+    Ok(())
+}
+
+#[test]
+fn tree_over_field_elements() -> eyre::Result<()> {
+    let tree = MerkleTree::with_hasher((1..=4).map(Fr::from).collect(), Poseidon);
+    assert_eq!(
+        tree.root().0,
+        fr("0x075d30e28d48842bd6c1044b68f982d586e2892ae91c77f8f56111d8f55070ed")?
+    );
+
+    // padding leafs are zero
+    let tree = MerkleTree::with_hasher((1..=3).map(Fr::from).collect(), Poseidon);
+    assert_eq!(
+        tree.root().0,
+        fr("0x0d9e989a60f1961e8fda683cfc3585608a47d513f9af9167c1287fa8cea0720e")?
+    );
+
+    for index in 0..3 {
+        let proof = tree.get_proof(index)?;
+        assert!(proof.verify());
+        assert_eq!(proof.leaf.0, Fr::from(index as u64 + 1));
+
+        let parsed = MerkleProof::<Poseidon>::from_bytes(&proof.to_bytes())?;
+        assert!(parsed.verify());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn canonical_encoding() -> eyre::Result<()> {
+    let element = fr("0x115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a")?;
+    let bytes = element.to_bytes_be();
+    assert_eq!(Fr::from_bytes_be(&bytes), Some(element));
+    assert_eq!(Fr::from(258).to_bytes_be()[30..], [1, 2]);
+
+    let modulus = "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001";
+    let below = "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000";
+    assert!(modulus.parse::<Root<Fr>>().is_err());
+    assert!(Fr::from_slice(&[0xff; 32]).is_none());
+    assert_eq!(
+        fr(below)?.add(&Fr::from(1)),
+        Fr::ZERO,
+        "the largest element wraps around"
+    );
+    assert_eq!(fr(below)?.mul(&fr(below)?), Fr::from(1));
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use merkle::{
+    hash::DefaultBuildHasher, Direction, LeafHash, MerkleError, MerkleProof, MerkleTree,
+    PaddingStrategy, Root, Step,
+};
+
+fn proof_with_steps(directions: &[Direction]) -> MerkleProof {
+    MerkleProof {
+        path: directions
+            .iter()
+            .enumerate()
+            .map(|(i, &direction)| Step {
+                direction,
+                value: 0x1111_1111_1111_1111u64.wrapping_mul(i as u64 + 1),
+            })
+            .collect(),
+        root: Root(0x0102_0304_0506_0708),
+        leaf: LeafHash(0xa0a1_a2a3_a4a5_a6a7),
+        index: 1,
+        width: 8,
+        padding: PaddingStrategy::DefaultValue,
+        hasher: Default::default(),
+    }
+}
+
+#[test]
+fn direction_encodings_round_trip() -> eyre::Result<()> {
+    for direction in [Direction::Left, Direction::Right] {
+        assert_eq!(direction.as_str().parse::<Direction>()?, direction);
+        assert_eq!(Direction::try_from(u8::from(direction))?, direction);
+    }
+
+    assert_eq!(Direction::Left.to_string(), "left");
+    assert_eq!(u8::from(Direction::Right), 1);
+
+    Ok(())
+}
+
+#[test]
+fn unknown_directions_are_rejected() {
+    assert_eq!(
+        "up".parse::<Direction>(),
+        Err(MerkleError::InvalidDirection("\"up\"".into()))
+    );
+    assert!("Left".parse::<Direction>().is_err());
+    assert_eq!(
+        Direction::try_from(2),
+        Err(MerkleError::InvalidDirection("2".into()))
+    );
+}
+
+#[test]
+fn bytes_round_trip() -> eyre::Result<()> {
+    let values: Vec<u32> = (0..1000).collect();
+    let tree = MerkleTree::from(values);
+
+    for index in [0, 1, 499, 998, 999, 1023] {
+        let proof = if index < tree.len() {
+            tree.get_proof(index)?
+        } else {
+            tree.get_padding_proof(index)?
+        };
+        let bytes = proof.to_bytes();
+        let decoded = MerkleProof::<DefaultBuildHasher>::from_bytes(&bytes)?;
+
+        assert_eq!(decoded.leaf, proof.leaf);
+        assert_eq!(decoded.root, proof.root);
+        assert_eq!(decoded.path.len(), proof.path.len());
+        for (a, b) in decoded.path.iter().zip(&proof.path) {
+            assert_eq!(a.direction, b.direction);
+            assert_eq!(a.value, b.value);
+        }
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert!(decoded.verify());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn bytes_are_compact() {
+    let directions: Vec<Direction> = (0..20)
+        .map(|i| {
+            if i % 3 == 0 {
+                Direction::Left
+            } else {
+                Direction::Right
+            }
+        })
+        .collect();
+    let proof = proof_with_steps(&directions);
+
+    // leaf, root, one byte each for index, tree size, padding and length,
+    // 3 bytes of directions and the siblings
+    assert_eq!(proof.to_bytes().len(), 8 + 8 + 4 + 3 + 20 * 8);
+}
+
+#[test]
+fn golden_bytes() -> eyre::Result<()> {
+    let proof = proof_with_steps(&[Direction::Left, Direction::Right, Direction::Right]);
+    let expected: Vec<u8> = [
+        // leaf
+        &[0xa0, 0xa1, 0xa2, 0xa3, 0xa4, 0xa5, 0xa6, 0xa7][..],
+        // root
+        &[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08],
+        // leaf index
+        &[0x01],
+        // eight leafs, padded with default values
+        &[0x08],
+        &[0x00],
+        // three steps
+        &[0x03],
+        // directions left, right, right
+        &[0b0000_0110],
+        // siblings
+        &[0x11; 8],
+        &[0x22; 8],
+        &[0x33; 8],
+    ]
+    .concat();
+
+    assert_eq!(proof.to_bytes(), expected);
+    assert_eq!(
+        MerkleProof::<DefaultBuildHasher>::from_bytes(&expected)?.to_bytes(),
+        expected
+    );
+
+    let empty = proof_with_steps(&[]);
+    assert_eq!(empty.to_bytes(), [&expected[..19], &[0x00]].concat());
+
+    Ok(())
+}
+
+#[test]
+fn malformed_bytes_are_rejected() {
+    let directions = [Direction::Right; 10];
+    let bytes = proof_with_steps(&directions).to_bytes();
+    assert!(MerkleProof::<DefaultBuildHasher>::from_bytes(&bytes).is_ok());
+
+    for len in 0..bytes.len() {
+        assert!(MerkleProof::<DefaultBuildHasher>::from_bytes(&bytes[..len]).is_err());
+    }
+
+    assert_eq!(
+        MerkleProof::<DefaultBuildHasher>::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err(),
+        MerkleError::Truncated {
+            needed: 2 + 10 * 8,
+            available: 2 + 10 * 8 - 1
+        }
+    );
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert_eq!(
+        MerkleProof::<DefaultBuildHasher>::from_bytes(&trailing).unwrap_err(),
+        MerkleError::TrailingBytes(1)
+    );
+
+    let mut padding = bytes.clone();
+    padding[18] = 3;
+    assert_eq!(
+        MerkleProof::<DefaultBuildHasher>::from_bytes(&padding).unwrap_err(),
+        MerkleError::InvalidEncoding("unknown padding strategy")
+    );
+
+    // only the lowest two bits of the second direction byte are in use
+    let mut unused = bytes.clone();
+    unused[21] |= 0b100;
+    assert!(matches!(
+        MerkleProof::<DefaultBuildHasher>::from_bytes(&unused),
+        Err(MerkleError::InvalidEncoding(_))
+    ));
+
+    // a huge claimed path length fails without allocating the path
+    let mut huge = bytes[..19].to_vec();
+    huge.extend_from_slice(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]);
+    assert!(MerkleProof::<DefaultBuildHasher>::from_bytes(&huge).is_err());
+
+    // varints longer than 64 bits
+    let mut overflow = bytes[..19].to_vec();
+    overflow.extend_from_slice(&[0xff; 10]);
+    overflow.push(0x01);
+    assert!(matches!(
+        MerkleProof::<DefaultBuildHasher>::from_bytes(&overflow),
+        Err(MerkleError::InvalidEncoding(_))
+    ));
+}
+
+#[test]
+fn decoded_paths_are_bounded() -> eyre::Result<()> {
+    use merkle::proof::MAX_PATH_LEN;
+
+    let longest = proof_with_steps(&[Direction::Left; MAX_PATH_LEN]);
+    MerkleProof::<DefaultBuildHasher>::from_bytes(&longest.to_bytes())?;
+    MerkleProof::<DefaultBuildHasher>::from_json(&longest.to_json())?;
+
+    let longer = proof_with_steps(&[Direction::Left; MAX_PATH_LEN + 1]);
+    assert_eq!(
+        MerkleProof::<DefaultBuildHasher>::from_bytes(&longer.to_bytes()).unwrap_err(),
+        MerkleError::InvalidEncoding("proof path is too long")
+    );
+    assert_eq!(
+        MerkleProof::<DefaultBuildHasher>::from_json(&longer.to_json()).unwrap_err(),
+        MerkleError::InvalidJson(format!("\"path\" has more than {MAX_PATH_LEN} steps"))
+    );
+    Ok(())
+}
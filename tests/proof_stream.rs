@@ -0,0 +1,167 @@
+use std::io::{self, Cursor, Read};
+
+use merkle::{
+    hash::{DefaultBuildHasher, Domain},
+    verify::verify_streaming,
+    LeafHash, MerkleError, MerkleProof, MerkleTree, ProofReader, Root,
+};
+
+fn tree() -> MerkleTree<u32> {
+    (0..1000).collect()
+}
+
+/// the merkle error inside an invalid data error
+fn cause(err: &io::Error) -> Option<&MerkleError> {
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{err}");
+    err.get_ref()?.downcast_ref()
+}
+
+#[test]
+fn through_a_cursor() -> eyre::Result<()> {
+    let tree = tree();
+    let root = tree.root();
+    for index in [0, 1, 511, 999] {
+        let proof = tree.get_proof(index)?;
+        let mut bytes = Vec::new();
+        proof.write_to(&mut bytes)?;
+        assert_eq!(bytes, proof.encode());
+        assert_eq!(MerkleProof::decode(&bytes)?, proof);
+
+        let reader: ProofReader<_> = ProofReader::new(Cursor::new(&bytes))?;
+        assert_eq!(reader.leaf(), proof.leaf);
+        assert_eq!(reader.root(), proof.root);
+        assert_eq!((reader.index(), reader.width()), (index, 1024));
+        assert_eq!(reader.len(), proof.path.len());
+        let steps = reader.collect::<io::Result<Vec<_>>>()?;
+        assert_eq!(steps, proof.path);
+
+        let reader: ProofReader<_> = ProofReader::new(Cursor::new(&bytes))?;
+        assert!(verify_streaming(&proof.leaf, &root, reader)?);
+        let reader: ProofReader<_> = ProofReader::new(Cursor::new(&bytes))?;
+        assert!(!verify_streaming(&proof.leaf, &Root(root.0 ^ 1), reader)?);
+        let reader: ProofReader<_> = ProofReader::new(Cursor::new(&bytes))?;
+        assert!(!verify_streaming(
+            &LeafHash(proof.leaf.0 ^ 1),
+            &root,
+            reader
+        )?);
+    }
+    Ok(())
+}
+
+#[test]
+fn through_a_pipe() -> eyre::Result<()> {
+    let tree = tree();
+    let proofs: Vec<MerkleProof> = (0..20)
+        .map(|i| tree.get_proof(i * 50))
+        .collect::<Result<_, _>>()?;
+    let (reader, mut writer) = io::pipe()?;
+
+    let sent = proofs.clone();
+    let producer = std::thread::spawn(move || -> io::Result<()> {
+        for proof in &sent {
+            proof.write_to(&mut writer)?;
+        }
+        Ok(())
+    });
+
+    // proofs follow each other on the stream, each reader stops at its end
+    let mut reader = io::BufReader::new(reader);
+    for proof in &proofs {
+        let steps: ProofReader<_> = ProofReader::new(&mut reader)?;
+        assert!(verify_streaming(&proof.leaf, &tree.root(), steps)?);
+    }
+    producer.join().unwrap()?;
+    assert_eq!(reader.read(&mut [0])?, 0);
+    Ok(())
+}
+
+#[test]
+fn truncated_streams_fail_where_they_end() -> eyre::Result<()> {
+    let proof = tree().get_proof(123)?;
+    let bytes = proof.encode();
+    let siblings = proof.path.len() * 8;
+    let header = bytes.len() - siblings;
+
+    for len in 0..bytes.len() {
+        let reader = ProofReader::<_>::new(&bytes[..len]);
+        if len < header {
+            assert_eq!(
+                reader.err().map(|err| err.kind()),
+                Some(io::ErrorKind::UnexpectedEof),
+                "{len}"
+            );
+            continue;
+        }
+
+        // every complete sibling is yielded before the error
+        let steps: Vec<io::Result<_>> = reader?.collect();
+        let complete = (len - header) / 8;
+        assert_eq!(steps.len(), complete + 1, "{len}");
+        assert!(steps[..complete].iter().all(Result::is_ok));
+        let err = steps[complete].as_ref().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let reader: ProofReader<_> = ProofReader::new(&bytes[..len])?;
+        let err = verify_streaming(&proof.leaf, &proof.root, reader).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+    Ok(())
+}
+
+#[test]
+fn malformed_streams_fail_early() -> eyre::Result<()> {
+    let proof = tree().get_proof(5)?;
+    let bytes = proof.encode();
+
+    // nothing after the magic bytes is needed to reject them
+    let err = ProofReader::<_>::new(&b"MRKX"[..]).err().unwrap();
+    assert_eq!(
+        cause(&err),
+        Some(&MerkleError::InvalidEncoding("not an encoded proof"))
+    );
+
+    let mut version = bytes.clone();
+    version[4] = 9;
+    let err = ProofReader::<_>::new(&version[..5]).err().unwrap();
+    assert_eq!(
+        cause(&err),
+        Some(&MerkleError::UnsupportedVersion {
+            version: 9,
+            latest: 1
+        })
+    );
+
+    let other = MerkleTree::with_hasher(
+        vec![1u32, 2],
+        Domain::new(DefaultBuildHasher::default(), b"other"),
+    );
+    let other = other.get_proof(0)?.encode();
+    let err = ProofReader::<_>::new(&other[..]).err().unwrap();
+    assert_eq!(cause(&err), Some(&MerkleError::WrongBackend));
+
+    // after the header the shape is checked before any sibling is read
+    let mut moved = proof.clone();
+    moved.index = 6;
+    let moved = moved.encode();
+    let header = moved.len() - proof.path.len() * 8;
+    let reader: ProofReader<_> = ProofReader::new(&moved[..header])?;
+    assert!(!verify_streaming(&proof.leaf, &proof.root, reader)?);
+    Ok(())
+}
+
+#[test]
+fn nothing_after_the_proof_is_read() -> eyre::Result<()> {
+    let proof = tree().get_proof(77)?;
+    let mut bytes = proof.encode();
+    bytes.extend_from_slice(b"next");
+
+    let mut reader: ProofReader<_> = ProofReader::new(Cursor::new(bytes))?;
+    for step in reader.by_ref() {
+        step?;
+    }
+    let mut rest = Vec::new();
+    reader.into_inner().read_to_end(&mut rest)?;
+    assert_eq!(rest, b"next");
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use merkle::{verify::verify_range, MerkleError, MerkleTree, PaddingStrategy};
+
+const PADDINGS: [PaddingStrategy; 3] = [
+    PaddingStrategy::DefaultValue,
+    PaddingStrategy::DuplicateLast,
+    PaddingStrategy::PromoteOdd,
+];
+
+#[test]
+fn every_range_verifies() -> eyre::Result<()> {
+    for len in 1..=19u32 {
+        for padding in PADDINGS {
+            let tree = MerkleTree::from((0..len).collect::<Vec<_>>()).with_padding(padding);
+            let leafs: Vec<u64> = tree.padded_leaf_hashes().collect();
+            let (width, stored) = (leafs.len(), len as usize);
+            for start in 0..stored {
+                for end in start + 1..=stored {
+                    let proof = tree.get_range_proof(start..end)?;
+                    let range = &leafs[start..end];
+                    assert!(
+                        verify_range(&tree.root(), start, range, &proof),
+                        "{len} {padding:?} {start}..{end}"
+                    );
+                    assert!(proof.decommitments.len() <= 2 * tree.height());
+
+                    // padding leafs are all alike, shifting over them proves the same
+                    if end == width || leafs[start + 1..end + 1] != *range {
+                        assert!(!verify_range(&tree.root(), start + 1, range, &proof));
+                    }
+                    let mut changed = range.to_vec();
+                    changed[0] ^= 1;
+                    assert!(!verify_range(&tree.root(), start, &changed, &proof));
+                    if end < width {
+                        let longer = &leafs[start..end + 1];
+                        assert!(!verify_range(&tree.root(), start, longer, &proof));
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn single_leaf_and_full_tree() -> eyre::Result<()> {
+    let tree = MerkleTree::from((0..1024u32).collect::<Vec<_>>());
+    let leafs: Vec<u64> = tree.padded_leaf_hashes().collect();
+
+    // a single leaf needs the siblings of its ordinary proof
+    let single = tree.get_range_proof(500..501)?;
+    assert_eq!(single.decommitments.len(), tree.get_proof(500)?.path.len());
+    assert!(verify_range(&tree.root(), 500, &leafs[500..501], &single));
+
+    let full = tree.get_range_proof(0..1024)?;
+    assert!(full.decommitments.is_empty());
+    assert!(verify_range(&tree.root(), 0, &leafs, &full));
+    Ok(())
+}
+
+#[test]
+fn smaller_than_separate_proofs() -> eyre::Result<()> {
+    let tree = MerkleTree::from((0..4096u32).collect::<Vec<_>>());
+    // crosses the boundary between the two halves of the tree
+    let proof = tree.get_range_proof(1000..3000)?;
+    let separate: usize = (1000..3000)
+        .map(|index| Ok(tree.get_proof(index)?.path.len()))
+        .sum::<eyre::Result<_>>()?;
+    assert!(proof.decommitments.len() <= 24);
+    assert!(proof.decommitments.len() * 1000 < separate);
+
+    let hashed = MerkleTree::from((0..4096u32).collect::<Vec<_>>()).finalize();
+    let leafs: Vec<u64> = tree.padded_leaf_hashes().collect();
+    let proof = hashed.get_range_proof(1000..3000)?;
+    assert!(verify_range(
+        &hashed.root(),
+        1000,
+        &leafs[1000..3000],
+        &proof
+    ));
+    Ok(())
+}
+
+#[test]
+fn invalid_ranges() {
+    let tree = MerkleTree::from(vec![1u32, 2, 3]);
+    assert_eq!(
+        tree.get_range_proof(2..2).err(),
+        Some(MerkleError::NoIndices)
+    );
+    assert_eq!(
+        tree.get_range_proof(1..5).err(),
+        Some(MerkleError::IndexOutOfBounds { index: 4, len: 3 })
+    );
+    // padding is not a member of the tree
+    assert_eq!(
+        tree.get_range_proof(1..4).err(),
+        Some(MerkleError::IndexOutOfBounds { index: 3, len: 3 })
+    );
+}
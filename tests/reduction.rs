@@ -0,0 +1,102 @@
+//! the levels and roots of the tree against a reference that reduces a
+//! `VecDeque` pair by pair, the way the tree was first built
+
+use std::collections::VecDeque;
+
+use merkle::{
+    hash::{DefaultBuildHasher, LeafHasher, MerkleHasher},
+    Direction, LeafHash, MerkleTree, PaddingStrategy, Root, Step,
+};
+
+const PADDINGS: [PaddingStrategy; 3] = [
+    PaddingStrategy::DefaultValue,
+    PaddingStrategy::DuplicateLast,
+    PaddingStrategy::PromoteOdd,
+];
+
+/// every level of the tree over `values`, leafs first
+fn reference_levels(values: &[u32], padding: PaddingStrategy) -> Vec<Vec<u64>> {
+    let hasher = DefaultBuildHasher::default();
+    let mut level: VecDeque<u64> = values.iter().map(|value| hasher.hash_leaf(value)).collect();
+    if padding == PaddingStrategy::DefaultValue || level.is_empty() {
+        let width = level.len().next_power_of_two();
+        level.resize(width, hasher.hash_padding());
+    }
+
+    let mut levels = vec![Vec::from(level.clone())];
+    while level.len() > 1 {
+        let mut next = VecDeque::new();
+        while let Some(left) = level.pop_front() {
+            next.push_back(match (level.pop_front(), padding) {
+                (Some(right), _) => hasher.hash_siblings(&left, &right),
+                (None, PaddingStrategy::DuplicateLast) => hasher.hash_siblings(&left, &left),
+                (None, _) => left,
+            });
+        }
+        levels.push(Vec::from(next.clone()));
+        level = next;
+    }
+    levels
+}
+
+/// the path of `index` read off the reference levels
+fn reference_path(levels: &[Vec<u64>], padding: PaddingStrategy, index: usize) -> Vec<Step> {
+    let mut index = index;
+    let mut path = Vec::new();
+    for level in &levels[..levels.len() - 1] {
+        let sibling = index ^ 1;
+        let direction = if index % 2 == 1 {
+            Direction::Left
+        } else {
+            Direction::Right
+        };
+        match level.get(sibling) {
+            Some(&value) => path.push(Step { direction, value }),
+            None if padding == PaddingStrategy::DuplicateLast => path.push(Step {
+                direction,
+                value: level[index],
+            }),
+            None => {}
+        }
+        index /= 2;
+    }
+    path
+}
+
+#[test]
+fn roots_and_proofs_match_the_reference() -> eyre::Result<()> {
+    for padding in PADDINGS {
+        for len in (0..=130u32).chain([255, 256, 257, 1000, 4097]) {
+            let values: Vec<u32> = (0..len).map(|i| i.wrapping_mul(0x9e37_79b9)).collect();
+            let levels = reference_levels(&values, padding);
+            let tree = MerkleTree::from(values).with_padding(padding);
+
+            let root = Root(levels[levels.len() - 1][0]);
+            assert_eq!(tree.root(), root, "{len} {padding:?}");
+            assert_eq!(tree.leafs(), levels[0]);
+            for index in 0..len as usize {
+                let proof = tree.get_proof(index)?;
+                assert_eq!(proof.leaf, LeafHash(levels[0][index]));
+                assert_eq!(proof.path, reference_path(&levels, padding, index));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn folded_roots_of_versions_match_the_reference() -> eyre::Result<()> {
+    for padding in PADDINGS {
+        let values: Vec<u32> = (0..300).collect();
+        let tree = MerkleTree::from(values.clone()).with_padding(padding);
+        for version in 0..=values.len() {
+            let levels = reference_levels(&values[..version], padding);
+            assert_eq!(
+                tree.root_at(version)?,
+                Root(levels[levels.len() - 1][0]),
+                "{version} {padding:?}"
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use merkle::{MerkleError, MerkleTree, PaddingStrategy};
+
+const PADDINGS: [PaddingStrategy; 3] = [
+    PaddingStrategy::DefaultValue,
+    PaddingStrategy::DuplicateLast,
+    PaddingStrategy::PromoteOdd,
+];
+
+#[test]
+fn removed_leaf_shifts_the_rest() -> eyre::Result<()> {
+    let mut values: Vec<u32> = (0..9).collect();
+    let mut tree = MerkleTree::from(values.clone());
+    assert_eq!(tree.padded_len(), 16);
+    let old = (5..9)
+        .map(|index| tree.get_proof(index))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    assert_eq!(tree.remove(4)?, 4);
+    values.remove(4);
+    let fresh = MerkleTree::from(values);
+    assert_eq!(tree.len(), 8);
+    assert_eq!(tree.padded_len(), 8);
+    assert_eq!(tree.root(), fresh.root());
+
+    for proof in old {
+        assert!(!proof.verify_against(&tree.root()));
+    }
+    for index in 0..8 {
+        assert!(tree.get_proof(index)?.verify_against(&fresh.root()));
+    }
+    Ok(())
+}
+
+#[test]
+fn remove_without_cached_levels() -> eyre::Result<()> {
+    for padding in PADDINGS {
+        for index in [0, 6, 12] {
+            let mut values: Vec<u32> = (0..13).collect();
+            let mut tree = MerkleTree::from(values.clone()).with_padding(padding);
+            let mut cached = MerkleTree::from(values.clone()).with_padding(padding);
+            cached.root();
+
+            assert_eq!(tree.remove(index)?, values.remove(index));
+            cached.remove(index)?;
+            let fresh = MerkleTree::from(values).with_padding(padding);
+            assert_eq!(tree.root(), fresh.root());
+            assert_eq!(cached.root(), fresh.root());
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn padding_cannot_be_removed() {
+    let mut tree = MerkleTree::from((0..9u32).collect::<Vec<_>>());
+    let root = tree.root();
+    for index in [9, 15, 16] {
+        assert_eq!(
+            tree.remove(index).err(),
+            Some(MerkleError::IndexOutOfBounds { index, len: 9 })
+        );
+    }
+    assert_eq!(tree.root(), root);
+}
@@ -0,0 +1,55 @@
+use merkle::{MerkleTree, PaddingStrategy};
+
+#[cfg(feature = "sha256")]
+#[test]
+fn five_leafs() -> eyre::Result<()> {
+    use merkle::hash::Sha256;
+
+    let values = ["a", "b", "c", "d", "e"].map(|s| s.as_bytes().to_vec());
+    let tree = MerkleTree::with_hasher(values.to_vec(), Sha256);
+    let expected = "                                c6cde1
+              14ede5                              28e3a5
+     e5a01f            bffe0b            f92051            f5a5fd
+ ca9781   3e23e8   2e7d2c   18ac3e   3f79bb  [000000] [000000] [000000]
+";
+    assert_eq!(tree.render(), expected);
+
+    let tree = tree.with_padding(PaddingStrategy::PromoteOdd);
+    let lines: Vec<_> = tree.render().lines().map(str::to_owned).collect();
+    assert_eq!(lines.len(), 4);
+    assert!(!lines[3].contains('['));
+    // the promoted leaf sits below the same hash on every level up to the top
+    assert!(lines[1].ends_with("3f79bb"));
+    assert!(lines[2].ends_with("3f79bb"));
+    Ok(())
+}
+
+#[test]
+fn large_trees_are_cut() -> eyre::Result<()> {
+    let tree = MerkleTree::from((0..1_000_000).collect::<Vec<u32>>());
+    let rendered = tree.render();
+    let lines: Vec<_> = rendered.lines().collect();
+    assert_eq!(lines.len(), 6);
+    assert_eq!(
+        lines[5],
+        "… 16 more levels, 1000000 leafs and 48576 padding"
+    );
+    assert_eq!(lines[4].split_whitespace().count(), 16);
+    assert!(lines.iter().all(|line| line.len() < 16 * 9));
+
+    let top = tree.render_top(1);
+    assert_eq!(top.lines().count(), 2);
+    assert_eq!(top.lines().next().unwrap().trim().len(), 6);
+    Ok(())
+}
+
+#[test]
+fn small_trees_are_complete() -> eyre::Result<()> {
+    let tree = MerkleTree::from(vec![1u32, 2]);
+    assert_eq!(tree.render_top(10).lines().count(), 2);
+    assert_eq!(tree.render_top(10), tree.render());
+
+    let tree = MerkleTree::from(vec![7u32]);
+    assert_eq!(tree.render().lines().count(), 1);
+    Ok(())
+}
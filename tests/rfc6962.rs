@@ -0,0 +1,118 @@
+#![cfg(feature = "rfc6962")]
+
+mod common;
+
+use merkle::MerkleTree;
+
+use common::hex;
+
+/// leafs used by the certificate-transparency-go test suite
+const LEAVES: [&[u8]; 8] = [
+    b"",
+    b"\x00",
+    b"\x10",
+    b"\x20\x21",
+    b"\x30\x31",
+    b"\x40\x41\x42\x43",
+    b"\x50\x51\x52\x53\x54\x55\x56\x57",
+    b"\x60\x61\x62\x63\x64\x65\x66\x67\x68\x69\x6a\x6b\x6c\x6d\x6e\x6f",
+];
+
+/// roots of the trees made of the first 1..=8 leaves
+const ROOTS: [&str; 8] = [
+    "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+    "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+    "aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77",
+    "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+    "4e3bbb1f7b478dcfe71fb631631519a3bca12c9aefca1612bfce4c13a86264d4",
+    "76e67dadbcdf1e10e1b74ddc608abd2f98dfb16fbce75277b5232a127f2087ef",
+    "ddb89be403809e325750d3d263cd78929c2942b7942a34b77e122c9594a74c8c",
+    "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328",
+];
+
+/// (index, tree size, audit path)
+const PATHS: [(usize, usize, &[&str]); 4] = [
+    (
+        0,
+        8,
+        &[
+            "96a296d224f285c67bee93c30f8a309157f0daa35dc5b87e410b78630a09cfc7",
+            "5f083f0a1a33ca076a95279832580db3e0ef4584bdff1f54c8a360f50de3031e",
+            "6b47aaf29ee3c2af9af889bc1fb9254dabd31177f16232dd6aab035ca39bf6e4",
+        ],
+    ),
+    (
+        5,
+        8,
+        &[
+            "bc1a0643b12e4d2d7c77918f44e0f4f79a838b6cf9ec5b5c283e1f4d88599e6b",
+            "ca854ea128ed050b41b35ffc1b87b8eb2bde461e9e3b5596ece6b9d5975a0ae0",
+            "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+        ],
+    ),
+    (
+        2,
+        3,
+        &["fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125"],
+    ),
+    (
+        1,
+        5,
+        &[
+            "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+            "5f083f0a1a33ca076a95279832580db3e0ef4584bdff1f54c8a360f50de3031e",
+            "bc1a0643b12e4d2d7c77918f44e0f4f79a838b6cf9ec5b5c283e1f4d88599e6b",
+        ],
+    ),
+];
+
+fn leaves(size: usize) -> Vec<&'static [u8]> {
+    LEAVES[..size].to_vec()
+}
+
+#[test]
+fn empty_root() {
+    let tree = MerkleTree::rfc6962(Vec::<&[u8]>::new());
+    assert_eq!(
+        tree.root().to_string(),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+}
+
+#[test]
+fn roots() {
+    for (size, root) in (1..=8).zip(ROOTS) {
+        let tree = MerkleTree::rfc6962(leaves(size));
+        assert_eq!(tree.root().to_string(), root, "tree of size {size}");
+    }
+}
+
+#[test]
+fn audit_paths() -> eyre::Result<()> {
+    for (index, size, expected) in PATHS {
+        let tree = MerkleTree::rfc6962(leaves(size));
+        let proof = tree.get_proof(index)?;
+
+        let path: Vec<String> = proof.path.iter().map(|step| hex(&step.value)).collect();
+        assert_eq!(path, expected, "leaf {index} of tree of size {size}");
+        assert!(proof.verify());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn every_proof_verifies() -> eyre::Result<()> {
+    for size in 1..=8 {
+        let tree = MerkleTree::rfc6962(leaves(size));
+        assert_eq!(tree.leafs().len(), size);
+
+        for index in 0..size {
+            let proof = tree.get_proof(index)?;
+            assert!(proof.verify_against(&tree.root()));
+        }
+        assert!(tree.get_proof(size).is_err());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use merkle::{LeafHash, MerkleError, MerkleTree, Root};
+
+#[test]
+fn format_round_trip() -> eyre::Result<()> {
+    let root = Root(0x0000_00ab_cdef_0123u64);
+    assert_eq!(root.to_string(), "000000abcdef0123");
+    assert_eq!(format!("{root:x}"), "000000abcdef0123");
+    assert_eq!(format!("{root:#x}"), "0x000000abcdef0123");
+
+    assert_eq!("000000abcdef0123".parse::<Root>()?, root);
+    assert_eq!("0x000000abcdef0123".parse::<Root>()?, root);
+    assert_eq!("000000ABCDEF0123".parse::<Root>()?, root);
+
+    let tree = MerkleTree::from((0..9u32).collect::<Vec<_>>());
+    let root = tree.root();
+    assert_eq!(root.to_string().parse::<Root>()?, root);
+    assert_eq!(format!("{root:#x}").parse::<Root>()?, root);
+
+    Ok(())
+}
+
+#[test]
+fn malformed_hex_is_rejected() {
+    let error = |s: &str| s.parse::<Root>().unwrap_err();
+
+    assert_eq!(
+        error("abc"),
+        MerkleError::InvalidHex("root has 3 hex digits, a digest has 16".into())
+    );
+    assert_eq!(
+        error("0x000000abcdef012"),
+        MerkleError::InvalidHex("root has 15 hex digits, a digest has 16".into())
+    );
+    assert_eq!(
+        error("000000abcdef012g"),
+        MerkleError::InvalidHex("root contains the non hex digit 'g'".into())
+    );
+    assert!(matches!(error(""), MerkleError::InvalidHex(_)));
+    assert!(matches!(
+        error("0X000000abcdef0123"),
+        MerkleError::InvalidHex(_)
+    ));
+    assert!(matches!(
+        error("+00000abcdef0123"),
+        MerkleError::InvalidHex(_)
+    ));
+    assert!(matches!(
+        error("00000000abcdéf0"),
+        MerkleError::InvalidHex(_)
+    ));
+}
+
+#[test]
+fn proof_lines() -> eyre::Result<()> {
+    let tree = MerkleTree::from(vec![1u32, 2, 3]);
+    let proof = tree.get_proof(1)?;
+
+    let expected = format!(
+        "leaf 1 of 4: {}\n← {}\n→ {}\nroot: {}",
+        proof.leaf,
+        Root(proof.path[0].value),
+        Root(proof.path[1].value),
+        proof.root
+    );
+    assert_eq!(proof.to_string(), expected);
+    assert_eq!(format!("{proof:x}"), expected);
+
+    let prefixed = format!("{proof:#x}");
+    assert_eq!(prefixed.lines().count(), 4);
+    assert!(prefixed.lines().all(|line| line.contains("0x")));
+
+    Ok(())
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn wide_roots() -> eyre::Result<()> {
+    use merkle::hash::Sha256;
+
+    let tree = MerkleTree::with_hasher(vec!["a", "b", "c", "d"], Sha256);
+    let root = tree.root();
+    assert_eq!(root.to_string().len(), 64);
+    assert_eq!(root.to_string().parse::<Root<[u8; 32]>>()?, root);
+    assert!(root.to_string()[..16].parse::<Root<[u8; 32]>>().is_err());
+
+    let proof = tree.get_proof(1)?.to_string();
+    assert!(proof.starts_with(
+        "leaf 1 of 4: 3e23e8160039594a33894f6564e1b1348bbd7a0088d42c4acb73eeaed59c009d\n← ca97"
+    ));
+    Ok(())
+}
+
+#[test]
+fn leaf_hash_format_round_trip() -> eyre::Result<()> {
+    let tree = MerkleTree::from((0..9u32).collect::<Vec<_>>());
+    let leaf = tree.get_proof(4)?.leaf;
+    assert_eq!(leaf.to_string().parse::<LeafHash>()?, leaf);
+    assert_eq!(format!("{leaf:#x}").parse::<LeafHash>()?, leaf);
+    assert_eq!(format!("{leaf:x}"), format!("{}", Root(leaf.0)));
+
+    assert_eq!(
+        "abc".parse::<LeafHash>().unwrap_err(),
+        MerkleError::InvalidHex("leaf hash has 3 hex digits, a digest has 16".into())
+    );
+    Ok(())
+}
+
+#[test]
+fn inner_digests() {
+    let tree = MerkleTree::from(vec![1u32, 2, 3]);
+    let root = tree.root();
+    assert_eq!(Root::from(root.into_inner()), root);
+    assert_eq!(LeafHash::from(7u64).into_inner(), 7);
+}
@@ -0,0 +1,95 @@
+use merkle::{
+    hash::DefaultBuildHasher, MerkleError, PaddingStrategy, SaltedMerkleTree, SaltedProof,
+};
+
+fn tree() -> SaltedMerkleTree {
+    let mut tree = SaltedMerkleTree::new();
+    for (i, value) in ["alice", "bob", "carol", "bob", "dave"].iter().enumerate() {
+        tree.add_salted_with(value, [i as u8; 32]);
+    }
+    tree
+}
+
+#[test]
+fn verify_with_salt() -> eyre::Result<()> {
+    let tree = tree();
+    assert_eq!(tree.len(), 5);
+
+    let proof = tree.get_proof(2)?;
+    assert_eq!(proof.salt, [2; 32]);
+    assert_eq!(tree.salt(2), Some(&[2; 32]));
+    let root = tree.root();
+    assert_eq!(proof.proof.root, root);
+    assert!(proof.verify_against(&root, "carol"));
+    assert!(!proof.verify_against(&root, "bob"));
+
+    let wrong = SaltedProof {
+        salt: [3; 32],
+        proof: tree.get_proof(2)?.proof,
+    };
+    assert!(!wrong.verify_against(&root, "carol"));
+
+    // a proof from another tree only leads to the root of that tree
+    let mut other = SaltedMerkleTree::new();
+    other.add_salted_with("carol", [2; 32]);
+    let foreign = other.get_proof(0)?;
+    assert!(foreign.verify_against(&other.root(), "carol"));
+    assert!(!foreign.verify_against(&root, "carol"));
+    Ok(())
+}
+
+#[test]
+fn same_value_different_salts() -> eyre::Result<()> {
+    let tree = tree();
+    let (first, second) = (tree.get_proof(1)?, tree.get_proof(3)?);
+    assert_ne!(first.proof.leaf, second.proof.leaf);
+    assert!(first.verify_against(&tree.root(), "bob"));
+    assert!(second.verify_against(&tree.root(), "bob"));
+    Ok(())
+}
+
+#[test]
+fn random_salts() -> eyre::Result<()> {
+    let mut tree = SaltedMerkleTree::new().with_padding(PaddingStrategy::PromoteOdd);
+    let salts: Vec<_> = (0..3).map(|_| tree.add_salted("same")).collect();
+    assert_ne!(salts[0], salts[1]);
+    assert_ne!(salts[1], salts[2]);
+    for (index, salt) in salts.iter().enumerate() {
+        let proof = tree.get_proof(index)?;
+        assert_eq!(&proof.salt, salt);
+        assert!(proof.verify_against(&tree.root(), "same"));
+    }
+    Ok(())
+}
+
+#[test]
+fn padding_has_no_salt() -> eyre::Result<()> {
+    let tree = tree();
+    assert_eq!(tree.salt(5), None);
+    assert_eq!(
+        tree.get_proof(5).unwrap_err(),
+        MerkleError::IndexOutOfBounds { index: 5, len: 5 }
+    );
+    Ok(())
+}
+
+#[test]
+fn bytes_include_the_salt() -> eyre::Result<()> {
+    let proof = tree().get_proof(4)?;
+    let bytes = proof.to_bytes();
+    assert_eq!(bytes[..32], [4; 32]);
+    assert_eq!(bytes[32..], proof.proof.to_bytes());
+
+    let decoded = SaltedProof::<DefaultBuildHasher>::from_bytes(&bytes)?;
+    assert_eq!(decoded.salt, proof.salt);
+    assert!(decoded.verify_against(&tree().root(), "dave"));
+
+    assert_eq!(
+        SaltedProof::<DefaultBuildHasher>::from_bytes(&bytes[..20]).unwrap_err(),
+        MerkleError::Truncated {
+            needed: 32,
+            available: 20
+        }
+    );
+    Ok(())
+}
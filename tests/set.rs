@@ -0,0 +1,109 @@
+use merkle::{Duplicates, MerkleError, MerkleSet};
+
+/// a fixed pseudo random sequence, the same on every run
+fn random(seed: u64) -> impl Iterator<Item = u64> {
+    let mut state = seed;
+    std::iter::repeat_with(move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        state >> 33
+    })
+}
+
+/// `values` in an order picked by `seed`
+fn shuffled(values: &[u32], seed: u64) -> Vec<u32> {
+    let mut keyed: Vec<_> = random(seed).zip(values.iter().copied()).collect();
+    keyed.sort_unstable();
+    keyed.into_iter().map(|(_, value)| value).collect()
+}
+
+#[test]
+fn insertion_order_does_not_matter() -> eyre::Result<()> {
+    let items: Vec<u32> = (0..10_000).map(|i| i * 7 + 3).collect();
+    let mut sorted = MerkleSet::default();
+    sorted.insert_all(items.iter().copied())?;
+    let root = sorted.root().unwrap();
+
+    for seed in 1..4 {
+        let order = shuffled(&items, seed);
+        let mut one_by_one = MerkleSet::new(Duplicates::Reject);
+        for &item in &order {
+            one_by_one.insert(item)?;
+        }
+        assert_eq!(one_by_one.root(), Some(root), "{seed}");
+        assert_eq!(one_by_one.values(), sorted.values());
+
+        // half at once, the rest one by one
+        let mut mixed = MerkleSet::default();
+        mixed.insert_all(order[..5000].iter().copied())?;
+        for &item in &order[5000..] {
+            mixed.insert(item)?;
+        }
+        assert_eq!(mixed.root(), Some(root), "{seed}");
+    }
+    Ok(())
+}
+
+#[test]
+fn proofs_by_value_verify_in_any_order() -> eyre::Result<()> {
+    let items: Vec<u32> = (0..1000).collect();
+    let mut first = MerkleSet::default();
+    first.insert_all(shuffled(&items, 5))?;
+    let mut second = MerkleSet::default();
+    second.insert_all(shuffled(&items, 6))?;
+    let root = second.root().unwrap();
+
+    for item in [0, 1, 499, 999] {
+        let proof = first.prove(&item)?;
+        assert_eq!(proof, second.prove(&item)?);
+        assert!(proof.verify(&root, &item));
+        assert!(!proof.verify(&root, &(item + 1)));
+    }
+    assert_eq!(first.prove(&1000).err(), Some(MerkleError::ValueNotFound));
+    assert!(MerkleSet::<u32>::default().root().is_none());
+    Ok(())
+}
+
+#[test]
+fn sets_reject_duplicates() -> eyre::Result<()> {
+    let mut set = MerkleSet::default();
+    set.insert_all([1u32, 2, 3])?;
+    let root = set.root();
+    assert_eq!(set.insert(2), Err(MerkleError::DuplicateValue));
+    assert_eq!(set.insert_all([4, 5, 3]), Err(MerkleError::DuplicateValue));
+    assert_eq!(set.insert_all([4, 5, 4]), Err(MerkleError::DuplicateValue));
+    assert_eq!((set.len(), set.root()), (3, root));
+
+    assert!(set.remove(&2));
+    assert!(!set.remove(&2));
+    set.insert(2)?;
+    assert_eq!(set.root(), root);
+    Ok(())
+}
+
+#[test]
+fn multisets_keep_every_copy() -> eyre::Result<()> {
+    let items = [1u32, 2, 2, 3, 3, 3];
+    let mut multiset = MerkleSet::new(Duplicates::Allow);
+    multiset.insert_all(items)?;
+    assert_eq!(multiset.len(), 6);
+    assert_eq!(multiset.count(&3), 3);
+    assert_eq!(multiset.count(&4), 0);
+
+    let mut reordered = MerkleSet::new(Duplicates::Allow);
+    reordered.insert(3)?;
+    reordered.insert_all([2, 3, 1])?;
+    reordered.insert_all([3, 2])?;
+    assert_eq!(reordered.root(), multiset.root());
+
+    let mut set = MerkleSet::default();
+    set.insert_all([1u32, 2, 3])?;
+    assert_ne!(set.root(), multiset.root());
+
+    let root = multiset.root().unwrap();
+    assert!(multiset.prove(&3)?.verify(&root, &3));
+    assert!(multiset.remove(&3));
+    assert_eq!(multiset.count(&3), 2);
+    Ok(())
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "sha256")]
+
+mod common;
+
+use merkle::{
+    hash::{sha256, LeafHasher, Sha256},
+    LeafHash, MerkleProof, MerkleTree,
+};
+
+use common::hex;
+
+#[test]
+fn known_answers() {
+    assert_eq!(
+        hex(&sha256::digest(b"")),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        hex(&sha256::digest(b"abc")),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        hex(&sha256::digest(
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+        )),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+    assert_eq!(
+        hex(&sha256::digest(&vec![b'a'; 1_000_000])),
+        "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+    );
+}
+
+#[test]
+fn root_of_byte_strings() {
+    let values: Vec<&[u8]> = vec![b"alice", b"bob", b"carol"];
+    let tree = MerkleTree::with_hasher(values, Sha256);
+
+    // computed independently as
+    // l = [sha256(x) for x in [b"alice", b"bob", b"carol"]] + [bytes(32)]
+    // sha256(sha256(l[0] + l[1]) + sha256(l[2] + l[3]))
+    assert_eq!(
+        tree.root().to_string(),
+        "d113ed70daad0daaca92222fa62531d9e5cdf45e328bc3b4773896050339c0ee"
+    );
+}
+
+#[test]
+fn sha256_proofs() -> eyre::Result<()> {
+    let values: Vec<&str> = vec!["a", "b", "c", "d", "e"];
+    let tree = MerkleTree::with_hasher(values, Sha256);
+
+    for index in 0..5 {
+        let proof = tree.get_proof(index)?;
+        assert!(proof.verify_against(&tree.root()));
+    }
+    for index in 5..8 {
+        let proof = tree.get_padding_proof(index)?;
+        assert!(proof.verify_against(&tree.root()));
+    }
+
+    let proof = tree.get_proof(2)?;
+    assert_eq!(proof.leaf.0, Sha256.hash_leaf("c"));
+    assert_eq!(proof.leaf, LeafHash(sha256::digest(b"c")));
+
+    Ok(())
+}
+
+#[test]
+fn sha256_proof_bytes() -> eyre::Result<()> {
+    let values: Vec<&str> = vec!["a", "b", "c", "d", "e"];
+    let tree = MerkleTree::with_hasher(values, Sha256);
+
+    let proof = tree.get_proof(4)?;
+    let bytes = proof.to_bytes();
+    assert_eq!(bytes.len(), 32 + 32 + 4 + 1 + 3 * 32);
+
+    let decoded = MerkleProof::<Sha256>::from_bytes(&bytes)?;
+    assert_eq!(decoded.to_bytes(), bytes);
+    assert!(decoded.verify());
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use merkle::{MerkleError, SetProof, SortedMerkleTree};
+
+fn random(seed: u64, count: usize) -> Vec<u64> {
+    let mut state = seed;
+    (0..count)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        })
+        .collect()
+}
+
+#[test]
+fn inclusion_and_exclusion() -> eyre::Result<()> {
+    let present = random(3, 777);
+    let absent = random(4, 300);
+
+    let mut tree = SortedMerkleTree::new();
+    for &value in &present {
+        assert!(tree.insert(value));
+    }
+    assert!(!tree.insert(present[0]));
+    assert_eq!(tree.len(), 777);
+    let root = tree.root().ok_or_else(|| eyre::eyre!("no root"))?;
+
+    for value in &present {
+        let proof = tree.contains_proof(value)?;
+        assert!(matches!(proof, SetProof::Inclusion(_)));
+        assert!(proof.verify(&root, value));
+        assert!(!proof.verify(&root, &absent[0]));
+    }
+
+    let mut edges = 0;
+    for value in &absent {
+        let proof = tree.contains_proof(value)?;
+        match &proof {
+            SetProof::Exclusion { left, right } => {
+                edges += usize::from(left.is_none() || right.is_none());
+            }
+            SetProof::Inclusion(_) => panic!("absent value {value} is included"),
+        }
+        assert!(proof.verify(&root, value));
+        // an exclusion proof does not hold for the values around it
+        for neighbour in &present {
+            assert!(!proof.verify(&root, neighbour));
+        }
+    }
+    assert!(edges < absent.len());
+
+    Ok(())
+}
+
+#[test]
+fn edges_of_the_set() -> eyre::Result<()> {
+    let mut tree = SortedMerkleTree::<u64>::new();
+    assert!(tree.root().is_none());
+    assert!(matches!(
+        tree.contains_proof(&1),
+        Err(MerkleError::EmptyTree)
+    ));
+
+    tree.insert(1);
+    let root = tree.root().ok_or_else(|| eyre::eyre!("no root"))?;
+    for value in 2..200 {
+        let proof = tree.contains_proof(&value)?;
+        assert!(proof.verify(&root, &value));
+    }
+
+    tree.insert(2);
+    assert_ne!(tree.root(), Some(root));
+
+    Ok(())
+}
+
+#[test]
+fn proofs_from_another_set_fail() -> eyre::Result<()> {
+    let values = random(5, 100);
+    let mut tree = SortedMerkleTree::new();
+    let mut forged = SortedMerkleTree::new();
+    for &value in &values {
+        tree.insert(value);
+        if value != values[42] {
+            forged.insert(value);
+        }
+    }
+    let root = tree.root().ok_or_else(|| eyre::eyre!("no root"))?;
+    let forged_root = forged.root().ok_or_else(|| eyre::eyre!("no root"))?;
+
+    // the forged set proves the value absent, but only against its own root
+    let proof = forged.contains_proof(&values[42])?;
+    assert!(matches!(proof, SetProof::Exclusion { .. }));
+    assert!(proof.verify(&forged_root, &values[42]));
+    assert!(!proof.verify(&root, &values[42]));
+
+    let proof = tree.contains_proof(&values[42])?;
+    assert!(proof.verify(&root, &values[42]));
+    assert!(!proof.verify(&forged_root, &values[42]));
+
+    Ok(())
+}
+
+#[test]
+fn inserts_rehash_like_a_rebuild() -> eyre::Result<()> {
+    let values = random(6, 300);
+    let mut tree = SortedMerkleTree::new();
+    for (count, &value) in values.iter().enumerate() {
+        // keep the levels cached so the insert updates them
+        tree.root();
+        tree.insert(value);
+
+        let mut rebuilt = SortedMerkleTree::new();
+        for &value in &values[..=count] {
+            rebuilt.insert(value);
+        }
+        assert_eq!(tree.root(), rebuilt.root(), "{count}");
+    }
+
+    let root = tree.root().ok_or_else(|| eyre::eyre!("no root"))?;
+    for value in &values {
+        assert!(tree.contains_proof(value)?.verify(&root, value));
+    }
+    Ok(())
+}
@@ -0,0 +1,136 @@
+#![cfg(feature = "keccak")]
+
+use merkle::{
+    hash::{Keccak256, MerkleHasher, SortedPairs},
+    verify::verify_sorted_pairs,
+    MerkleProof, MerkleTree, PaddingStrategy, Root,
+};
+
+/// a tree over `a` to `g` with keccak256 leafs and sorted pairs, the layout of
+/// `new MerkleTree(leaves.map(keccak256), keccak256, { sortPairs: true })` with
+/// its root and the `getHexProof` of every leaf
+const FIXTURE: &str = include_str!("fixtures/sorted_pairs.json");
+
+struct Fixture {
+    leaves: Vec<String>,
+    root: [u8; 32],
+    proofs: Vec<Vec<[u8; 32]>>,
+}
+
+/// the fixture only holds strings and arrays of them, quoted strings are read
+/// in order and arrays are told apart by their brackets
+fn fixture() -> eyre::Result<Fixture> {
+    let (head, proofs) = FIXTURE
+        .split_once("\"proofs\"")
+        .ok_or_else(|| eyre::eyre!("no proofs"))?;
+    let (leaves, root) = head
+        .split_once("\"root\"")
+        .ok_or_else(|| eyre::eyre!("no root"))?;
+
+    let strings =
+        |s: &str| -> Vec<String> { s.split('"').skip(1).step_by(2).map(str::to_owned).collect() };
+    let digest = |s: &str| -> eyre::Result<[u8; 32]> { Ok(s.parse::<Root<[u8; 32]>>()?.0) };
+
+    Ok(Fixture {
+        leaves: strings(leaves).into_iter().skip(1).collect(),
+        root: digest(&strings(root)[0])?,
+        proofs: proofs
+            .split(']')
+            .map(|proof| strings(proof).iter().map(|s| digest(s)).collect())
+            .collect::<eyre::Result<Vec<Vec<_>>>>()?
+            .into_iter()
+            .take_while(|proof: &Vec<_>| !proof.is_empty())
+            .collect(),
+    })
+}
+
+fn sorted_tree(leaves: &[String]) -> MerkleTree<&str, SortedPairs<Keccak256>> {
+    MerkleTree::with_hasher(
+        leaves.iter().map(String::as_str).collect(),
+        SortedPairs(Keccak256),
+    )
+    .with_padding(PaddingStrategy::PromoteOdd)
+}
+
+#[test]
+fn matches_merkletreejs() -> eyre::Result<()> {
+    let fixture = fixture()?;
+    assert_eq!(fixture.leaves.len(), 7);
+    assert_eq!(fixture.proofs.len(), 7);
+
+    let tree = sorted_tree(&fixture.leaves);
+    assert_eq!(tree.root(), Root(fixture.root));
+
+    for (index, expected) in fixture.proofs.iter().enumerate() {
+        let proof = tree.get_proof(index)?;
+        assert!(proof.verify());
+        assert_eq!(&proof.siblings(), expected);
+        assert!(verify_sorted_pairs(
+            &SortedPairs(Keccak256),
+            &proof.leaf,
+            expected,
+            &Root(fixture.root)
+        ));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn mixing_modes_fails() -> eyre::Result<()> {
+    let fixture = fixture()?;
+    let sorted = sorted_tree(&fixture.leaves);
+    let plain = MerkleTree::with_hasher(
+        fixture.leaves.iter().map(String::as_str).collect(),
+        Keccak256,
+    )
+    .with_padding(PaddingStrategy::PromoteOdd);
+    assert_ne!(sorted.root(), plain.root());
+
+    let mut rejected = 0;
+    for index in 0..fixture.leaves.len() {
+        // a sorted pair proof folded as a plain one and the other way round
+        let proof = sorted.get_proof(index)?;
+        let as_plain = MerkleProof {
+            path: proof.path,
+            root: proof.root,
+            leaf: proof.leaf,
+            index: proof.index,
+            width: proof.width,
+            padding: proof.padding,
+            hasher: Keccak256,
+        };
+
+        let proof = plain.get_proof(index)?;
+        let siblings: Vec<_> = proof.path.iter().map(|step| step.value).collect();
+        let as_sorted = verify_sorted_pairs(
+            &SortedPairs(Keccak256),
+            &proof.leaf,
+            &siblings,
+            &plain.root(),
+        );
+
+        assert!(!as_plain.verify());
+        if !as_sorted {
+            rejected += 1;
+        }
+    }
+    // a plain path whose pairs all happen to be in order folds the same way
+    assert!(rejected > 0);
+
+    Ok(())
+}
+
+#[test]
+fn siblings_are_hashed_in_order() {
+    let hasher = SortedPairs(Keccak256);
+    let (a, b) = ([1u8; 32], [2u8; 32]);
+    assert_eq!(
+        MerkleHasher::hash_siblings(&hasher, &a, &b),
+        MerkleHasher::hash_siblings(&hasher, &b, &a)
+    );
+    assert_eq!(
+        MerkleHasher::hash_siblings(&hasher, &b, &a),
+        MerkleHasher::hash_siblings(&Keccak256, &a, &b)
+    );
+}
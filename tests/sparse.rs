@@ -0,0 +1,98 @@
+use merkle::{MerkleError, SparseMerkleTree};
+
+/// a cheap deterministic stream of pseudo random keys
+fn keys(seed: u64, count: usize) -> Vec<u64> {
+    let mut state = seed;
+    (0..count)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        })
+        .collect()
+}
+
+#[test]
+fn membership_and_non_membership() -> eyre::Result<()> {
+    let present = keys(1, 2000);
+    let absent = keys(2, 200);
+
+    let mut tree = SparseMerkleTree::<u64, u64>::new();
+    for key in &present {
+        tree.insert(key, &(key / 2 + 1));
+    }
+
+    let root = tree.root();
+    for key in &present {
+        let proof = tree.get_proof(key)?;
+        assert_eq!(proof.root, tree.root());
+        assert!(proof.verify_membership(&root, key, &(key / 2 + 1)));
+        assert!(!proof.verify_membership(&root, key, &(key / 2 + 2)));
+        assert!(!proof.verify_non_membership::<u64, u64>(&root, key));
+        assert_eq!(
+            tree.get_non_membership_proof(key).unwrap_err(),
+            MerkleError::KeyExists
+        );
+    }
+
+    for key in &absent {
+        let proof = tree.get_non_membership_proof(key)?;
+        assert!(proof.verify_non_membership::<u64, u64>(&root, key));
+        assert!(!proof.verify_non_membership::<u64, u64>(&root, &present[0]));
+        assert_eq!(tree.get_proof(key).unwrap_err(), MerkleError::KeyNotFound);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn insert_invalidates_non_membership() -> eyre::Result<()> {
+    let mut tree = SparseMerkleTree::<u64, u64>::new();
+    let empty = tree.root();
+    tree.insert(&1, &10);
+    tree.insert(&2, &20);
+
+    let old = tree.root();
+    let stale = tree.get_non_membership_proof(&3)?;
+    assert!(stale.verify_non_membership::<u64, u64>(&old, &3));
+
+    tree.insert(&3, &30);
+    let root = tree.root();
+    assert!(stale.verify());
+    assert!(!stale.verify_non_membership::<u64, u64>(&root, &3));
+    let proof = tree.get_proof(&3)?;
+    assert!(proof.verify_membership(&root, &3u64, &30u64));
+    assert!(!proof.verify_membership(&old, &3u64, &30u64));
+
+    // the order of inserts does not matter, overwriting reuses the leaf
+    let mut other = SparseMerkleTree::<u64, u64>::new();
+    assert_eq!(other.root(), empty);
+    other.insert(&3, &0);
+    other.insert(&2, &20);
+    other.insert(&1, &10);
+    other.insert(&3, &30);
+    assert_eq!(other.root(), tree.root());
+
+    Ok(())
+}
+
+#[test]
+fn proofs_from_another_tree_fail() -> eyre::Result<()> {
+    let mut tree = SparseMerkleTree::<u64, u64>::new();
+    let mut forged = SparseMerkleTree::<u64, u64>::new();
+    for key in keys(3, 100) {
+        tree.insert(&key, &key);
+        forged.insert(&key, &key);
+    }
+    tree.insert(&7, &70);
+
+    // the forged tree proves the key absent, but only against its own root
+    let proof = forged.get_non_membership_proof(&7)?;
+    assert!(proof.verify());
+    assert!(proof.verify_non_membership::<u64, u64>(&forged.root(), &7));
+    assert!(!proof.verify_non_membership::<u64, u64>(&tree.root(), &7));
+    assert!(!proof.verify_against(&tree.root()));
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+#![cfg(feature = "async")]
+
+use std::{
+    future::Future,
+    io,
+    pin::{pin, Pin},
+    task::{Context, Poll, Waker},
+};
+
+use merkle::{
+    stream::{self, AsyncRead, Stream},
+    IncrementalMerkleTree, MerkleTree,
+};
+
+/// poll `future` to completion on this thread
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// hands out its values in chunks, pending in between like a socket
+struct Chunked<T> {
+    chunks: Vec<Vec<T>>,
+    ready: bool,
+}
+
+impl<T> Chunked<T> {
+    fn new(values: Vec<T>, size: usize) -> Self {
+        let mut chunks: Vec<Vec<T>> = Vec::new();
+        for value in values.into_iter().rev() {
+            match chunks.last_mut() {
+                Some(chunk) if chunk.len() < size => chunk.insert(0, value),
+                _ => chunks.push(vec![value]),
+            }
+        }
+        Self {
+            chunks,
+            ready: false,
+        }
+    }
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.ready {
+            return Poll::Ready(());
+        }
+        self.ready = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+impl<T: Unpin> Stream for Chunked<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        if self.poll_ready(cx).is_pending() {
+            return Poll::Pending;
+        }
+        let Some(chunk) = self.chunks.last_mut() else {
+            return Poll::Ready(None);
+        };
+        let value = chunk.remove(0);
+        if chunk.is_empty() {
+            self.chunks.pop();
+            self.ready = false;
+        }
+        Poll::Ready(Some(value))
+    }
+}
+
+impl AsyncRead for Chunked<u8> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.poll_ready(cx).is_pending() {
+            return Poll::Pending;
+        }
+        let Some(chunk) = self.chunks.last_mut() else {
+            return Poll::Ready(Ok(0));
+        };
+        let n = buf.len().min(chunk.len());
+        buf[..n].copy_from_slice(&chunk[..n]);
+        chunk.drain(..n);
+        if chunk.is_empty() {
+            self.chunks.pop();
+            self.ready = false;
+        }
+        Poll::Ready(Ok(n))
+    }
+}
+
+fn ok(values: Vec<u64>) -> Chunked<io::Result<u64>> {
+    Chunked::new(values.into_iter().map(Ok).collect(), 3)
+}
+
+#[test]
+fn matches_synchronous_construction() -> eyre::Result<()> {
+    for len in [0, 1, 5, 8, 100] {
+        let values: Vec<u64> = (0..len).collect();
+        let tree = MerkleTree::from(values.clone());
+
+        let hashed = block_on(MerkleTree::from_stream(ok(values.clone())))?;
+        assert_eq!(hashed.root(), tree.root());
+        let root = block_on(MerkleTree::root_of_stream(ok(values.clone())))?;
+        assert_eq!(root, tree.root());
+
+        let ready = stream::iter(values.into_iter().map(Ok::<_, io::Error>));
+        assert_eq!(block_on(MerkleTree::root_of_stream(ready))?, tree.root());
+    }
+    Ok(())
+}
+
+#[test]
+fn errors_keep_the_values_before_them() -> eyre::Result<()> {
+    let values = || {
+        let reset = io::Error::from(io::ErrorKind::ConnectionReset);
+        Chunked::new(vec![Ok(1), Ok(2), Ok(3), Err(reset), Ok(4)], 2)
+    };
+    let err = block_on(MerkleTree::<u64>::from_stream(values()))
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+
+    let mut tree = IncrementalMerkleTree::new();
+    let mut stream = values();
+    assert!(block_on(tree.add_stream(&mut stream)).is_err());
+    assert_eq!(tree.len(), 3);
+    block_on(tree.add_stream(&mut stream))?;
+    assert_eq!(tree.root(), MerkleTree::from(vec![1u64, 2, 3, 4]).root());
+    Ok(())
+}
+
+#[test]
+fn async_reader_matches_reader() -> eyre::Result<()> {
+    let bytes: Vec<u8> = (0..10_001).map(|i| (i % 253) as u8).collect();
+    let tree = MerkleTree::from_reader(&bytes[..], 1000)?;
+    let read = block_on(MerkleTree::from_async_reader(
+        Chunked::new(bytes.clone(), 333),
+        1000,
+    ))?;
+    assert_eq!(read.root(), tree.root());
+    assert_eq!(read.size(), 10_001);
+    assert_eq!(read.len(), 11);
+
+    let slice = block_on(MerkleTree::from_async_reader(&bytes[..], 1000))?;
+    assert_eq!(slice.root(), tree.root());
+    Ok(())
+}
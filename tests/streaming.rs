@@ -0,0 +1,101 @@
+//! backends built from a `StreamingDigest` through `Hashing`
+
+mod common;
+
+use merkle::{
+    hash::{Hashing, StreamingDigest},
+    LeafHash, MerkleProof, MerkleTree, Root,
+};
+
+/// every prefix split into pieces of `step` bytes hashes like the whole
+fn check_pieces<D: StreamingDigest>() {
+    let data: Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+    for len in [0, 1, 63, 64, 65, 127, 128, 129, 256, 1000] {
+        for step in [1, 7, 64, 128, 1000] {
+            let mut state = D::default();
+            data[..len]
+                .chunks(step)
+                .for_each(|piece| state.update(piece));
+            assert_eq!(state.finalize(), D::digest(&data[..len]), "{len} {step}");
+        }
+    }
+}
+
+/// roots of one and two leafs are plain digests, proofs of larger trees
+/// verify and survive every encoding
+fn check_tree<D: StreamingDigest>() -> eyre::Result<()> {
+    let tree = MerkleTree::with_hasher(vec![&b"a"[..]], Hashing::<D>::new());
+    assert_eq!(tree.root(), Root(D::digest(b"a")));
+
+    let tree = MerkleTree::with_hasher(vec![&b"a"[..], b"b"], Hashing::<D>::new());
+    let concatenated = [D::digest(b"a").as_ref(), D::digest(b"b").as_ref()].concat();
+    assert_eq!(tree.root(), Root(D::digest(&concatenated)));
+
+    let values: Vec<Vec<u8>> = (0..11u32).map(|i| i.to_be_bytes().to_vec()).collect();
+    let tree = MerkleTree::with_hasher(values.clone(), Hashing::<D>::new());
+    for (index, value) in values.iter().enumerate() {
+        let proof = tree.get_proof(index)?;
+        assert!(proof.verify_against(&tree.root()));
+        assert_eq!(proof.leaf, LeafHash(D::digest(value)));
+
+        assert_eq!(
+            MerkleProof::<Hashing<D>>::from_bytes(&proof.to_bytes())?,
+            proof
+        );
+        assert_eq!(
+            MerkleProof::<Hashing<D>>::from_json(&proof.to_json())?,
+            proof
+        );
+        assert_eq!(MerkleProof::<Hashing<D>>::decode(&proof.encode())?, proof);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn sha256_state() -> eyre::Result<()> {
+    use merkle::hash::{sha256, Sha256};
+
+    check_pieces::<sha256::State>();
+    check_tree::<sha256::State>()?;
+
+    // the same tree as the dedicated backend
+    let values: Vec<&str> = vec!["a", "b", "c", "d", "e"];
+    let generic = MerkleTree::with_hasher(values.clone(), Hashing::<sha256::State>::new());
+    let dedicated = MerkleTree::with_hasher(values, Sha256);
+    assert_eq!(generic.root(), dedicated.root());
+    assert_eq!(
+        generic.get_proof(3)?.to_bytes(),
+        dedicated.get_proof(3)?.to_bytes()
+    );
+    Ok(())
+}
+
+#[cfg(feature = "blake2b")]
+#[test]
+fn blake2b() -> eyre::Result<()> {
+    use common::hex;
+    use merkle::hash::{blake2b, Blake2b512};
+
+    assert_eq!(
+        hex(&blake2b::digest(b"")),
+        "786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419\
+         d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce"
+    );
+    // RFC 7693 appendix A
+    assert_eq!(
+        hex(&blake2b::digest(b"abc")),
+        "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+         7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+    );
+    assert_eq!(
+        hex(&blake2b::digest(
+            b"The quick brown fox jumps over the lazy dog"
+        )),
+        "a8add4bdddfd93e4877d2746e62817b116364a1fa7bc148d95090bc7333b3673\
+         f82401cf7aa2e4cb1ecd90296e3f14cb5413f8ed77be73045b13914cdcd6a918"
+    );
+
+    check_pieces::<Blake2b512>();
+    check_tree::<Blake2b512>()
+}
@@ -0,0 +1,106 @@
+use merkle::{
+    tree::MAX_LEAFS, verify::verify_subtree, MerkleError, MerkleTree, PaddingStrategy, Root,
+};
+
+#[test]
+fn subtree_roots_chain_to_the_root() -> eyre::Result<()> {
+    let values: Vec<u32> = (0..10_000).collect();
+    let tree = MerkleTree::from(values.clone());
+
+    // the leafs 4096..8192 are the second subtree of height 12
+    let proof = tree.get_subtree_proof_for(4096..8192)?;
+    assert_eq!((proof.index, proof.width), (1, 4));
+    let subtree_root = Root(proof.leaf.0);
+    assert!(verify_subtree(&tree.root(), &subtree_root, &proof));
+    assert!(!verify_subtree(&tree.root(), &tree.root(), &proof));
+
+    // the downstream party builds the subtree from its values alone
+    let subtree = MerkleTree::from(values[4096..8192].to_vec());
+    assert_eq!(subtree.root(), subtree_root);
+    let leaf = subtree.get_proof(1000)?;
+    assert!(leaf.verify_against(&subtree_root));
+
+    let chained = leaf.chain(proof)?;
+    assert_eq!((chained.index, chained.width), (5096, 16384));
+    assert!(chained.verify_against(&tree.root()));
+    assert_eq!(chained.to_bytes(), tree.get_proof(5096)?.to_bytes());
+    Ok(())
+}
+
+#[test]
+fn every_node_has_a_proof() -> eyre::Result<()> {
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        let tree = MerkleTree::from((0..13u32).collect::<Vec<_>>()).with_padding(padding);
+        let mut width = tree.padded_len();
+        for level in 0..=tree.height() {
+            for index in 0..width {
+                let proof = tree.get_subtree_proof(level, index)?;
+                assert!(verify_subtree(&tree.root(), &Root(proof.leaf.0), &proof));
+            }
+            width = width.div_ceil(2);
+        }
+        assert_eq!(
+            tree.get_subtree_proof(tree.height(), 0)?.leaf.0,
+            tree.root().0
+        );
+        let hashed = MerkleTree::from((0..13u32).collect::<Vec<_>>())
+            .with_padding(padding)
+            .finalize();
+        assert_eq!(
+            hashed.get_subtree_proof(2, 1)?.to_bytes(),
+            tree.get_subtree_proof(2, 1)?.to_bytes()
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn unaligned_subtrees() {
+    let tree = MerkleTree::from((0..100u32).collect::<Vec<_>>());
+    for range in [3..7, 4..7, 0..3, 5..5] {
+        assert_eq!(
+            tree.get_subtree_proof_for(range.clone()).err(),
+            Some(MerkleError::UnalignedSubtree {
+                start: range.start,
+                end: range.end
+            })
+        );
+    }
+    assert_eq!(
+        tree.get_subtree_proof_for(128..256).err(),
+        Some(MerkleError::IndexOutOfBounds {
+            index: 255,
+            len: 128
+        })
+    );
+    assert_eq!(
+        tree.get_subtree_proof(8, 0).err(),
+        Some(MerkleError::IndexOutOfBounds { index: 8, len: 8 })
+    );
+    assert!(tree.get_subtree_proof(7, 1).is_err());
+
+    let promoted = MerkleTree::from(vec![1u32, 2, 3]).with_padding(PaddingStrategy::PromoteOdd);
+    let leaf = promoted.get_proof(0).unwrap();
+    let upper = promoted.get_subtree_proof(1, 0).unwrap();
+    assert_eq!(
+        leaf.chain(upper).err(),
+        Some(MerkleError::UnsupportedPadding(PaddingStrategy::PromoteOdd))
+    );
+
+    // a subtree proof claiming a tree so wide the joined one has too many leafs
+    let tree = MerkleTree::from(vec![1u32, 2, 3, 4]);
+    let mut leaf = tree.get_proof(0).unwrap();
+    let mut upper = tree.get_subtree_proof(1, 0).unwrap();
+    (leaf.width, upper.width) = (1 << 40, 1 << 40);
+    assert_eq!(
+        leaf.chain(upper).err(),
+        Some(MerkleError::TooLarge {
+            len: usize::MAX,
+            max: MAX_LEAFS
+        })
+    );
+}
@@ -0,0 +1,74 @@
+use std::collections::HashSet;
+
+use merkle::{Direction, MerkleProof, MerkleTree, PaddingStrategy, Step};
+
+#[test]
+fn clones_are_independent() -> eyre::Result<()> {
+    let tree = MerkleTree::from((0..10u32).collect::<Vec<_>>());
+    let root = tree.root();
+
+    let mut fork = tree.clone();
+    assert!(fork.same_root(&tree));
+    fork.add(10);
+    fork.update(3, 30)?;
+    assert_ne!(fork.root(), root);
+    assert!(!fork.same_root(&tree));
+
+    assert_eq!(tree.root(), root);
+    assert_eq!(tree.values(), &(0..10).collect::<Vec<_>>()[..]);
+    Ok(())
+}
+
+#[test]
+fn trees_show_their_values() {
+    let tree = MerkleTree::from(vec![1u8, 2, 3]);
+    assert_eq!(
+        format!("{tree:?}"),
+        "MerkleTree { values: [1, 2, 3], padding: DefaultValue, .. }"
+    );
+}
+
+#[test]
+fn same_root_across_value_types() {
+    // signed and unsigned integers hash to the same bytes
+    let unsigned = MerkleTree::from(vec![1u64, 2, 3]);
+    let signed = MerkleTree::from(vec![1i64, 2, 3]);
+    assert!(unsigned.same_root(&signed));
+
+    let promoted = MerkleTree::from(vec![1u64, 2, 3]).with_padding(PaddingStrategy::PromoteOdd);
+    assert!(!unsigned.same_root(&promoted));
+}
+
+#[test]
+fn identical_proofs_dedupe() -> eyre::Result<()> {
+    let tree = MerkleTree::from((0..8u32).collect::<Vec<_>>());
+    let proofs: HashSet<MerkleProof> = [1, 5, 1, 1, 5, 6]
+        .into_iter()
+        .map(|index| tree.get_proof(index))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(proofs.len(), 3);
+
+    let proof = tree.get_proof(5)?;
+    assert!(proofs.contains(&proof));
+    assert_eq!(proof.clone(), proof);
+    assert_ne!(proof, tree.get_proof(6)?);
+    Ok(())
+}
+
+#[test]
+fn steps_compare_by_value() {
+    let step = Step {
+        direction: Direction::Left,
+        value: 7u64,
+    };
+    assert_eq!(step.clone(), step);
+    assert_ne!(
+        step,
+        Step {
+            direction: Direction::Right,
+            value: 7,
+        }
+    );
+    let directions: HashSet<Direction> = [Direction::Left, Direction::Left].into();
+    assert_eq!(directions.len(), 1);
+}
@@ -0,0 +1,328 @@
+use merkle::{
+    hash::{DefaultBuildHasher, LeafHasher, MerkleHasher},
+    Direction, LeafHash, MerkleError, MerkleProof, MerkleTree, PaddingStrategy, Root, Step,
+};
+
+#[test]
+fn basic_proof() -> eyre::Result<()> {
+    let values: Vec<u32> = (0..100_000).collect();
+    let mut tree = MerkleTree::from(values);
+    let proof = tree.get_proof(500)?;
+
+    assert!(proof.verify_against(&tree.root()));
+
+    tree.add(42);
+    assert!(!proof.verify_against(&tree.root()));
+
+    Ok(())
+}
+
+#[test]
+fn empty_tree() -> eyre::Result<()> {
+    let tree: MerkleTree<u32> = MerkleTree::from(vec![]);
+
+    // tree initiated with an empty list should have 1 leaf which is also the root
+    assert_eq!(tree.leafs().len(), 1);
+
+    // the leaf is padding, there is no value to prove
+    assert_eq!(
+        tree.get_proof(0).unwrap_err(),
+        MerkleError::IndexOutOfBounds { index: 0, len: 0 }
+    );
+    let proof = tree.get_padding_proof(0)?;
+    assert!(proof.verify_against(&tree.root()));
+
+    Ok(())
+}
+
+#[test]
+fn out_of_bounds() {
+    let tree: MerkleTree<u32> = MerkleTree::from(vec![1, 2]);
+    assert_eq!(tree.leafs().len(), 2);
+
+    let proof = tree.get_proof(2);
+    assert_eq!(
+        proof.unwrap_err(),
+        MerkleError::IndexOutOfBounds { index: 2, len: 2 }
+    );
+}
+
+#[test]
+fn contains() {
+    let mut tree: MerkleTree<u32> = MerkleTree::from(vec![1, 2]);
+
+    let elem = 3;
+    let hash = DefaultBuildHasher::default().hash_leaf(&elem);
+
+    assert!(!tree.contains(&hash));
+
+    tree.add(elem);
+    assert!(tree.contains(&hash));
+}
+
+#[test]
+fn leafs_and_nodes_are_domain_separated() {
+    let hasher = DefaultBuildHasher::default();
+    let tree: MerkleTree<u64> = MerkleTree::from(vec![1, 2, 3, 4]);
+    let leafs = tree.leafs();
+
+    let left = hasher.hash_siblings(&leafs[0], &leafs[1]);
+    let right = hasher.hash_siblings(&leafs[2], &leafs[3]);
+    assert_eq!(Root(hasher.hash_siblings(&left, &right)), tree.root());
+
+    // a leaf made of two digests must not hash like the inner node above them
+    let forged = (leafs[0], leafs[1]);
+    assert_ne!(hasher.hash_leaf(&forged), left);
+
+    // otherwise this proof of depth 1 would verify against a tree of depth 2
+    let proof = MerkleProof {
+        path: vec![Step {
+            direction: Direction::Right,
+            value: right,
+        }],
+        root: tree.root(),
+        leaf: LeafHash(hasher.hash_leaf(&forged)),
+        index: 0,
+        width: 2,
+        padding: PaddingStrategy::DefaultValue,
+        hasher,
+    };
+    assert!(!proof.verify());
+}
+
+#[test]
+fn update() -> eyre::Result<()> {
+    let values: Vec<u32> = (0..10_000).collect();
+    let mut tree = MerkleTree::from(values);
+
+    let old_root = tree.root();
+    let old_proof = tree.get_proof(1234)?;
+
+    tree.update(1234, 42)?;
+    assert_ne!(tree.root(), old_root);
+
+    let stale = MerkleProof {
+        root: tree.root(),
+        ..old_proof
+    };
+    assert!(!stale.verify());
+
+    let proof = tree.get_proof(1234)?;
+    assert!(proof.verify_against(&tree.root()));
+    assert_eq!(
+        proof.leaf.0,
+        DefaultBuildHasher::default().hash_leaf(&42u32)
+    );
+
+    // same as building from scratch
+    let mut values: Vec<u32> = (0..10_000).collect();
+    values[1234] = 42;
+    assert_eq!(tree.root(), MerkleTree::from(values).root());
+
+    Ok(())
+}
+
+#[test]
+fn update_out_of_bounds() {
+    let mut tree: MerkleTree<u32> = MerkleTree::from(vec![1, 2, 3]);
+
+    // index 3 is a padding leaf, not a stored value
+    assert_eq!(tree.leafs().len(), 4);
+    assert_eq!(
+        tree.update(3, 4),
+        Err(MerkleError::IndexOutOfBounds { index: 3, len: 3 })
+    );
+    assert!(tree.update(2, 4).is_ok());
+}
+
+#[test]
+fn collect_into_tree() {
+    let collected: MerkleTree<u32> = (0..1000).collect();
+    let values: Vec<u32> = (0..1000).collect();
+
+    assert_eq!(collected.root(), MerkleTree::from(values).root());
+}
+
+#[test]
+fn extend_tree() {
+    let mut tree: MerkleTree<u32> = (0..300).collect();
+    tree.root();
+    tree.extend(300..1000);
+
+    let values: Vec<u32> = (0..1000).collect();
+    assert_eq!(tree.root(), MerkleTree::from(values).root());
+}
+
+#[test]
+fn iterate_values_and_hashes() {
+    let tree: MerkleTree<u32> = (10..15).collect();
+    let root = tree.root();
+
+    assert_eq!(
+        tree.iter().copied().collect::<Vec<_>>(),
+        [10, 11, 12, 13, 14]
+    );
+    assert_eq!((&tree).into_iter().count(), 5);
+
+    let hasher = DefaultBuildHasher::default();
+    let hashes: Vec<u64> = tree.leaf_hashes().collect();
+    let expected: Vec<u64> = (10..15u32).map(|value| hasher.hash_leaf(&value)).collect();
+    assert_eq!(hashes, expected);
+
+    let padded: Vec<u64> = tree.padded_leaf_hashes().collect();
+    assert_eq!(padded.len(), 8);
+    assert_eq!(padded, tree.leafs());
+
+    assert_eq!(tree.root(), root);
+    assert_eq!(tree.into_iter().collect::<Vec<_>>(), [10, 11, 12, 13, 14]);
+}
+
+#[test]
+fn batch_proofs() -> eyre::Result<()> {
+    let tree: MerkleTree<u32> = (0..100_000).collect();
+    let indices: Vec<usize> = (0..500).map(|i| i * 7919 % 100_000).collect();
+
+    let proofs = tree.proofs_for(&indices)?;
+    assert_eq!(proofs.len(), 500);
+    assert!(proofs.iter().all(MerkleProof::verify));
+
+    let single = tree.get_proof(indices[42])?;
+    assert_eq!(proofs[42].leaf, single.leaf);
+    assert_eq!(proofs[42].root, single.root);
+    assert!(proofs[42]
+        .path
+        .iter()
+        .zip(&single.path)
+        .all(|(a, b)| a.direction == b.direction && a.value == b.value));
+
+    assert_eq!(
+        tree.proofs_for(&[1, 1 << 17, 2]).unwrap_err(),
+        MerkleError::IndexOutOfBounds {
+            index: 1 << 17,
+            len: 100_000
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn sizes_and_heights() -> eyre::Result<()> {
+    // len, padded_len and height for every padding strategy
+    let expected = [
+        (0, [(1, 0), (1, 0), (1, 0)]),
+        (1, [(1, 0), (1, 0), (1, 0)]),
+        (2, [(2, 1), (2, 1), (2, 1)]),
+        (3, [(4, 2), (3, 2), (3, 2)]),
+        (5, [(8, 3), (5, 3), (5, 3)]),
+        (1024, [(1024, 10), (1024, 10), (1024, 10)]),
+    ];
+    let paddings = [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ];
+
+    for (len, shapes) in expected {
+        for (padding, (padded_len, height)) in paddings.into_iter().zip(shapes) {
+            let tree = MerkleTree::from((0..len as u32).collect::<Vec<_>>()).with_padding(padding);
+            assert_eq!(tree.len(), len);
+            assert_eq!(tree.is_empty(), len == 0);
+            assert_eq!(tree.padded_len(), padded_len, "{len} values, {padding:?}");
+            assert_eq!(tree.height(), height, "{len} values, {padding:?}");
+
+            // the accessors agree with the tree that is actually built
+            assert_eq!(tree.leafs().len(), padded_len);
+            let longest = (0..padded_len)
+                .map(|index| {
+                    if index < len {
+                        tree.get_proof(index)
+                    } else {
+                        tree.get_padding_proof(index)
+                    }
+                })
+                .map(|proof| proof.map(|proof| proof.path.len()))
+                .collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(longest.into_iter().max(), Some(height));
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn stored_values() {
+    let tree = MerkleTree::from(vec![3u32, 1, 4]);
+    assert_eq!(tree.values(), [3, 1, 4]);
+    assert_eq!(tree.get(1), Some(&1));
+    assert_eq!(tree.get(3), None);
+
+    let empty = MerkleTree::<u32>::from(vec![]);
+    assert!(empty.values().is_empty());
+    assert_eq!(empty.get(0), None);
+}
+
+#[test]
+fn find_values() -> eyre::Result<()> {
+    let tree = MerkleTree::from(vec!["a", "b", "a", "c", "a"]);
+    assert_eq!(tree.position_of(&"a"), Some(0));
+    assert_eq!(tree.positions_of(&"a"), [0, 2, 4]);
+    assert_eq!(tree.position_of(&"c"), Some(3));
+    assert_eq!(tree.position_of(&"d"), None);
+    assert!(tree.positions_of(&"d").is_empty());
+    assert!(tree.contains_value(&"b"));
+    assert!(!tree.contains_value(&"d"));
+
+    let proof = tree.get_proof_by_value(&"c")?;
+    assert_eq!(proof.index, 3);
+    assert!(proof.verify());
+    assert_eq!(
+        tree.get_proof_by_value(&"d").unwrap_err(),
+        MerkleError::ValueNotFound
+    );
+
+    // padding leafs hash like default values but are not stored values
+    let tree = MerkleTree::from(vec![1u32, 2, 3]);
+    assert_eq!(tree.leafs().len(), 4);
+    assert!(!tree.contains_value(&0));
+    assert!(tree.contains(&tree.leafs()[3]));
+
+    let hashed = MerkleTree::from(vec!["a", "b", "a"]).finalize();
+    assert_eq!(hashed.position_of("b"), Some(1));
+    assert_eq!(hashed.positions_of("a"), [0, 2]);
+
+    Ok(())
+}
+
+#[test]
+fn values_are_found_by_hash() {
+    /// equal hashes for values that are not equal
+    #[derive(Debug, Default, PartialEq)]
+    struct Tagged {
+        id: u32,
+        note: &'static str,
+    }
+
+    impl std::hash::Hash for Tagged {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            self.id.hash(state);
+        }
+    }
+
+    let tree = MerkleTree::from(vec![
+        Tagged {
+            id: 1,
+            note: "first",
+        },
+        Tagged {
+            id: 2,
+            note: "second",
+        },
+    ]);
+    let lookalike = Tagged {
+        id: 2,
+        note: "other",
+    };
+    assert_ne!(tree.get(1), Some(&lookalike));
+    assert_eq!(tree.position_of(&lookalike), Some(1));
+}
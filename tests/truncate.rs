@@ -0,0 +1,79 @@
+use merkle::{MerkleTree, PaddingStrategy};
+
+const PADDINGS: [PaddingStrategy; 3] = [
+    PaddingStrategy::DefaultValue,
+    PaddingStrategy::DuplicateLast,
+    PaddingStrategy::PromoteOdd,
+];
+
+#[test]
+fn truncated_tree_matches_prefix() -> eyre::Result<()> {
+    let values: Vec<u32> = (0..1000).collect();
+    for padding in PADDINGS {
+        let mut tree = MerkleTree::from(values.clone()).with_padding(padding);
+        tree.root();
+        tree.truncate(700);
+
+        let fresh = MerkleTree::from(values[..700].to_vec()).with_padding(padding);
+        assert_eq!(tree.len(), 700);
+        assert_eq!(tree.padded_len(), fresh.padded_len());
+        assert_eq!(tree.root(), fresh.root());
+        for index in [0, 511, 512, 699] {
+            assert_eq!(
+                tree.get_proof(index)?.to_bytes(),
+                fresh.get_proof(index)?.to_bytes()
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn truncate_without_cached_levels() {
+    let mut tree = MerkleTree::from((0..100u32).collect::<Vec<_>>());
+    tree.truncate(33);
+    assert_eq!(
+        tree.root(),
+        MerkleTree::from((0..33u32).collect::<Vec<_>>()).root()
+    );
+}
+
+#[test]
+fn truncate_beyond_len_keeps_tree() {
+    let mut tree = MerkleTree::from((0..10u32).collect::<Vec<_>>());
+    let root = tree.root();
+    tree.truncate(10);
+    tree.truncate(20);
+    assert_eq!(tree.len(), 10);
+    assert_eq!(tree.root(), root);
+}
+
+#[test]
+fn popping_to_empty() {
+    for padding in PADDINGS {
+        let mut values: Vec<u32> = (0..20).collect();
+        let mut tree = MerkleTree::from(values.clone()).with_padding(padding);
+        tree.root();
+
+        while let Some(value) = tree.pop() {
+            assert_eq!(values.pop(), Some(value));
+            let fresh = MerkleTree::from(values.clone()).with_padding(padding);
+            assert_eq!(tree.root(), fresh.root());
+        }
+        assert!(tree.is_empty());
+        assert_eq!(
+            tree.root(),
+            MerkleTree::<u32>::from(vec![]).with_padding(padding).root()
+        );
+        assert_eq!(tree.pop(), None);
+    }
+}
+
+#[test]
+fn pop_then_add_again() {
+    let mut tree = MerkleTree::from((0..9u32).collect::<Vec<_>>());
+    let root = tree.root();
+    assert_eq!(tree.pop(), Some(8));
+    tree.add(8);
+    assert_eq!(tree.root(), root);
+}
@@ -0,0 +1,71 @@
+use merkle::{hash::LeafHasher, LeafHash, MerkleTree, PaddingStrategy, ProofError, Root};
+
+/// a fixed pseudo random sequence, the same on every run
+fn random(seed: u64) -> impl Iterator<Item = u64> {
+    let mut state = seed;
+    std::iter::repeat_with(move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        state >> 33
+    })
+}
+
+#[test]
+fn verifier_follows_updates_with_the_root_only() -> eyre::Result<()> {
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        let mut tree = MerkleTree::from((0..777).collect::<Vec<u64>>()).with_padding(padding);
+        let mut trusted = tree.root();
+
+        let mut random = random(7);
+        for _ in 0..100 {
+            let index = (random.next().unwrap() % 777) as usize;
+            let value = random.next().unwrap();
+            let update = tree.update_with_proof(index, value)?;
+
+            // the siblings are the same before and after the update, unless
+            // the last node of an odd level is paired with a copy of itself
+            let after = tree.get_proof(index)?;
+            assert_eq!(after.leaf, update.new_leaf);
+            if padding != PaddingStrategy::DuplicateLast {
+                assert_eq!(after.path, update.proof.path, "{padding:?}");
+            }
+            trusted = update.apply(&trusted)?;
+            assert_eq!(trusted, tree.root(), "{padding:?}");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn stale_or_forged_updates_are_rejected() -> eyre::Result<()> {
+    let mut tree = MerkleTree::from((0..10).collect::<Vec<u32>>());
+    let first = tree.root();
+    let update = tree.update_with_proof(3, 30)?;
+    assert_eq!(update.new_leaf, LeafHash(tree.hasher().hash_leaf(&30)));
+    assert_eq!(update.apply(&first)?, tree.root());
+
+    // the next update starts from the new root, not the old one
+    let second = tree.root();
+    let next = tree.update_with_proof(4, 40)?;
+    assert!(matches!(
+        next.apply(&first),
+        Err(ProofError::RootMismatch { .. })
+    ));
+    assert_eq!(next.apply(&second)?, tree.root());
+
+    // a forged root in the proof does not matter, a forged old leaf does
+    let mut forged = next.clone();
+    forged.proof.root = Root(0);
+    assert_eq!(forged.apply(&second)?, tree.root());
+    forged.proof.leaf = LeafHash(1);
+    assert!(forged.apply(&second).is_err());
+
+    assert!(tree.update_with_proof(10, 0).is_err());
+    assert_eq!(tree.len(), 10);
+    Ok(())
+}
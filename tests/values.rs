@@ -0,0 +1,151 @@
+use merkle::{
+    hash::{DefaultBuildHasher, LeafHasher},
+    LeafHash, MerkleTree,
+};
+
+#[derive(Hash, Default)]
+struct Account {
+    name: String,
+    balances: Vec<u64>,
+}
+
+#[test]
+fn string_leafs() -> eyre::Result<()> {
+    let values: Vec<String> = ["alice", "bob", "carol"].map(String::from).to_vec();
+    let mut tree = MerkleTree::from(values);
+
+    tree.add("dave".to_string());
+    tree.update(0, "erin".to_string())?;
+
+    let proof = tree.get_proof(3)?;
+    assert!(proof.verify());
+    assert_eq!(
+        proof.leaf.0,
+        DefaultBuildHasher::default().hash_leaf("dave")
+    );
+    assert_eq!(proof.root, tree.root());
+
+    let expected = ["erin", "bob", "carol", "dave"].map(String::from).to_vec();
+    assert_eq!(tree.root(), MerkleTree::from(expected).root());
+
+    Ok(())
+}
+
+#[test]
+fn struct_leafs() -> eyre::Result<()> {
+    let accounts = (0..10)
+        .map(|i| Account {
+            name: format!("account {i}"),
+            balances: vec![i; i as usize],
+        })
+        .collect();
+    let mut tree: MerkleTree<Account> = MerkleTree::with_hasher(accounts, Default::default());
+
+    tree.add(Account {
+        name: "late".into(),
+        balances: vec![1, 2, 3],
+    });
+
+    for index in [0, 5, 10] {
+        let proof = tree.get_proof(index)?;
+        assert!(proof.verify_against(&tree.root()));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn owned_byte_leafs() -> eyre::Result<()> {
+    use merkle::hash::{sha256, Sha256};
+
+    let values: Vec<Vec<u8>> = vec![b"alice".to_vec(), b"bob".to_vec(), b"carol".to_vec()];
+    let tree = MerkleTree::with_hasher(values, Sha256);
+
+    let proof = tree.get_proof(1)?;
+    assert_eq!(proof.leaf, LeafHash(sha256::digest(b"bob")));
+    assert!(proof.verify());
+
+    Ok(())
+}
+
+/// a value that must never be cloned by the tree
+#[derive(Hash, Default)]
+struct NoClone(u64);
+
+impl Clone for NoClone {
+    fn clone(&self) -> Self {
+        panic!("values must not be cloned");
+    }
+}
+
+#[test]
+fn values_are_not_cloned() -> eyre::Result<()> {
+    let values: Vec<NoClone> = (0..1000).map(NoClone).collect();
+    let mut tree = MerkleTree::from(values);
+
+    tree.root();
+    let proof = tree.get_proof(999)?;
+    assert!(proof.verify());
+    tree.get_multi_proof(&[1, 2, 3])?;
+
+    tree.add(NoClone(1000));
+    tree.update(5, NoClone(0))?;
+    tree.root();
+    tree.get_padding_proof(1023)?;
+
+    Ok(())
+}
+
+#[test]
+fn values_by_index() {
+    let tree = MerkleTree::from(vec![10u32, 20, 30]);
+    assert_eq!((tree[0], tree[2]), (10, 30));
+    assert_eq!(tree.get(1), Some(&20));
+    assert_eq!(tree.get(3), None);
+}
+
+#[test]
+#[should_panic]
+fn padding_is_not_indexed() {
+    let tree = MerkleTree::from(vec![10u32, 20, 30]);
+    assert_eq!(tree.padded_len(), 4);
+    let _ = tree[3];
+}
+
+#[test]
+fn changes_through_get_mut_rehash_on_drop() -> eyre::Result<()> {
+    let account = |name: &str, balance| Account {
+        name: name.to_string(),
+        balances: vec![balance],
+    };
+    let mut tree = MerkleTree::from(vec![account("alice", 1), account("bob", 2)]);
+    let before = tree.root();
+    let roots = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sink = roots.clone();
+    tree.on_root_change(move |root, _| sink.lock().unwrap().push(root));
+
+    // reading through the guard changes nothing
+    assert_eq!(tree.get_mut(1).map(|bob| bob.balances[0]), Some(2));
+    assert_eq!(tree.root(), before);
+    assert!(roots.lock().unwrap().is_empty());
+
+    {
+        let mut bob = tree.get_mut(1).unwrap();
+        assert_eq!(bob.index(), 1);
+        bob.balances.push(3);
+        bob.name.push('!');
+    }
+    let fresh = MerkleTree::from(vec![
+        account("alice", 1),
+        Account {
+            name: "bob!".to_string(),
+            balances: vec![2, 3],
+        },
+    ]);
+    assert_eq!(tree.root(), fresh.root());
+    assert_eq!(*roots.lock().unwrap(), [fresh.root()]);
+    assert!(tree.get_proof(1)?.verify_against(&fresh.root()));
+    assert!(tree.get_mut(2).is_none());
+    Ok(())
+}
@@ -0,0 +1,320 @@
+use merkle::{
+    hash::{DefaultBuildHasher, LeafHasher},
+    verify::verify_path,
+    Direction, LeafHash, MerkleProof, MerkleTree, PaddingStrategy, ProofError, Root,
+};
+
+/// build a proof on the proving side, the verifying side only sees the proof
+fn prove(index: usize) -> eyre::Result<MerkleProof> {
+    let tree: MerkleTree<u32> = (0..100).collect();
+    Ok(tree.get_proof(index)?)
+}
+
+#[test]
+fn verify_without_tree() -> eyre::Result<()> {
+    let proof = prove(42)?;
+    assert!(proof.verify());
+    assert!(proof.verify_against(&proof.root));
+    assert!(!proof.verify_against(&Root(proof.root.0 ^ 1)));
+
+    Ok(())
+}
+
+#[test]
+fn forged_root_is_ignored() -> eyre::Result<()> {
+    let trusted: MerkleTree<u32> = (0..100).collect();
+    let root = trusted.root();
+
+    // a prover with another tree builds a consistent proof for a value the
+    // trusted tree does not hold
+    let forged = MerkleTree::from((100..200u32).collect::<Vec<_>>()).get_proof(42)?;
+    assert!(forged.verify());
+    assert!(!forged.verify_against(&root));
+
+    // naming the trusted root in the proof does not help
+    let relabeled = MerkleProof { root, ..forged };
+    assert!(!relabeled.verify());
+    assert!(!relabeled.verify_against(&root));
+
+    Ok(())
+}
+
+#[test]
+fn verify_hashes_only() -> eyre::Result<()> {
+    let proof = prove(7)?;
+    let hasher = DefaultBuildHasher::default();
+    let leaf = LeafHash(hasher.hash_leaf(&7u32));
+
+    assert!(verify_path(&hasher, &leaf, &proof.path, &proof.root));
+    assert!(!verify_path(&hasher, &leaf, &proof.path[1..], &proof.root));
+    assert!(!verify_path(
+        &hasher,
+        &LeafHash(hasher.hash_leaf(&8u32)),
+        &proof.path,
+        &proof.root
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_shim() -> eyre::Result<()> {
+    let proof = prove(3)?;
+    assert!(MerkleTree::<u32>::verify_proof(&proof));
+
+    Ok(())
+}
+
+#[test]
+fn index_is_bound() -> eyre::Result<()> {
+    // equal values at several positions hash to equal leafs
+    let tree = MerkleTree::from(vec![7u32; 8]);
+    let mut proof = tree.get_proof(5)?;
+    assert_eq!(proof.index, 5);
+    assert!(proof.verify());
+
+    for index in [0, 1, 2, 3, 4, 6, 7, 13, usize::MAX] {
+        proof.index = index;
+        assert!(!proof.verify(), "proof for 5 verified as {index}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn promoted_paths_match_their_index() {
+    for len in 1..=40 {
+        let values: Vec<u32> = (0..len).collect();
+        let tree = MerkleTree::from(values).with_padding(PaddingStrategy::PromoteOdd);
+        let proofs = tree
+            .proofs_for(&(0..len as usize).collect::<Vec<_>>())
+            .unwrap();
+        for (index, mut proof) in proofs.into_iter().enumerate() {
+            assert!(proof.verify());
+            proof.index = index ^ 1;
+            assert!(!proof.verify());
+        }
+    }
+}
+
+#[test]
+fn path_length_is_bound() -> eyre::Result<()> {
+    let mut proof = prove(42)?;
+    assert_eq!(proof.width, 128);
+    assert!(proof.verify_with_len(128));
+    assert!(!proof.verify_with_len(1 << 20));
+
+    proof.path.pop();
+    assert!(!proof.verify());
+    assert_eq!(
+        proof.verify_detailed(),
+        Err(ProofError::PathLength {
+            expected: 7,
+            actual: 6
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn degenerate_proof_is_rejected() {
+    let hasher = DefaultBuildHasher::default();
+    let leaf = hasher.hash_leaf(&1u32);
+    let proof = MerkleProof {
+        path: Vec::new(),
+        root: Root(leaf),
+        leaf: LeafHash(leaf),
+        index: 0,
+        width: 1,
+        padding: PaddingStrategy::DefaultValue,
+        hasher,
+    };
+
+    // a tree of a single leaf has its leaf as root
+    assert!(proof.verify());
+    assert!(!proof.verify_with_len(1 << 20));
+
+    // claiming a bigger tree needs a longer path
+    let claimed = MerkleProof {
+        width: 1 << 20,
+        ..proof
+    };
+    assert!(!claimed.verify());
+    assert_eq!(
+        claimed.verify_detailed(),
+        Err(ProofError::PathLength {
+            expected: 20,
+            actual: 0
+        })
+    );
+}
+
+#[test]
+fn failures_are_reported() -> eyre::Result<()> {
+    let proof = prove(42)?;
+    assert_eq!(proof.verify_detailed(), Ok(()));
+
+    let root = MerkleProof {
+        root: Root(proof.root.0 ^ 1),
+        ..prove(42)?
+    };
+    assert_eq!(
+        root.verify_detailed(),
+        Err(ProofError::RootMismatch {
+            computed: proof.root.0,
+            expected: proof.root.0 ^ 1
+        })
+    );
+
+    let mut leaf = prove(42)?;
+    leaf.leaf.0 ^= 1;
+    assert!(matches!(
+        leaf.verify_detailed(),
+        Err(ProofError::RootMismatch { expected, .. }) if expected == proof.root.0
+    ));
+
+    let mut sibling = prove(42)?;
+    sibling.path[3].value ^= 1;
+    assert!(matches!(
+        sibling.verify_detailed(),
+        Err(ProofError::RootMismatch { .. })
+    ));
+
+    let mut direction = prove(42)?;
+    direction.path[2].direction = Direction::Left;
+    assert_eq!(
+        direction.verify_detailed(),
+        Err(ProofError::DirectionMismatch { step: 2 })
+    );
+
+    let mut index = prove(42)?;
+    index.index = 43;
+    assert_eq!(
+        index.verify_detailed(),
+        Err(ProofError::DirectionMismatch { step: 0 })
+    );
+    index.index = 128;
+    assert_eq!(
+        index.verify_detailed(),
+        Err(ProofError::IndexOutOfBounds {
+            index: 128,
+            width: 128
+        })
+    );
+
+    let mut width = prove(42)?;
+    width.width = 64;
+    assert_eq!(
+        width.verify_detailed(),
+        Err(ProofError::PathLength {
+            expected: 6,
+            actual: 7
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn verify_against_recent_roots() -> eyre::Result<()> {
+    let mut tree: MerkleTree<u32> = (0..10).collect();
+    let mut roots = Vec::new();
+    let mut proofs = Vec::new();
+    for size in [10, 11, 16, 17, 50, 64, 100] {
+        while tree.len() < size {
+            tree.add(tree.len() as u32);
+        }
+        roots.push(tree.root());
+        proofs.push(tree.get_proof(3)?);
+    }
+
+    for (position, proof) in proofs.iter().enumerate() {
+        assert_eq!(proof.verify_against_any(&roots), Some(position));
+        assert_eq!(proof.verify_against_any(&roots[position + 1..]), None);
+    }
+
+    let mut tampered = proofs[2].clone();
+    tampered.path[0].value ^= 1;
+    assert_eq!(tampered.verify_against_any(&roots), None);
+    assert_eq!(proofs[0].verify_against_any(&[]), None);
+
+    // the first of equal roots is reported
+    let repeated = [roots[1], roots[0], roots[0]];
+    assert_eq!(proofs[0].verify_against_any(&repeated), Some(1));
+
+    Ok(())
+}
+
+#[test]
+fn directions_imply_the_index() -> eyre::Result<()> {
+    let tree: MerkleTree<u32> = (0..64).collect();
+    for index in 0..64 {
+        let proof = tree.get_proof(index)?;
+        assert_eq!(proof.implied_index(), Ok(index));
+
+        for level in 0..proof.path.len() {
+            let mut flipped = proof.clone();
+            flipped.path[level].direction = match flipped.path[level].direction {
+                Direction::Left => Direction::Right,
+                Direction::Right => Direction::Left,
+            };
+            assert_eq!(flipped.implied_index(), Ok(index ^ 1 << level));
+            assert!(!flipped.verify_against(&tree.root()));
+            assert_eq!(
+                flipped.verify_detailed(),
+                Err(ProofError::DirectionMismatch { step: level })
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn promoted_directions_imply_the_index() -> eyre::Result<()> {
+    for padding in [
+        PaddingStrategy::DefaultValue,
+        PaddingStrategy::DuplicateLast,
+        PaddingStrategy::PromoteOdd,
+    ] {
+        for len in 1..=40u32 {
+            let tree = MerkleTree::from((0..len).collect::<Vec<_>>()).with_padding(padding);
+            for index in 0..len as usize {
+                let proof = tree.get_proof(index)?;
+                assert_eq!(proof.implied_index(), Ok(index), "{padding:?} {len}");
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn implied_index_needs_a_whole_path() -> eyre::Result<()> {
+    let mut proof = prove(42)?;
+    proof.path.pop();
+    assert_eq!(
+        proof.implied_index(),
+        Err(ProofError::PathLength {
+            expected: 7,
+            actual: 6
+        })
+    );
+
+    let mut proof = prove(42)?;
+    proof.width = 64;
+    assert_eq!(
+        proof.implied_index(),
+        Err(ProofError::PathLength {
+            expected: 6,
+            actual: 7
+        })
+    );
+
+    proof.width = 0;
+    assert!(matches!(
+        proof.implied_index(),
+        Err(ProofError::IndexOutOfBounds { width: 0, .. })
+    ));
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use merkle::{MerkleError, MerkleTree, PaddingStrategy};
+
+#[test]
+fn historical_roots_and_proofs() -> eyre::Result<()> {
+    for padding in [PaddingStrategy::DefaultValue, PaddingStrategy::PromoteOdd] {
+        let mut tree = MerkleTree::from(Vec::<u32>::new()).with_padding(padding);
+        let mut roots = vec![tree.root()];
+        for value in 0..1000 {
+            tree.add(value);
+            roots.push(tree.root());
+        }
+        assert_eq!(tree.version(), 1000);
+
+        for (version, root) in roots.iter().enumerate() {
+            assert_eq!(tree.root_at(version)?, *root);
+        }
+
+        for version in (1..=1000).step_by(37) {
+            for index in (0..version).step_by(11) {
+                let proof = tree.get_proof_at(index, version)?;
+                assert_eq!(proof.root, roots[version]);
+                assert!(proof.verify());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn future_versions_and_indices() {
+    let tree: MerkleTree<u32> = (0..10).collect();
+
+    assert_eq!(
+        tree.root_at(11),
+        Err(MerkleError::UnknownVersion {
+            version: 11,
+            latest: 10
+        })
+    );
+    assert_eq!(
+        tree.get_proof_at(5, 5).unwrap_err(),
+        MerkleError::IndexOutOfBounds { index: 5, len: 5 }
+    );
+}
+
+#[test]
+fn rewritten_versions_are_stale() -> eyre::Result<()> {
+    let mut tree = MerkleTree::from(Vec::<u32>::new());
+    let mut roots = vec![tree.root()];
+    for value in 0..20 {
+        tree.add(value);
+        roots.push(tree.root());
+    }
+
+    // changing the value at 5 rewrites every version holding it
+    tree.update(5, 55)?;
+    for (version, root) in roots.iter().enumerate().take(6) {
+        assert_eq!(tree.root_at(version)?, *root);
+    }
+    for version in 6..=20 {
+        assert_eq!(
+            tree.root_at(version),
+            Err(MerkleError::StaleVersion { version })
+        );
+        assert!(tree.get_proof_at(0, version).is_err());
+    }
+    let proof = tree.get_proof_at(4, 5)?;
+    assert!(proof.verify_against(&roots[5]));
+
+    // versions reached after the change have the roots they were reached with
+    tree.add(20);
+    assert_eq!(tree.root_at(21)?, tree.root());
+    tree.truncate(3);
+    assert_eq!(tree.root_at(3)?, roots[3]);
+    tree.add(30);
+    assert_eq!(tree.root_at(4)?, tree.root());
+    assert_ne!(tree.root_at(4)?, roots[4]);
+
+    // removing a value shifts the ones after it
+    tree.add_all(31..40);
+    let before = tree.root_at(8)?;
+    tree.remove(1)?;
+    assert_eq!(tree.root_at(1)?, roots[1]);
+    assert_eq!(
+        tree.root_at(8),
+        Err(MerkleError::StaleVersion { version: 8 })
+    );
+    assert_ne!(tree.root(), before);
+
+    Ok(())
+}
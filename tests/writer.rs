@@ -0,0 +1,108 @@
+use std::io::{self, Write};
+
+use merkle::{MerkleTree, MerkleWriter};
+
+/// a fixed pseudo random sequence, the same on every run
+fn random(seed: u64) -> impl Iterator<Item = u64> {
+    let mut state = seed;
+    std::iter::repeat_with(move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        state >> 33
+    })
+}
+
+/// write `data` in pieces of random sizes, empty ones included
+fn write_randomly(writer: &mut impl Write, data: &[u8], seed: u64) -> io::Result<()> {
+    let mut sizes = random(seed);
+    let mut rest = data;
+    while !rest.is_empty() {
+        let size = (sizes.next().unwrap() % 10_000) as usize;
+        let size = if size < 100 { 0 } else { size.min(rest.len()) };
+        writer.write_all(&rest[..size])?;
+        rest = &rest[size..];
+    }
+    Ok(())
+}
+
+#[test]
+fn root_equals_reading() -> eyre::Result<()> {
+    let data: Vec<u8> = random(1).take(3 << 20 | 12_345).map(|x| x as u8).collect();
+    for (chunk_size, len) in [
+        (4096, data.len()),
+        (1000, data.len()),
+        (1 << 20, 3 << 20),
+        (7, 100_000),
+    ] {
+        let mut writer = MerkleWriter::new(Vec::new(), chunk_size)?;
+        write_randomly(&mut writer, &data[..len], chunk_size as u64)?;
+        assert_eq!(writer.size(), len as u64);
+        let (written, root, tree) = writer.finish()?;
+
+        assert_eq!(written, &data[..len]);
+        assert!(tree.is_none());
+        let read = MerkleTree::from_reader(&data[..len], chunk_size)?;
+        assert_eq!(root, read.root(), "{chunk_size} {len}");
+    }
+    Ok(())
+}
+
+#[test]
+fn kept_leafs_give_the_tree() -> eyre::Result<()> {
+    let data: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+    let mut writer = MerkleWriter::new(io::sink(), 4096)?.keep_leafs();
+    write_randomly(&mut writer, &data, 9)?;
+    let (_, root, tree) = writer.finish()?;
+
+    let tree = tree.unwrap();
+    let read = MerkleTree::from_reader(&data[..], 4096)?;
+    assert_eq!(tree.root(), root);
+    assert_eq!(tree.len(), read.len());
+    assert_eq!(tree.size(), read.size());
+    assert_eq!(tree.get_proof(7)?, read.get_proof(7)?);
+    Ok(())
+}
+
+#[test]
+fn nothing_written() -> eyre::Result<()> {
+    let mut writer = MerkleWriter::new(Vec::new(), 16)?;
+    writer.write_all(&[])?;
+    let (written, root, _) = writer.finish()?;
+    assert!(written.is_empty());
+    assert_eq!(root, MerkleTree::from_reader(io::empty(), 16)?.root());
+
+    assert_eq!(
+        MerkleWriter::new(Vec::new(), 0).err().map(|err| err.kind()),
+        Some(io::ErrorKind::InvalidInput)
+    );
+    Ok(())
+}
+
+/// accepts at most three bytes per write
+struct Trickle(Vec<u8>);
+
+impl Write for Trickle {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let take = buf.len().min(3);
+        self.0.extend_from_slice(&buf[..take]);
+        Ok(take)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn short_writes_hash_what_was_written() -> eyre::Result<()> {
+    let data: Vec<u8> = (0..1000u32).map(|i| i as u8).collect();
+    let mut writer = MerkleWriter::new(Trickle(Vec::new()), 64)?;
+    assert_eq!(writer.write(&data)?, 3);
+    writer.write_all(&data[3..])?;
+    let (Trickle(written), root, _) = writer.finish()?;
+
+    assert_eq!(written, data);
+    assert_eq!(root, MerkleTree::from_reader(&data[..], 64)?.root());
+    Ok(())
+}